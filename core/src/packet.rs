@@ -98,6 +98,16 @@ impl<'p> PacketData<'p> {
         }
     }
 
+    /// Get a slice of the packet data which lives as long as the underlying buffer.
+    ///
+    /// Returns `None` if the packet owns its contents.
+    pub fn as_borrowed(&self) -> Option<&'p [u8]> {
+        match *self {
+            PacketData::Owned(_) => None,
+            PacketData::Borrowed(data) => Some(data),
+        }
+    }
+
     /// No-op - returns `self`.
     pub fn to_immutable(self) -> PacketData<'p> {
         self
@@ -193,19 +203,8 @@ impl PrimitiveValues for Ipv4Addr {
 }
 
 impl PrimitiveValues for Ipv6Addr {
-    type T = (u16, u16, u16, u16, u16, u16, u16, u16);
-    fn to_primitive_values(&self) -> (u16, u16, u16, u16, u16, u16, u16, u16) {
-        let segments = self.segments();
-
-        (
-            segments[0],
-            segments[1],
-            segments[2],
-            segments[3],
-            segments[4],
-            segments[5],
-            segments[6],
-            segments[7],
-        )
+    type T = (u128,);
+    fn to_primitive_values(&self) -> (u128,) {
+        (u128::from(*self),)
     }
 }
@@ -8,15 +8,18 @@
 
 //! Provides type aliases for various primitive integer types
 //!
-//! These types are aliased to the next largest of [`u8`, `u16`, `u32`, `u64`], and purely serve as
-//! hints for the `#[packet]` macro to enable the generation of the correct bit manipulations to
-//! get the value out of a packet.
+//! These types are aliased to the next largest of [`u8`, `u16`, `u32`, `u64`, `u128`], and purely
+//! serve as hints for the `#[packet]` macro to enable the generation of the correct bit
+//! manipulations to get the value out of a packet.
 //!
 //! They should NOT be used outside of data types marked as `#[packet]`.
 //!
 //! All aliases for types larger than `u8` contain a `be` or `le` suffix. These specify whether the
 //! value is big or little endian, respectively. When using `set_*()` and `get_*()` methods, host
 //! endianness should be used - the methods will convert as appropriate.
+//!
+//! The `cstring` and `pstring` aliases similarly describe how a string is delimited on the wire.
+//! Their accessors return a `Result<&str, Utf8Error>` and their mutators take a `&str`.
 
 #![allow(non_camel_case_types)]
 
@@ -267,6 +270,262 @@ pub type u63be = u64;
 /// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
 pub type u64be = u64;
 
+/// Represents an unsigned 65-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u65be = u128;
+
+/// Represents an unsigned 66-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u66be = u128;
+
+/// Represents an unsigned 67-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u67be = u128;
+
+/// Represents an unsigned 68-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u68be = u128;
+
+/// Represents an unsigned 69-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u69be = u128;
+
+/// Represents an unsigned 70-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u70be = u128;
+
+/// Represents an unsigned 71-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u71be = u128;
+
+/// Represents an unsigned 72-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u72be = u128;
+
+/// Represents an unsigned 73-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u73be = u128;
+
+/// Represents an unsigned 74-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u74be = u128;
+
+/// Represents an unsigned 75-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u75be = u128;
+
+/// Represents an unsigned 76-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u76be = u128;
+
+/// Represents an unsigned 77-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u77be = u128;
+
+/// Represents an unsigned 78-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u78be = u128;
+
+/// Represents an unsigned 79-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u79be = u128;
+
+/// Represents an unsigned 80-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u80be = u128;
+
+/// Represents an unsigned 81-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u81be = u128;
+
+/// Represents an unsigned 82-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u82be = u128;
+
+/// Represents an unsigned 83-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u83be = u128;
+
+/// Represents an unsigned 84-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u84be = u128;
+
+/// Represents an unsigned 85-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u85be = u128;
+
+/// Represents an unsigned 86-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u86be = u128;
+
+/// Represents an unsigned 87-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u87be = u128;
+
+/// Represents an unsigned 88-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u88be = u128;
+
+/// Represents an unsigned 89-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u89be = u128;
+
+/// Represents an unsigned 90-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u90be = u128;
+
+/// Represents an unsigned 91-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u91be = u128;
+
+/// Represents an unsigned 92-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u92be = u128;
+
+/// Represents an unsigned 93-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u93be = u128;
+
+/// Represents an unsigned 94-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u94be = u128;
+
+/// Represents an unsigned 95-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u95be = u128;
+
+/// Represents an unsigned 96-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u96be = u128;
+
+/// Represents an unsigned 97-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u97be = u128;
+
+/// Represents an unsigned 98-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u98be = u128;
+
+/// Represents an unsigned 99-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u99be = u128;
+
+/// Represents an unsigned 100-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u100be = u128;
+
+/// Represents an unsigned 101-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u101be = u128;
+
+/// Represents an unsigned 102-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u102be = u128;
+
+/// Represents an unsigned 103-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u103be = u128;
+
+/// Represents an unsigned 104-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u104be = u128;
+
+/// Represents an unsigned 105-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u105be = u128;
+
+/// Represents an unsigned 106-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u106be = u128;
+
+/// Represents an unsigned 107-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u107be = u128;
+
+/// Represents an unsigned 108-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u108be = u128;
+
+/// Represents an unsigned 109-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u109be = u128;
+
+/// Represents an unsigned 110-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u110be = u128;
+
+/// Represents an unsigned 111-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u111be = u128;
+
+/// Represents an unsigned 112-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u112be = u128;
+
+/// Represents an unsigned 113-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u113be = u128;
+
+/// Represents an unsigned 114-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u114be = u128;
+
+/// Represents an unsigned 115-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u115be = u128;
+
+/// Represents an unsigned 116-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u116be = u128;
+
+/// Represents an unsigned 117-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u117be = u128;
+
+/// Represents an unsigned 118-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u118be = u128;
+
+/// Represents an unsigned 119-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u119be = u128;
+
+/// Represents an unsigned 120-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u120be = u128;
+
+/// Represents an unsigned 121-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u121be = u128;
+
+/// Represents an unsigned 122-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u122be = u128;
+
+/// Represents an unsigned 123-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u123be = u128;
+
+/// Represents an unsigned 124-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u124be = u128;
+
+/// Represents an unsigned 125-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u125be = u128;
+
+/// Represents an unsigned 126-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u126be = u128;
+
+/// Represents an unsigned 127-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u127be = u128;
+
+/// Represents an unsigned 128-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as big-endian, but accessors/mutators will return/take host-order values.
+pub type u128be = u128;
+
 /// Represents an unsigned 9-bit integer. libpnet #[packet]-derived structs using this type will
 /// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
 pub type u9le = u16;
@@ -491,6 +750,262 @@ pub type u63le = u64;
 /// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
 pub type u64le = u64;
 
+/// Represents an unsigned 65-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u65le = u128;
+
+/// Represents an unsigned 66-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u66le = u128;
+
+/// Represents an unsigned 67-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u67le = u128;
+
+/// Represents an unsigned 68-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u68le = u128;
+
+/// Represents an unsigned 69-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u69le = u128;
+
+/// Represents an unsigned 70-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u70le = u128;
+
+/// Represents an unsigned 71-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u71le = u128;
+
+/// Represents an unsigned 72-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u72le = u128;
+
+/// Represents an unsigned 73-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u73le = u128;
+
+/// Represents an unsigned 74-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u74le = u128;
+
+/// Represents an unsigned 75-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u75le = u128;
+
+/// Represents an unsigned 76-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u76le = u128;
+
+/// Represents an unsigned 77-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u77le = u128;
+
+/// Represents an unsigned 78-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u78le = u128;
+
+/// Represents an unsigned 79-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u79le = u128;
+
+/// Represents an unsigned 80-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u80le = u128;
+
+/// Represents an unsigned 81-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u81le = u128;
+
+/// Represents an unsigned 82-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u82le = u128;
+
+/// Represents an unsigned 83-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u83le = u128;
+
+/// Represents an unsigned 84-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u84le = u128;
+
+/// Represents an unsigned 85-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u85le = u128;
+
+/// Represents an unsigned 86-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u86le = u128;
+
+/// Represents an unsigned 87-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u87le = u128;
+
+/// Represents an unsigned 88-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u88le = u128;
+
+/// Represents an unsigned 89-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u89le = u128;
+
+/// Represents an unsigned 90-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u90le = u128;
+
+/// Represents an unsigned 91-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u91le = u128;
+
+/// Represents an unsigned 92-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u92le = u128;
+
+/// Represents an unsigned 93-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u93le = u128;
+
+/// Represents an unsigned 94-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u94le = u128;
+
+/// Represents an unsigned 95-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u95le = u128;
+
+/// Represents an unsigned 96-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u96le = u128;
+
+/// Represents an unsigned 97-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u97le = u128;
+
+/// Represents an unsigned 98-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u98le = u128;
+
+/// Represents an unsigned 99-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u99le = u128;
+
+/// Represents an unsigned 100-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u100le = u128;
+
+/// Represents an unsigned 101-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u101le = u128;
+
+/// Represents an unsigned 102-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u102le = u128;
+
+/// Represents an unsigned 103-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u103le = u128;
+
+/// Represents an unsigned 104-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u104le = u128;
+
+/// Represents an unsigned 105-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u105le = u128;
+
+/// Represents an unsigned 106-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u106le = u128;
+
+/// Represents an unsigned 107-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u107le = u128;
+
+/// Represents an unsigned 108-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u108le = u128;
+
+/// Represents an unsigned 109-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u109le = u128;
+
+/// Represents an unsigned 110-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u110le = u128;
+
+/// Represents an unsigned 111-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u111le = u128;
+
+/// Represents an unsigned 112-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u112le = u128;
+
+/// Represents an unsigned 113-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u113le = u128;
+
+/// Represents an unsigned 114-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u114le = u128;
+
+/// Represents an unsigned 115-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u115le = u128;
+
+/// Represents an unsigned 116-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u116le = u128;
+
+/// Represents an unsigned 117-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u117le = u128;
+
+/// Represents an unsigned 118-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u118le = u128;
+
+/// Represents an unsigned 119-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u119le = u128;
+
+/// Represents an unsigned 120-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u120le = u128;
+
+/// Represents an unsigned 121-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u121le = u128;
+
+/// Represents an unsigned 122-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u122le = u128;
+
+/// Represents an unsigned 123-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u123le = u128;
+
+/// Represents an unsigned 124-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u124le = u128;
+
+/// Represents an unsigned 125-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u125le = u128;
+
+/// Represents an unsigned 126-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u126le = u128;
+
+/// Represents an unsigned 127-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u127le = u128;
+
+/// Represents an unsigned 128-bit integer. libpnet #[packet]-derived structs using this type will
+/// hold it in memory as little-endian, but accessors/mutators will return/take host-order values.
+pub type u128le = u128;
+
 /// Represents an unsigned 9-bit integer in host endianness.
 pub type u9he = u16;
 
@@ -658,3 +1173,202 @@ pub type u63he = u64;
 
 /// Represents an unsigned 64-bit integer in host endianness.
 pub type u64he = u64;
+
+/// Represents an unsigned 65-bit integer in host endianness.
+pub type u65he = u128;
+
+/// Represents an unsigned 66-bit integer in host endianness.
+pub type u66he = u128;
+
+/// Represents an unsigned 67-bit integer in host endianness.
+pub type u67he = u128;
+
+/// Represents an unsigned 68-bit integer in host endianness.
+pub type u68he = u128;
+
+/// Represents an unsigned 69-bit integer in host endianness.
+pub type u69he = u128;
+
+/// Represents an unsigned 70-bit integer in host endianness.
+pub type u70he = u128;
+
+/// Represents an unsigned 71-bit integer in host endianness.
+pub type u71he = u128;
+
+/// Represents an unsigned 72-bit integer in host endianness.
+pub type u72he = u128;
+
+/// Represents an unsigned 73-bit integer in host endianness.
+pub type u73he = u128;
+
+/// Represents an unsigned 74-bit integer in host endianness.
+pub type u74he = u128;
+
+/// Represents an unsigned 75-bit integer in host endianness.
+pub type u75he = u128;
+
+/// Represents an unsigned 76-bit integer in host endianness.
+pub type u76he = u128;
+
+/// Represents an unsigned 77-bit integer in host endianness.
+pub type u77he = u128;
+
+/// Represents an unsigned 78-bit integer in host endianness.
+pub type u78he = u128;
+
+/// Represents an unsigned 79-bit integer in host endianness.
+pub type u79he = u128;
+
+/// Represents an unsigned 80-bit integer in host endianness.
+pub type u80he = u128;
+
+/// Represents an unsigned 81-bit integer in host endianness.
+pub type u81he = u128;
+
+/// Represents an unsigned 82-bit integer in host endianness.
+pub type u82he = u128;
+
+/// Represents an unsigned 83-bit integer in host endianness.
+pub type u83he = u128;
+
+/// Represents an unsigned 84-bit integer in host endianness.
+pub type u84he = u128;
+
+/// Represents an unsigned 85-bit integer in host endianness.
+pub type u85he = u128;
+
+/// Represents an unsigned 86-bit integer in host endianness.
+pub type u86he = u128;
+
+/// Represents an unsigned 87-bit integer in host endianness.
+pub type u87he = u128;
+
+/// Represents an unsigned 88-bit integer in host endianness.
+pub type u88he = u128;
+
+/// Represents an unsigned 89-bit integer in host endianness.
+pub type u89he = u128;
+
+/// Represents an unsigned 90-bit integer in host endianness.
+pub type u90he = u128;
+
+/// Represents an unsigned 91-bit integer in host endianness.
+pub type u91he = u128;
+
+/// Represents an unsigned 92-bit integer in host endianness.
+pub type u92he = u128;
+
+/// Represents an unsigned 93-bit integer in host endianness.
+pub type u93he = u128;
+
+/// Represents an unsigned 94-bit integer in host endianness.
+pub type u94he = u128;
+
+/// Represents an unsigned 95-bit integer in host endianness.
+pub type u95he = u128;
+
+/// Represents an unsigned 96-bit integer in host endianness.
+pub type u96he = u128;
+
+/// Represents an unsigned 97-bit integer in host endianness.
+pub type u97he = u128;
+
+/// Represents an unsigned 98-bit integer in host endianness.
+pub type u98he = u128;
+
+/// Represents an unsigned 99-bit integer in host endianness.
+pub type u99he = u128;
+
+/// Represents an unsigned 100-bit integer in host endianness.
+pub type u100he = u128;
+
+/// Represents an unsigned 101-bit integer in host endianness.
+pub type u101he = u128;
+
+/// Represents an unsigned 102-bit integer in host endianness.
+pub type u102he = u128;
+
+/// Represents an unsigned 103-bit integer in host endianness.
+pub type u103he = u128;
+
+/// Represents an unsigned 104-bit integer in host endianness.
+pub type u104he = u128;
+
+/// Represents an unsigned 105-bit integer in host endianness.
+pub type u105he = u128;
+
+/// Represents an unsigned 106-bit integer in host endianness.
+pub type u106he = u128;
+
+/// Represents an unsigned 107-bit integer in host endianness.
+pub type u107he = u128;
+
+/// Represents an unsigned 108-bit integer in host endianness.
+pub type u108he = u128;
+
+/// Represents an unsigned 109-bit integer in host endianness.
+pub type u109he = u128;
+
+/// Represents an unsigned 110-bit integer in host endianness.
+pub type u110he = u128;
+
+/// Represents an unsigned 111-bit integer in host endianness.
+pub type u111he = u128;
+
+/// Represents an unsigned 112-bit integer in host endianness.
+pub type u112he = u128;
+
+/// Represents an unsigned 113-bit integer in host endianness.
+pub type u113he = u128;
+
+/// Represents an unsigned 114-bit integer in host endianness.
+pub type u114he = u128;
+
+/// Represents an unsigned 115-bit integer in host endianness.
+pub type u115he = u128;
+
+/// Represents an unsigned 116-bit integer in host endianness.
+pub type u116he = u128;
+
+/// Represents an unsigned 117-bit integer in host endianness.
+pub type u117he = u128;
+
+/// Represents an unsigned 118-bit integer in host endianness.
+pub type u118he = u128;
+
+/// Represents an unsigned 119-bit integer in host endianness.
+pub type u119he = u128;
+
+/// Represents an unsigned 120-bit integer in host endianness.
+pub type u120he = u128;
+
+/// Represents an unsigned 121-bit integer in host endianness.
+pub type u121he = u128;
+
+/// Represents an unsigned 122-bit integer in host endianness.
+pub type u122he = u128;
+
+/// Represents an unsigned 123-bit integer in host endianness.
+pub type u123he = u128;
+
+/// Represents an unsigned 124-bit integer in host endianness.
+pub type u124he = u128;
+
+/// Represents an unsigned 125-bit integer in host endianness.
+pub type u125he = u128;
+
+/// Represents an unsigned 126-bit integer in host endianness.
+pub type u126he = u128;
+
+/// Represents an unsigned 127-bit integer in host endianness.
+pub type u127he = u128;
+
+/// Represents an unsigned 128-bit integer in host endianness.
+pub type u128he = u128;
+
+/// Represents a UTF-8 string terminated by a NUL byte. The terminator is not part of the value.
+pub type cstring = String;
+
+/// Represents a UTF-8 string prefixed by its length in bytes, stored as a single byte. The prefix
+/// is not part of the value.
+pub type pstring = String;
@@ -10,8 +10,8 @@
 
 //! Implements the #[packet] decorator.
 
-use crate::parse::{parse_ty, Packet, Type};
-use crate::util::{operations, to_mutator, Endianness, GetOperation, SetOperation};
+use crate::parse::{parse_ty, Packet, StringKind, Type};
+use crate::util::{operations, to_mutator, GetOperation, SetOperation};
 use quote::{format_ident, quote};
 use syn::Error;
 
@@ -84,6 +84,7 @@ fn packet_impl(
     let mut bit_offset = 0;
     let mut offset_fns_packet = Vec::new();
     let mut offset_fns_struct = Vec::new();
+    let mut offset_fns_bounded = Vec::new();
     let mut accessors = vec![];
     let mut mutators = vec![];
     let mut populate = vec![];
@@ -104,7 +105,7 @@ fn packet_impl(
         let packet_length = if let Some(packet_length) = field.packet_length.as_ref() {
             let packet_length = packet_length.replace(
                 "r#field_offset",
-                &format!("&self.packet[({})..]", co.to_string()),
+                &format!("self.packet[..].get(({})..).unwrap_or(&[])", co.to_string()),
             );
             let packet_length = syn::parse_str::<syn::Expr>(&packet_length)?;
             quote!(#packet_length)
@@ -140,7 +141,7 @@ fn packet_impl(
                         #[cfg_attr(feature = "clippy", allow(used_underscore_binding))]
                         pub fn #get_field_name_raw(&self) -> &[u8] {
                             use std::cmp::min;
-                            let current_offset = #co;
+                            let current_offset = min(#co, self.packet.len());
                             let end = min(current_offset + #packet_length, self.packet.len());
                             &self.packet[current_offset..end]
                         }
@@ -152,14 +153,14 @@ fn packet_impl(
                         #[cfg_attr(feature = "clippy", allow(used_underscore_binding))]
                         pub fn #get_field_name_raw_mut(&mut self) -> &mut [u8] {
                             use std::cmp::min;
-                            let current_offset = #co;
+                            let current_offset = min(#co, self.packet.len());
                             let end = min(current_offset + #packet_length, self.packet.len());
                             &mut self.packet[current_offset..end]
                         }
                     });
                 }
                 match **inner_ty {
-                    Type::Primitive(ref inner_ty_str, size, _endianness) => {
+                    Type::Primitive(ref inner_ty_str, size, endianness) => {
                         let inner_ty: syn::Type = syn::parse_str(inner_ty_str)?;
                         if size % 8 != 0 {
                             return Err(Error::new(
@@ -169,30 +170,78 @@ fn packet_impl(
                         }
                         let get_name = format_ident!("get_{}", field.name);
                         let set_name = format_ident!("set_{}", field.name);
-                        let ops = operations(0, size, Endianness::Big).unwrap();
+                        let ops = operations(0, size, endianness).unwrap();
                         let size = size / 8;
                         let access_ops = gen_get_ops("packet", inner_ty_str, &ops);
-                        accessors.push(quote! {
-                            /// Get the value of the {name} field (copies contents)
-                            #[inline]
-                            #[allow(trivial_numeric_casts, unused_parens, unused_braces)]
-                            #[cfg_attr(feature = "clippy", allow(used_underscore_binding))]
-                            pub fn #get_name(&self) -> Vec<#inner_ty> {
-                                use std::cmp::min;
-                                let current_offset = #co;
-                                let pkt_len = self.packet.len();
-                                let end = min(current_offset + #packet_length, pkt_len);
-
-                                let packet = &self.packet[current_offset..end];
-                                let mut vec: Vec<#inner_ty> = Vec::with_capacity(packet.len());
-                                let mut co = 0;
-                                for _ in 0..vec.capacity() {
-                                    vec.push(#access_ops);
-                                    co += #size;
+                        if inner_ty_str == "u8" {
+                            let get_name_vec = format_ident!("get_{}_vec", field.name);
+                            let get_name_borrowed = format_ident!("get_{}_borrowed", field.name);
+                            let get_slice = if field.is_payload {
+                                quote! {
+                                    use std::cmp::min;
+                                    let current_offset = min(#co, self.packet.len());
+                                    let end = min(current_offset + #packet_length, self.packet.len());
+                                    &self.packet[current_offset..end]
+                                }
+                            } else {
+                                let get_name_raw = format_ident!("get_{}_raw", field.name);
+                                quote!(self.#get_name_raw())
+                            };
+                            accessors.push(quote! {
+                                /// Get the value of the {name} field, without copying
+                                #[inline]
+                                #[allow(trivial_numeric_casts)]
+                                #[cfg_attr(feature = "clippy", allow(used_underscore_binding))]
+                                pub fn #get_name(&self) -> &[u8] {
+                                    #get_slice
                                 }
-                                vec
+
+                                /// Get the value of the {name} field (copies contents)
+                                #[inline]
+                                pub fn #get_name_vec(&self) -> Vec<u8> {
+                                    self.#get_name().to_vec()
+                                }
+                            });
+                            if !mutable {
+                                accessors.push(quote! {
+                                    /// Get the value of the {name} field, borrowed for the lifetime
+                                    /// of the underlying buffer. Returns `None` if the packet owns
+                                    /// its data.
+                                    #[inline]
+                                    #[allow(trivial_numeric_casts)]
+                                    #[cfg_attr(feature = "clippy", allow(used_underscore_binding))]
+                                    pub fn #get_name_borrowed(&self) -> Option<&'a [u8]> {
+                                        use std::cmp::min;
+                                        let packet = self.packet.as_borrowed()?;
+                                        let current_offset = min(#co, packet.len());
+                                        let end = min(current_offset + #packet_length, packet.len());
+                                        Some(&packet[current_offset..end])
+                                    }
+                                });
                             }
-                        });
+                        } else {
+                            accessors.push(quote! {
+                                /// Get the value of the {name} field (copies contents)
+                                #[inline]
+                                #[allow(trivial_numeric_casts, unused_parens, unused_braces)]
+                                #[cfg_attr(feature = "clippy", allow(used_underscore_binding))]
+                                pub fn #get_name(&self) -> Vec<#inner_ty> {
+                                    use std::cmp::min;
+                                    let current_offset = #co;
+                                    let pkt_len = self.packet.len();
+                                    let end = min(current_offset + #packet_length, pkt_len);
+
+                                    let packet = &self.packet[current_offset..end];
+                                    let mut vec: Vec<#inner_ty> = Vec::with_capacity(packet.len() / #size);
+                                    let mut co = 0;
+                                    for _ in 0..packet.len() / #size {
+                                        vec.push(#access_ops);
+                                        co += #size;
+                                    }
+                                    vec
+                                }
+                            });
+                        }
                         let check_len = if field.packet_length.is_some() {
                             quote! {
                                 let len = #packet_length;
@@ -237,6 +286,12 @@ fn packet_impl(
                             "variable length fields may not contain vectors",
                         ));
                     }
+                    Type::String(_) => {
+                        return Err(Error::new(
+                            field.span,
+                            "variable length fields may not contain strings",
+                        ));
+                    }
                     Type::Misc(ref inner_ty_str) => {
                         let get_name = format_ident!("get_{}", field.name);
                         let set_name = format_ident!("set_{}", field.name);
@@ -290,6 +345,100 @@ fn packet_impl(
                     }
                 }
             }
+            Type::String(kind) => {
+                if bit_offset % 8 != 0 {
+                    return Err(Error::new(field.span, "string fields must be byte aligned"));
+                }
+                let get_field_name_raw = format_ident!("get_{}_raw", field.name);
+                let (get_raw, set_raw) = match kind {
+                    StringKind::Sized => {
+                        let check_len = if field.packet_length.is_some() {
+                            quote! {
+                                let len = #packet_length;
+                                assert!(val.len() <= len);
+                            }
+                        } else {
+                            quote!()
+                        };
+                        let get_raw = quote! {
+                            let current_offset = min(#co, self.packet.len());
+                            let end = min(current_offset + #packet_length, self.packet.len());
+                            &self.packet[current_offset..end]
+                        };
+                        let set_raw = quote! {
+                            let current_offset = #co;
+                            #check_len
+                            self.packet[current_offset..current_offset + val.len()]
+                                .copy_from_slice(val.as_bytes());
+                        };
+                        (get_raw, set_raw)
+                    }
+                    StringKind::NulTerminated => {
+                        let get_raw = quote! {
+                            let current_offset = min(#co, self.packet.len());
+                            let data = &self.packet[current_offset..];
+                            let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+                            &data[..end]
+                        };
+                        let set_raw = quote! {
+                            let current_offset = #co;
+                            assert!(!val.as_bytes().contains(&0));
+                            self.packet[current_offset..current_offset + val.len()]
+                                .copy_from_slice(val.as_bytes());
+                            self.packet[current_offset + val.len()] = 0;
+                        };
+                        offset_fns_packet.push(quote!(self.#get_field_name_raw().len()));
+                        (get_raw, set_raw)
+                    }
+                    StringKind::LengthPrefixed => {
+                        let len =
+                            quote!(self.packet.as_slice().get(#co).map_or(0, |len| *len as usize));
+                        let get_raw = quote! {
+                            let current_offset = min(#co + 1, self.packet.len());
+                            let end = min(current_offset + #len, self.packet.len());
+                            &self.packet[current_offset..end]
+                        };
+                        let set_raw = quote! {
+                            let current_offset = #co;
+                            assert!(val.len() <= u8::MAX as usize);
+                            self.packet[current_offset] = val.len() as u8;
+                            self.packet[current_offset + 1..current_offset + 1 + val.len()]
+                                .copy_from_slice(val.as_bytes());
+                        };
+                        offset_fns_packet.push(len);
+                        (get_raw, set_raw)
+                    }
+                };
+                if kind != StringKind::Sized {
+                    // The NUL terminator or the length prefix
+                    bit_offset += 8;
+                }
+                accessors.push(quote! {
+                    /// Get the raw &[u8] value of the {name} field, without copying
+                    #[inline]
+                    #[allow(trivial_numeric_casts)]
+                    #[cfg_attr(feature = "clippy", allow(used_underscore_binding))]
+                    pub fn #get_field_name_raw(&self) -> &[u8] {
+                        use std::cmp::min;
+                        #get_raw
+                    }
+
+                    /// Get the value of the {name} field, without copying
+                    #[inline]
+                    pub fn #get_field_name(&self) -> Result<&str, std::str::Utf8Error> {
+                        std::str::from_utf8(self.#get_field_name_raw())
+                    }
+                });
+                mutators.push(quote! {
+                    /// Set the value of the {name} field (copies contents)
+                    #[inline]
+                    #[allow(trivial_numeric_casts)]
+                    #[cfg_attr(feature = "clippy", allow(used_underscore_binding))]
+                    pub fn #set_field_name(&mut self, val: &str) {
+                        #set_raw
+                    }
+                });
+            }
             Type::Misc(ref ty_str) => {
                 let mut inner_accessors = vec![];
                 let mut inner_mutators = vec![];
@@ -297,6 +446,11 @@ fn packet_impl(
                 let mut set_args = vec![];
                 let construct_with = field.construct_with.as_ref().expect("construct_with");
                 let ty: syn::Type = syn::parse_str(ty_str)?;
+                let construct = if field.construct_from {
+                    quote!(#ty::from)
+                } else {
+                    quote!(#ty::new)
+                };
                 for (i, arg) in construct_with.iter().enumerate() {
                     if let Type::Primitive(ref ty_str, size, endianness) = *arg {
                         let ops = operations(bit_offset % 8, size, endianness).unwrap();
@@ -354,7 +508,7 @@ fn packet_impl(
                     #[cfg_attr(feature = "clippy", allow(used_underscore_binding))]
                     pub fn #get_field_name(&self) -> #ty {
                         #(#inner_accessors)*
-                        #ty::new(#(#get_args,)*)
+                        #construct(#(#get_args,)*)
                     }
                 });
             }
@@ -363,9 +517,17 @@ fn packet_impl(
             offset_fns_packet.push(packet_length);
         }
         if let Some(struct_length) = field.struct_length.clone() {
-            offset_fns_struct.push(syn::parse_str(&struct_length)?);
+            let struct_length: proc_macro2::TokenStream = syn::parse_str(&struct_length)?;
+            // A trailing field without `#[length]` takes the rest of the buffer, so isn't part of
+            // the size of the packet on the wire
+            let unbounded = field.packet_length.is_none()
+                && matches!(field.ty, Type::Vector(_) | Type::String(StringKind::Sized));
+            if !unbounded {
+                offset_fns_bounded.push(struct_length.clone());
+            }
+            offset_fns_struct.push(struct_length);
         }
-        if let Type::Vector(_) = &field.ty {
+        if let Type::Vector(_) | Type::String(_) = &field.ty {
             populate.push(quote!(self.#set_field_name(&packet.#field_name);));
         } else {
             populate.push(quote!(self.#set_field_name(packet.#field_name);));
@@ -388,6 +550,7 @@ fn packet_impl(
 
     let base_name = format_ident!("{}", &packet.base_name);
     let struct_size = current_offset(bit_offset, &offset_fns_struct[..]);
+    let bounded_size = current_offset(bit_offset, &offset_fns_bounded[..]);
     let byte_size = if bit_offset % 8 == 0 {
         bit_offset / 8
     } else {
@@ -457,6 +620,14 @@ fn packet_impl(
                 #struct_size
             }
 
+            /// The size (in bytes) of a #base_name instance when converted into a byte-array,
+            /// without a trailing field that has no `#[length]`. This is the size reported by
+            /// `PacketSize` for the resulting packet.
+            #[inline]
+            pub fn bounded_packet_size(_packet: &#base_name) -> usize {
+                #bounded_size
+            }
+
             #populate
 
             #(#accessors)*
@@ -692,7 +863,17 @@ pub fn converters(packet: &Packet) -> Result<proc_macro2::TokenStream, Error> {
     for field in &packet.fields {
         let field_name = format_ident!("{}", &field.name);
         let get_field_name = format_ident!("get_{}", &field.name);
-        get_fields.push(quote!(#field_name: self.#get_field_name()));
+        if field.is_bytes() {
+            let get_field_name_vec = format_ident!("get_{}_vec", &field.name);
+            get_fields.push(quote!(#field_name: self.#get_field_name_vec()));
+        } else if let Type::String(_) = field.ty {
+            let get_field_name_raw = format_ident!("get_{}_raw", &field.name);
+            get_fields.push(quote! {
+                #field_name: String::from_utf8_lossy(self.#get_field_name_raw()).into_owned()
+            });
+        } else {
+            get_fields.push(quote!(#field_name: self.#get_field_name()));
+        }
     }
     Ok(quote! {
         impl<'p> libpacket_core::FromPacket for #packet_name<'p> {
@@ -719,6 +900,164 @@ pub fn converters(packet: &Packet) -> Result<proc_macro2::TokenStream, Error> {
     })
 }
 
+/// Strategy for a primitive field, covering every value which fits in its width.
+fn primitive_strategy(ty_str: &str, size: usize) -> Result<proc_macro2::TokenStream, Error> {
+    let ty: syn::Type = syn::parse_str(ty_str)?;
+    Ok(quote! {
+        0..=(<#ty>::MAX >> (std::mem::size_of::<#ty>() * 8 - #size))
+    })
+}
+
+pub fn arbitrary_impls(packet: &Packet) -> Result<proc_macro2::TokenStream, Error> {
+    if !cfg!(feature = "proptest") {
+        return Ok(quote!());
+    }
+    let name = format_ident!("{}", packet.base_name);
+    let mut fixed_strategies = vec![];
+    let mut fixed_names = vec![];
+    let mut variable_names = vec![];
+    let mut variable_steps = vec![];
+    for (i, field) in packet.fields.iter().enumerate() {
+        let field_name = format_ident!("{}", field.name);
+        let is_last = i == packet.fields.len() - 1;
+        let len = match (&field.owned_length, &field.packet_length) {
+            (Some(owned_length), _) => {
+                let owned_length = syn::parse_str::<syn::Expr>(owned_length)?;
+                Some(quote!(#owned_length))
+            }
+            // The length depends on the buffer, so can't be derived from the other fields
+            (None, Some(_)) => match field.ty {
+                Type::Vector(_) | Type::String(StringKind::Sized) => return Ok(quote!()),
+                _ => None,
+            },
+            (None, None) => None,
+        };
+        let strategy = match field.ty {
+            Type::Primitive(ref ty_str, size, _) => {
+                fixed_strategies.push(primitive_strategy(ty_str, size)?);
+                fixed_names.push(field_name);
+                continue;
+            }
+            Type::Misc(ref ty_str) => {
+                let ty: syn::Type = syn::parse_str(ty_str)?;
+                let construct_with = field.construct_with.as_ref().expect("construct_with");
+                let mut args = vec![];
+                let mut strategies = vec![];
+                for (i, arg) in construct_with.iter().enumerate() {
+                    if let Type::Primitive(ref ty_str, size, _) = *arg {
+                        args.push(format_ident!("arg{}", i));
+                        strategies.push(primitive_strategy(ty_str, size)?);
+                    }
+                }
+                let construct = if field.construct_from {
+                    quote!(#ty::from)
+                } else {
+                    quote!(#ty::new)
+                };
+                fixed_strategies.push(quote! {
+                    (#(#strategies,)*).prop_map(|(#(#args,)*)| #construct(#(#args,)*))
+                });
+                fixed_names.push(field_name);
+                continue;
+            }
+            Type::String(kind) => {
+                let chars = match kind {
+                    StringKind::NulTerminated => quote!(1u8..0x80),
+                    _ => quote!(0u8..0x80),
+                };
+                let count = match len {
+                    Some(ref len) => quote!(#len),
+                    None => quote!(0..32),
+                };
+                quote! {
+                    proptest::collection::vec(#chars, #count)
+                        .prop_map(|bytes| String::from_utf8(bytes).unwrap())
+                }
+            }
+            Type::Vector(ref inner_ty) => match **inner_ty {
+                Type::Primitive(ref ty_str, size, _) => {
+                    let elem = primitive_strategy(ty_str, size)?;
+                    let count = match len {
+                        Some(ref len) => {
+                            let size = size / 8;
+                            quote!(#len / #size)
+                        }
+                        None => quote!(0..64),
+                    };
+                    quote!(proptest::collection::vec(#elem, #count))
+                }
+                Type::Misc(ref ty_str) => {
+                    let ty: syn::Type = syn::parse_str(ty_str)?;
+                    let ty_packet = format_ident!("{}Packet", ty_str);
+                    match len {
+                        Some(ref len) => quote! {{
+                            let len = #len;
+                            let max = len / std::cmp::max(#ty_packet::minimum_packet_size(), 1);
+                            proptest::collection::vec(any::<#ty>(), 0..=max).prop_filter(
+                                "packets must fill the field",
+                                move |vals| {
+                                    vals.iter().map(|v| #ty_packet::packet_size(v)).sum::<usize>()
+                                        == len
+                                },
+                            )
+                        }},
+                        None => quote!(proptest::collection::vec(any::<#ty>(), 0..4)),
+                    }
+                }
+                _ => return Ok(quote!()),
+            },
+        };
+        if len.is_none() && !is_last {
+            // A fixed-size string, which doesn't depend on any other field
+            fixed_strategies.push(strategy);
+            fixed_names.push(field_name);
+            continue;
+        }
+        variable_steps.push(quote! {
+            let strategy = strategy
+                .prop_flat_map(|_packet| {
+                    let strategy = #strategy;
+                    (Just(_packet), strategy)
+                })
+                .prop_map(|(mut _packet, val)| {
+                    _packet.#field_name = val;
+                    _packet
+                });
+        });
+        variable_names.push(field_name);
+    }
+
+    // Tuples of strategies are limited to 12 elements, so nest them
+    let (fixed_strategy, fixed_pattern) = if fixed_names.is_empty() {
+        (quote!(Just(())), quote!(()))
+    } else {
+        let strategies = fixed_strategies
+            .chunks(10)
+            .map(|chunk| quote!((#(#chunk,)*)));
+        let patterns = fixed_names.chunks(10).map(|chunk| quote!((#(#chunk,)*)));
+        (quote!((#(#strategies,)*)), quote!((#(#patterns,)*)))
+    };
+
+    Ok(quote! {
+        impl proptest::arbitrary::Arbitrary for #name {
+            type Parameters = ();
+            type Strategy = proptest::strategy::BoxedStrategy<#name>;
+
+            #[allow(unused_parens, trivial_numeric_casts)]
+            #[cfg_attr(feature = "clippy", allow(used_underscore_binding))]
+            fn arbitrary_with(_: ()) -> Self::Strategy {
+                use proptest::prelude::*;
+                let strategy = #fixed_strategy.prop_map(|#fixed_pattern| #name {
+                    #(#fixed_names,)*
+                    #(#variable_names: Default::default(),)*
+                });
+                #(#variable_steps)*
+                strategy.boxed()
+            }
+        }
+    })
+}
+
 pub fn debug_impls(packet: &Packet) -> Result<proc_macro2::TokenStream, Error> {
     let mut field_fmt_str = String::new();
     let mut get_fields = vec![];
@@ -807,6 +1146,8 @@ fn gen_get_ops(name: &str, ty: &str, operations: &[GetOperation]) -> proc_macro2
 
 #[test]
 fn test_gen_get_ops() {
+    use crate::util::Endianness;
+
     {
         let ops = operations(0, 24, Endianness::Big).unwrap();
         let result = gen_get_ops("test", "u24be", &ops);
@@ -54,6 +54,15 @@
 //!        identity function. It exists for consistency with `MutableExamplePacket`.
 //!      - A number of accessor methods, of the form `pub get_{field_name}(&self) -> {field_type}`,
 //!        which will retrieve the host representation of the on-the-wire value.
+//!        `Vec<u8>` fields are returned as a borrowed `&[u8]` rather than copied; an owned
+//!        copy is available from `pub get_{field_name}_vec(&self) -> Vec<u8>`.
+//!      - For `Vec<u8>` fields, an accessor of the form
+//!        `pub get_{field_name}_borrowed(&self) -> Option<&'p [u8]>`, which returns a slice tied
+//!        to the lifetime of the underlying buffer rather than to `&self`. It returns `None` if
+//!        the packet owns its buffer.
+//!      - For `Vec<T>` fields of another packet type, an additional accessor of the form
+//!        `pub get_{field_name}_iter(&self) -> TIterable`, which lazily parses each `TPacket`
+//!        from the buffer instead of collecting owned `T` values.
 //!  * A `MutableExamplePacket<'p>` structure, which is used when sending packets on the network.
 //!    This structure contains:
 //!      - A method, `pub fn new<'p>(packet: &'p mut [u8]) -> MutableExamplePacket<'p>`, used for
@@ -78,6 +87,11 @@
 //!      - `pnet::packet::PacketSize` (`ExamplePacket` and `MutableExamplePacket`)
 //!  * An `ExampleIterator` structure, which implements `std::iter::Iterator`, to allow iterating
 //!    over vectors of `ExamplePacket` contained within another packet. Used internally.
+//!  * If the `proptest` feature of this crate is enabled, an implementation of
+//!    `proptest::arbitrary::Arbitrary` for `Example`, so the crate using the macro must then depend
+//!    on `proptest`. Variable length fields are sized by
+//!    evaluating their `#[length]` expression against the other fields, so `Example` must also
+//!    implement `Clone`. No implementation is generated if a length depends on the buffer (`...`).
 //!
 //! ## Attributes
 //!
@@ -110,6 +124,12 @@
 //!    `pnet_macros::types`, or another structure marked with #[derive(Packet)], for example
 //!    `Vec<Example>`.
 //!
+//!    A `String` field with a `#[length]` attribute holds that many bytes of UTF-8. Its accessor
+//!    returns a `Result<&str, Utf8Error>` without copying, and its mutator takes a `&str`. The
+//!    `cstring` (NUL-terminated) and `pstring` (prefixed with a one byte length) types from
+//!    `pnet_macros::types` work the same way, but carry their own length and so don't need a
+//!    `#[length]` attribute.
+//!
 //!  * \#[payload]
 //!
 //!    This attribute specifies the payload associated with the packet. This should specify the
@@ -134,6 +154,11 @@
 //!      - The `pnet::packet::ToPrimitiveValues` trait must be implemented for the field type,
 //!        which must return a tuple of the primitive types specified in the parameters to the
 //!        `#[construct_with(...)]` attribute, and in the `new` method.
+//!
+//!  * \#[construct_from(<primitive type>)]
+//!
+//!    Like `#[construct_with]`, for field types built from a single primitive with `From` rather
+//!    than `new`, such as `Ipv6Addr` with `#[construct_from(u128be)]`.
 
 #![deny(missing_docs)]
 
@@ -146,7 +171,7 @@ mod parse;
 mod util;
 
 /// The entry point for the `derive(Packet)` custom derive
-#[proc_macro_derive(Packet, attributes(construct_with, length, payload))]
+#[proc_macro_derive(Packet, attributes(construct_from, construct_with, length, payload))]
 pub fn derive_packet(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
     // ensure struct is public
@@ -178,6 +203,7 @@ fn generate_packet(s: &syn::DataStruct, name: String) -> Result<proc_macro2::Tok
     let ts_iterables = gen::iterables(&packet)?;
     let ts_converters = gen::converters(&packet)?;
     let ts_debug_impls = gen::debug_impls(&packet)?;
+    let ts_arbitrary_impls = gen::arbitrary_impls(&packet)?;
     let tts = quote! {
         #structs
         #ts_packet_impls
@@ -186,6 +212,7 @@ fn generate_packet(s: &syn::DataStruct, name: String) -> Result<proc_macro2::Tok
         #ts_iterables
         #ts_converters
         #ts_debug_impls
+        #ts_arbitrary_impls
     };
     Ok(tts)
 }
@@ -17,19 +17,46 @@ pub enum Type {
     Primitive(String, usize, Endianness),
     /// Any type of the form `Vec<T>`.
     Vector(Box<Type>),
+    /// A UTF-8 string, either `String` or one of the string types from `libpacket_core::types::*`.
+    String(StringKind),
     /// Any type which isn't a primitive or a vector.
     Misc(String),
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StringKind {
+    /// A `String` whose length is given by `#[length]`, or the rest of the packet.
+    Sized,
+    /// A `cstring`, terminated by a NUL byte.
+    NulTerminated,
+    /// A `pstring`, prefixed by its length as a single byte.
+    LengthPrefixed,
+}
+
 #[derive(Clone, Debug)]
 pub struct Field {
     pub name: String,
     pub span: Span,
     pub ty: Type,
     pub packet_length: Option<String>,
+    /// The length expression evaluated against the owned struct, `_packet`, rather than the
+    /// packet. `None` if the field has no `#[length]`, or its length depends on the buffer (`...`).
+    pub owned_length: Option<String>,
     pub struct_length: Option<String>,
     pub is_payload: bool,
     pub construct_with: Option<Vec<Type>>,
+    /// Whether the field is built with `From` rather than `new`, see `#[construct_from]`.
+    pub construct_from: bool,
+}
+
+impl Field {
+    /// Whether this field is a `Vec<u8>`, whose accessor returns a borrowed `&[u8]`.
+    pub fn is_bytes(&self) -> bool {
+        match &self.ty {
+            Type::Vector(inner) => matches!(**inner, Type::Primitive(ref ty, 8, _) if ty == "u8"),
+            _ => false,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -62,8 +89,10 @@ pub fn packet(s: &syn::DataStruct, name: String) -> Result<Packet, Error> {
             }
         };
         let mut construct_with = Vec::new();
+        let mut construct_from = false;
         let mut is_payload = false;
         let mut packet_length = None;
+        let mut owned_length = None;
         let mut struct_length = None;
         for attr in &field.attrs {
             let node = attr.parse_meta()?;
@@ -109,9 +138,16 @@ pub fn packet(s: &syn::DataStruct, name: String) -> Result<Packet, Error> {
                                 let tts = s2.parse::<syn::Expr>()?.to_token_stream();
                                 let tt_tokens: Vec<_> = tts.into_iter().collect();
                                 // Parse and replace fields
-                                let tokens_packet = parse_length_expr(&tt_tokens, &field_names)?;
+                                let tokens_packet =
+                                    parse_length_expr(&tt_tokens, &field_names, "self.get_{}()")?;
                                 let parsed = quote! { (#(#tokens_packet)*) as usize };
                                 packet_length = Some(parsed.to_string());
+                                if !s.value().contains("...") {
+                                    let tokens_owned =
+                                        parse_length_expr(&tt_tokens, &field_names, "_packet.{}")?;
+                                    let parsed = quote! { (#(#tokens_owned)*) as usize };
+                                    owned_length = Some(parsed.to_string());
+                                }
                             } else {
                                 return Err(Error::new(
                                     name_value.lit.span(),
@@ -129,11 +165,25 @@ pub fn packet(s: &syn::DataStruct, name: String) -> Result<Packet, Error> {
                 }
                 syn::Meta::List(ref l) => {
                     if let Some(ident) = l.path.get_ident() {
-                        if ident == "construct_with" {
+                        if ident == "construct_with" || ident == "construct_from" {
+                            if !construct_with.is_empty() {
+                                return Err(Error::new(
+                                    l.path.span(),
+                                    "#[construct_with] and #[construct_from] may only be \
+                                     specified once per field",
+                                ));
+                            }
                             if l.nested.is_empty() {
                                 return Err(Error::new(
                                     l.path.span(),
-                                    "#[construct_with] must have at least one argument",
+                                    format!("#[{}] must have at least one argument", ident),
+                                ));
+                            }
+                            construct_from = ident == "construct_from";
+                            if construct_from && l.nested.len() != 1 {
+                                return Err(Error::new(
+                                    l.nested.span(),
+                                    "#[construct_from] takes exactly one argument",
                                 ));
                             }
 
@@ -182,7 +232,26 @@ pub fn packet(s: &syn::DataStruct, name: String) -> Result<Packet, Error> {
         };
 
         match ty {
-            Type::Vector(_) => {
+            Type::Vector(ref inner) => {
+                struct_length = Some(match **inner {
+                    Type::Primitive(_, size, _) if size > 8 => {
+                        format!("_packet.{}.len() * {}", field_name, size / 8)
+                    }
+                    Type::Misc(ref inner) => format!(
+                        "_packet.{}.iter().map(|v| {}Packet::packet_size(v)).sum::<usize>()",
+                        field_name, inner
+                    ),
+                    _ => format!("_packet.{}.len()", field_name),
+                });
+                if i < sfields.len() - 1 && packet_length.is_none() {
+                    return Err(Error::new(
+                        field.ty.span(),
+                        "variable length field must specify #[length], unless it is the \
+                        last field of a packet",
+                    ));
+                }
+            }
+            Type::String(StringKind::Sized) => {
                 struct_length = Some(format!("_packet.{}.len()", field_name).to_owned());
                 if i < sfields.len() - 1 && packet_length.is_none() {
                     return Err(Error::new(
@@ -192,6 +261,16 @@ pub fn packet(s: &syn::DataStruct, name: String) -> Result<Packet, Error> {
                     ));
                 }
             }
+            Type::String(_) => {
+                struct_length = Some(format!("_packet.{}.len()", field_name).to_owned());
+                if packet_length.is_some() {
+                    return Err(Error::new(
+                        field.ty.span(),
+                        "cstring and pstring fields carry their own length and may not \
+                        specify #[length]",
+                    ));
+                }
+            }
             Type::Misc(_) => {
                 if construct_with.is_empty() {
                     return Err(Error::new(
@@ -208,9 +287,11 @@ pub fn packet(s: &syn::DataStruct, name: String) -> Result<Packet, Error> {
             span: field.span(),
             ty,
             packet_length,
+            owned_length,
             struct_length,
             is_payload,
             construct_with: Some(construct_with),
+            construct_from,
         });
     }
 
@@ -220,10 +301,12 @@ pub fn packet(s: &syn::DataStruct, name: String) -> Result<Packet, Error> {
     })
 }
 
-/// Return the processed length expression for a packet.
+/// Return the processed length expression for a packet, with each field name replaced using the
+/// `accessor` format string.
 fn parse_length_expr(
     tts: &[proc_macro2::TokenTree],
     field_names: &[String],
+    accessor: &str,
 ) -> Result<Vec<proc_macro2::TokenTree>, Error> {
     use proc_macro2::TokenTree;
     let error_msg = "Only field names, constants, integers, basic arithmetic expressions \
@@ -234,7 +317,7 @@ fn parse_length_expr(
         match tt_token {
             TokenTree::Ident(name) => {
                 if field_names.contains(&name.to_string()) {
-                    let tts: syn::Expr = syn::parse_str(&format!("self.get_{}()", name))?;
+                    let tts: syn::Expr = syn::parse_str(&accessor.replace("{}", &name.to_string()))?;
                     let mut modified_packet_tokens: Vec<_> =
                         tts.to_token_stream().into_iter().collect();
                     tokens_packet.append(&mut modified_packet_tokens);
@@ -254,7 +337,7 @@ fn parse_length_expr(
             }
             TokenTree::Group(ref group) => {
                 let ts: Vec<_> = group.stream().into_iter().collect();
-                let tts = parse_length_expr(&ts, field_names)?;
+                let tts = parse_length_expr(&ts, field_names, accessor)?;
                 let mut new_group = Group::new(
                     group.delimiter(),
                     proc_macro2::TokenStream::from_iter(tts.into_iter()),
@@ -271,7 +354,9 @@ fn parse_length_expr(
 
 fn parse_type(ty_str: String, endianness_important: bool) -> Result<Type, String> {
     if let Some((size, endianness, spec)) = parse_ty(&ty_str[..]) {
-        if !endianness_important || size <= 8 || spec == EndiannessSpecified::Yes {
+        if size > 128 {
+            Err("integer types larger than 128 bits are not supported".to_owned())
+        } else if !endianness_important || size <= 8 || spec == EndiannessSpecified::Yes {
             Ok(Type::Primitive(ty_str, size, endianness))
         } else {
             Err("endianness must be specified for types of size >= 8".to_owned())
@@ -285,6 +370,12 @@ fn parse_type(ty_str: String, endianness_important: bool) -> Result<Type, String
             Ok(ty) => Ok(Type::Vector(Box::new(ty))),
             Err(e) => Err(e),
         }
+    } else if ty_str == "String" {
+        Ok(Type::String(StringKind::Sized))
+    } else if ty_str == "cstring" {
+        Ok(Type::String(StringKind::NulTerminated))
+    } else if ty_str == "pstring" {
+        Ok(Type::String(StringKind::LengthPrefixed))
     } else if ty_str.starts_with("&") {
         Err(format!("invalid type: {}", ty_str))
     } else {
@@ -112,7 +112,7 @@ pub struct SetOperation {
     /// Bits to save from old byte
     save_mask: u8,
     /// Bits to mask out of value we're setting
-    value_mask: u64,
+    value_mask: u128,
     /// Number of places to left shift the value we're setting
     shiftl: u8,
     /// Number of places to right shift the value we're setting
@@ -144,7 +144,7 @@ macro_rules! radix_fn {
 }
 
 radix_fn!(radix16_u8, u8);
-radix_fn!(radix16_u64, u64);
+radix_fn!(radix16_u128, u128);
 
 impl fmt::Display for SetOperation {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
@@ -159,7 +159,7 @@ impl fmt::Display for SetOperation {
         };
 
         let mask_str = if should_mask {
-            format!("({{val}} & 0x{})", radix16_u64(self.value_mask))
+            format!("({{val}} & 0x{})", radix16_u128(self.value_mask))
         } else {
             "{val}".to_owned()
         };
@@ -388,16 +388,11 @@ fn test_get_shiftr() {
 /// Assumes big endian, and that each byte will be masked, then cast to the next power of two
 /// greater than or equal to size bits before shifting. offset should be in the range [0, 7]
 pub fn operations(offset: usize, size: usize, endianness: Endianness) -> Option<Vec<GetOperation>> {
-    if offset > 7 || size == 0 || size > 64 {
+    if offset > 7 || size == 0 || size > 128 {
         return None;
     }
 
-    let num_full_bytes = size / 8;
-    let num_bytes = if offset > 0 || size % 8 != 0 {
-        num_full_bytes + 1
-    } else {
-        num_full_bytes
-    };
+    let num_bytes = (offset + size + 7) / 8;
 
     let mut current_offset = offset;
     let mut num_bits_remaining = size;
@@ -618,7 +613,28 @@ fn operations_test() {
 
     assert_eq!(operations(8, 1, Endianness::Big), None);
     assert_eq!(operations(3, 0, Endianness::Big), None);
-    assert_eq!(operations(3, 65, Endianness::Big), None);
+    assert_eq!(operations(3, 129, Endianness::Big), None);
+
+    let ops = operations(0, 128, Endianness::Big).unwrap();
+    assert_eq!(ops.len(), 16);
+    assert_eq!(
+        ops[0],
+        Op {
+            mask: 0b11111111,
+            shiftl: 120,
+            shiftr: 0,
+        }
+    );
+    let ops = operations(3, 128, Endianness::Little).unwrap();
+    assert_eq!(ops.len(), 17);
+    assert_eq!(
+        ops[16],
+        Op {
+            mask: 0b11100000,
+            shiftl: 125,
+            shiftr: 5,
+        }
+    );
 
     assert_eq!(
         operations(3, 33, Endianness::Big).unwrap(),
@@ -653,7 +669,7 @@ fn operations_test() {
 }
 
 /// Mask `bits` bits of a byte. eg. mask_high_bits(2) == 0b00000011
-fn mask_high_bits(mut bits: u64) -> u64 {
+fn mask_high_bits(mut bits: u128) -> u128 {
     let mut mask = 0;
     while bits > 0 {
         mask = mask | (1 << (bits - 1));
@@ -668,7 +684,7 @@ fn mask_high_bits(mut bits: u64) -> u64 {
 ///
 /// In the form of (bits to get, bits to set)
 pub fn to_mutator(ops: &[GetOperation]) -> Vec<SetOperation> {
-    fn num_bits_set(n: u8) -> u64 {
+    fn num_bits_set(n: u8) -> u128 {
         let mut count = 0;
         for i in 0..8 {
             if n & (1 << i) > 0 {
@@ -1072,10 +1088,148 @@ fn test_to_mutator() {
 
 /// Takes a set of operations to get a field in big endian, and converts them to get the field in
 /// little endian.
-fn to_little_endian(_ops: Vec<GetOperation>) -> Vec<GetOperation> {
-    let mut ops = _ops.clone();
-    for (op, be_op) in ops.iter_mut().zip(_ops.iter().rev()) {
-        op.shiftl = be_op.shiftl;
-    }
-    ops
+///
+/// Each byte holds the same bits of the field as it would for big endian, but the bits held by
+/// the first byte are the least significant rather than the most significant. For byte aligned
+/// fields this is the usual little endian byte order.
+fn to_little_endian(ops: Vec<GetOperation>) -> Vec<GetOperation> {
+    let mut position = 0;
+    ops.into_iter()
+        .map(|op| {
+            let le_op = GetOperation {
+                mask: op.mask,
+                shiftl: position,
+                shiftr: op.mask.trailing_zeros() as u8,
+            };
+            position += op.mask.count_ones() as u8;
+            le_op
+        })
+        .collect()
+}
+
+#[test]
+fn test_operations_span() {
+    type Op = GetOperation;
+
+    // 10 bits starting at the last bit of a byte span three bytes
+    assert_eq!(
+        operations(7, 10, Endianness::Big).unwrap(),
+        vec![
+            Op {
+                mask: 0b00000001,
+                shiftl: 9,
+                shiftr: 0,
+            },
+            Op {
+                mask: 0b11111111,
+                shiftl: 1,
+                shiftr: 0,
+            },
+            Op {
+                mask: 0b10000000,
+                shiftl: 0,
+                shiftr: 7,
+            }
+        ]
+    );
+    assert_eq!(operations(3, 63, Endianness::Big).unwrap().len(), 9);
+}
+
+#[test]
+fn test_to_little_endian() {
+    type Op = GetOperation;
+
+    // Single bytes are unaffected
+    assert_eq!(
+        operations(3, 4, Endianness::Little).unwrap(),
+        operations(3, 4, Endianness::Big).unwrap()
+    );
+    assert_eq!(
+        operations(0, 16, Endianness::Little).unwrap(),
+        vec![
+            Op {
+                mask: 0b11111111,
+                shiftl: 0,
+                shiftr: 0,
+            },
+            Op {
+                mask: 0b11111111,
+                shiftl: 8,
+                shiftr: 0,
+            }
+        ]
+    );
+    assert_eq!(
+        operations(0, 12, Endianness::Little).unwrap(),
+        vec![
+            Op {
+                mask: 0b11111111,
+                shiftl: 0,
+                shiftr: 0,
+            },
+            Op {
+                mask: 0b11110000,
+                shiftl: 8,
+                shiftr: 4,
+            }
+        ]
+    );
+    assert_eq!(
+        operations(4, 12, Endianness::Little).unwrap(),
+        vec![
+            Op {
+                mask: 0b00001111,
+                shiftl: 0,
+                shiftr: 0,
+            },
+            Op {
+                mask: 0b11111111,
+                shiftl: 4,
+                shiftr: 0,
+            }
+        ]
+    );
+    assert_eq!(
+        operations(3, 16, Endianness::Little).unwrap(),
+        vec![
+            Op {
+                mask: 0b00011111,
+                shiftl: 0,
+                shiftr: 0,
+            },
+            Op {
+                mask: 0b11111111,
+                shiftl: 5,
+                shiftr: 0,
+            },
+            Op {
+                mask: 0b11100000,
+                shiftl: 13,
+                shiftr: 5,
+            }
+        ]
+    );
+}
+
+#[test]
+fn test_to_little_endian_mutator() {
+    type Sop = SetOperation;
+
+    assert_eq!(
+        to_mutator(&operations(4, 12, Endianness::Little).unwrap()),
+        vec![
+            Sop {
+                save_mask: 0b11110000,
+                value_mask: 0b1111,
+                shiftl: 0,
+                shiftr: 0,
+            },
+            Sop {
+                save_mask: 0b00000000,
+                value_mask: 0b111111110000,
+                shiftl: 0,
+                shiftr: 4,
+            }
+        ]
+    );
 }
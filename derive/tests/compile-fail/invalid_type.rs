@@ -10,7 +10,7 @@ use libpacket_derive::Packet;
 
 #[derive(Packet)]
 pub struct InvalidType {
-    pub field: String,
+    pub field: bool,
     #[payload]
     pub payload: Vec<u8>,
 }
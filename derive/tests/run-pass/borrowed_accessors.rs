@@ -0,0 +1,93 @@
+use libpacket_core::types::*;
+use libpacket_core::{FromPacket, Packet};
+use libpacket_derive::Packet;
+
+#[derive(Debug, Packet)]
+pub struct Tlv {
+    ty: u8,
+    length: u8,
+    #[length = "length"]
+    #[payload]
+    value: Vec<u8>,
+}
+
+#[derive(Debug, Packet)]
+pub struct Container {
+    id_len: u8,
+    #[length = "id_len"]
+    id: Vec<u8>,
+    tlvs_len: u8,
+    #[length = "tlvs_len"]
+    tlvs: Vec<Tlv>,
+    #[payload]
+    payload: Vec<u8>,
+}
+
+#[derive(Debug, Packet)]
+pub struct Words {
+    count: u8,
+    #[length = "count * 2"]
+    words: Vec<u16be>,
+    skip: u8,
+    #[length = "skip"]
+    skipped: Vec<u8>,
+    #[length = "2"]
+    tail: Vec<u8>,
+    #[payload]
+    payload: Vec<u8>,
+}
+
+fn container_id(data: &[u8]) -> &[u8] {
+    ContainerPacket::new(data).unwrap().get_id_borrowed().unwrap()
+}
+
+fn main() {
+    let data = [
+        3, 0xaa, 0xbb, 0xcc, /* id */
+        7, 1, 2, 0x10, 0x11, 2, 1, 0x20, /* tlvs */
+        0xff, 0xfe, /* payload */
+    ];
+    let packet = ContainerPacket::new(&data[..]).unwrap();
+
+    // Byte fields borrow from the underlying buffer.
+    let id: &[u8] = packet.get_id();
+    assert_eq!(id, &[0xaa, 0xbb, 0xcc]);
+    assert_eq!(id.as_ptr(), data[1..].as_ptr());
+    assert_eq!(packet.get_id(), packet.get_id_raw());
+    assert_eq!(packet.get_payload(), packet.payload());
+    assert_eq!(container_id(&data[..]), &[0xaa, 0xbb, 0xcc]);
+
+    // Owned copies are opt-in.
+    let id: Vec<u8> = packet.get_id_vec();
+    assert_eq!(id, vec![0xaa, 0xbb, 0xcc]);
+    assert_eq!(ContainerPacket::owned(data.to_vec()).unwrap().get_id_borrowed(), None);
+
+    // Sub-packets are parsed lazily.
+    let mut tlvs = packet.get_tlvs_iter();
+    let first = tlvs.next().unwrap();
+    assert_eq!(first.get_ty(), 1);
+    assert_eq!(first.get_value(), &[0x10, 0x11]);
+    let second = tlvs.next().unwrap();
+    assert_eq!(second.get_ty(), 2);
+    assert_eq!(second.get_value(), &[0x20]);
+    assert!(tlvs.next().is_none());
+
+    // The owned representation is still available on request.
+    let owned = packet.from_packet();
+    assert_eq!(owned.id, vec![0xaa, 0xbb, 0xcc]);
+    assert_eq!(owned.tlvs.len(), 2);
+    assert_eq!(owned.tlvs[0].value, vec![0x10, 0x11]);
+    assert_eq!(owned.payload, vec![0xff, 0xfe]);
+
+    // Wider vectors are still decoded into owned values.
+    let data = [2, 0x00, 0x01, 0x12, 0x34, 9];
+    let words = WordsPacket::new(&data[..]).unwrap();
+    let decoded: Vec<u16> = words.get_words();
+    assert_eq!(decoded, vec![0x0001, 0x1234]);
+
+    // Fields starting past the end of a truncated buffer are empty.
+    assert!(words.get_skipped().is_empty());
+    assert!(words.get_tail().is_empty());
+    assert!(words.get_tail_raw().is_empty());
+    assert_eq!(words.get_tail_borrowed(), Some(&[][..]));
+}
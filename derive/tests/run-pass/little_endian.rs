@@ -0,0 +1,199 @@
+use libpacket_core::types::*;
+use libpacket_derive::Packet;
+
+macro_rules! le_width {
+    ($m:ident, $ty:ident, $bits:expr) => {
+        mod $m {
+            use libpacket_core::types::*;
+            use libpacket_derive::Packet;
+
+            #[derive(Packet)]
+            pub struct Aligned {
+                value: $ty,
+                #[payload]
+                payload: Vec<u8>,
+            }
+
+            #[derive(Packet)]
+            pub struct Unaligned {
+                head: u3,
+                value: $ty,
+                tail: u5,
+                #[payload]
+                payload: Vec<u8>,
+            }
+
+            pub fn check() {
+                let bits: usize = $bits;
+                let max = u64::MAX >> (64 - bits);
+                for &pattern in &[0x0123_4567_89ab_cdef, 0xfedc_ba98_7654_3210, u64::MAX, 1] {
+                    let value = (pattern & max) as $ty;
+
+                    let mut buf = [0; 9];
+                    let mut packet = MutableAlignedPacket::new(&mut buf[..]).unwrap();
+                    packet.set_value(value);
+                    assert_eq!(packet.get_value(), value);
+                    if bits % 8 == 0 {
+                        assert_eq!(buf[..bits / 8], (pattern & max).to_le_bytes()[..bits / 8]);
+                    }
+
+                    let mut buf = [0xff; 10];
+                    let mut packet = MutableUnalignedPacket::new(&mut buf[..]).unwrap();
+                    packet.set_value(value);
+                    assert_eq!(packet.get_value(), value);
+                    assert_eq!(packet.get_head(), 0b111);
+                    assert_eq!(packet.get_tail(), 0b11111);
+                    packet.set_head(0);
+                    packet.set_tail(0);
+                    assert_eq!(packet.get_value(), value);
+                    packet.set_value(0);
+                    let used = (bits + 8) / 8;
+                    assert!(buf[..used].iter().all(|b| *b == 0));
+                    if bits % 8 != 0 {
+                        assert_eq!(buf[used], 0xff >> (bits % 8));
+                    }
+                    let size = UnalignedPacket::minimum_packet_size();
+                    assert!(buf[size..].iter().all(|b| *b == 0xff));
+                }
+            }
+        }
+    };
+}
+
+le_width!(w9, u9le, 9);
+le_width!(w10, u10le, 10);
+le_width!(w11, u11le, 11);
+le_width!(w12, u12le, 12);
+le_width!(w13, u13le, 13);
+le_width!(w14, u14le, 14);
+le_width!(w15, u15le, 15);
+le_width!(w16, u16le, 16);
+le_width!(w17, u17le, 17);
+le_width!(w18, u18le, 18);
+le_width!(w19, u19le, 19);
+le_width!(w20, u20le, 20);
+le_width!(w21, u21le, 21);
+le_width!(w22, u22le, 22);
+le_width!(w23, u23le, 23);
+le_width!(w24, u24le, 24);
+le_width!(w25, u25le, 25);
+le_width!(w26, u26le, 26);
+le_width!(w27, u27le, 27);
+le_width!(w28, u28le, 28);
+le_width!(w29, u29le, 29);
+le_width!(w30, u30le, 30);
+le_width!(w31, u31le, 31);
+le_width!(w32, u32le, 32);
+le_width!(w33, u33le, 33);
+le_width!(w34, u34le, 34);
+le_width!(w35, u35le, 35);
+le_width!(w36, u36le, 36);
+le_width!(w37, u37le, 37);
+le_width!(w38, u38le, 38);
+le_width!(w39, u39le, 39);
+le_width!(w40, u40le, 40);
+le_width!(w41, u41le, 41);
+le_width!(w42, u42le, 42);
+le_width!(w43, u43le, 43);
+le_width!(w44, u44le, 44);
+le_width!(w45, u45le, 45);
+le_width!(w46, u46le, 46);
+le_width!(w47, u47le, 47);
+le_width!(w48, u48le, 48);
+le_width!(w49, u49le, 49);
+le_width!(w50, u50le, 50);
+le_width!(w51, u51le, 51);
+le_width!(w52, u52le, 52);
+le_width!(w53, u53le, 53);
+le_width!(w54, u54le, 54);
+le_width!(w55, u55le, 55);
+le_width!(w56, u56le, 56);
+le_width!(w57, u57le, 57);
+le_width!(w58, u58le, 58);
+le_width!(w59, u59le, 59);
+le_width!(w60, u60le, 60);
+le_width!(w61, u61le, 61);
+le_width!(w62, u62le, 62);
+le_width!(w63, u63le, 63);
+le_width!(w64, u64le, 64);
+
+#[derive(Packet)]
+pub struct Words {
+    count: u8,
+    #[length = "count * 2"]
+    words: Vec<u16le>,
+    #[payload]
+    payload: Vec<u8>,
+}
+
+fn main() {
+    w9::check();
+    w10::check();
+    w11::check();
+    w12::check();
+    w13::check();
+    w14::check();
+    w15::check();
+    w16::check();
+    w17::check();
+    w18::check();
+    w19::check();
+    w20::check();
+    w21::check();
+    w22::check();
+    w23::check();
+    w24::check();
+    w25::check();
+    w26::check();
+    w27::check();
+    w28::check();
+    w29::check();
+    w30::check();
+    w31::check();
+    w32::check();
+    w33::check();
+    w34::check();
+    w35::check();
+    w36::check();
+    w37::check();
+    w38::check();
+    w39::check();
+    w40::check();
+    w41::check();
+    w42::check();
+    w43::check();
+    w44::check();
+    w45::check();
+    w46::check();
+    w47::check();
+    w48::check();
+    w49::check();
+    w50::check();
+    w51::check();
+    w52::check();
+    w53::check();
+    w54::check();
+    w55::check();
+    w56::check();
+    w57::check();
+    w58::check();
+    w59::check();
+    w60::check();
+    w61::check();
+    w62::check();
+    w63::check();
+    w64::check();
+
+    // Bits held by the first byte are the least significant.
+    let data = [0b1010_0101, 0b1100_0011, 0b1110_0000];
+    let packet = w16::UnalignedPacket::new(&data[..]).unwrap();
+    assert_eq!(packet.get_head(), 0b101);
+    assert_eq!(packet.get_value(), 0b111_11000011_00101);
+    assert_eq!(packet.get_tail(), 0);
+
+    let mut data = [2, 0x01, 0x02, 0x03, 0x04];
+    let mut packet = MutableWordsPacket::new(&mut data[..]).unwrap();
+    assert_eq!(packet.get_words(), vec![0x0201, 0x0403]);
+    packet.set_words(&[0x1234, 0x5678]);
+    assert_eq!(data, [2, 0x34, 0x12, 0x78, 0x56]);
+}
@@ -0,0 +1,67 @@
+use libpacket_core::types::*;
+use libpacket_core::{FromPacket, Packet, PacketSize};
+use libpacket_derive::Packet;
+
+#[derive(Debug, Packet)]
+pub struct Strings {
+    name_len: u8,
+    #[length = "name_len"]
+    name: String,
+    label: pstring,
+    path: cstring,
+    flags: u16be,
+    #[payload]
+    payload: Vec<u8>,
+}
+
+fn main() {
+    assert_eq!(StringsPacket::minimum_packet_size(), 5);
+
+    let data = [
+        3, b'f', b'o', b'o', /* name */
+        2, b'b', b'a', /* label */
+        b'/', b'x', 0, /* path */
+        0x12, 0x34, /* flags */
+        0xff, /* payload */
+    ];
+    let packet = StringsPacket::new(&data[..]).unwrap();
+    assert_eq!(packet.get_name(), Ok("foo"));
+    assert_eq!(packet.get_label(), Ok("ba"));
+    assert_eq!(packet.get_path(), Ok("/x"));
+    assert_eq!(packet.get_path_raw(), b"/x");
+    assert_eq!(packet.get_flags(), 0x1234);
+    assert_eq!(packet.payload(), &[0xff]);
+    assert_eq!(packet.packet_size(), data.len() - 1);
+
+    let owned = packet.from_packet();
+    assert_eq!(owned.name, "foo");
+    assert_eq!(owned.label, "ba");
+    assert_eq!(owned.path, "/x");
+    assert_eq!(StringsPacket::packet_size(&owned), data.len());
+
+    let mut buf = vec![0; StringsPacket::packet_size(&owned)];
+    MutableStringsPacket::new(&mut buf[..])
+        .unwrap()
+        .populate(&owned);
+    assert_eq!(buf, data);
+
+    let mut buf = [0; 5];
+    let mut packet = MutableStringsPacket::new(&mut buf[..]).unwrap();
+    packet.set_path("");
+    packet.set_flags(0xabcd);
+    assert_eq!(packet.get_name(), Ok(""));
+    assert_eq!(packet.get_label(), Ok(""));
+    assert_eq!(buf, [0, 0, 0, 0xab, 0xcd]);
+
+    // Invalid UTF-8 is reported rather than replaced.
+    let data = [1, 0xff, 0, 0, 0, 0];
+    let packet = StringsPacket::new(&data[..]).unwrap();
+    assert!(packet.get_name().is_err());
+    assert_eq!(packet.from_packet().name, "\u{fffd}");
+
+    // Truncated strings are clamped to the buffer.
+    let data = [0, 9, b'a', b'b', b'c'];
+    let packet = StringsPacket::new(&data[..]).unwrap();
+    assert_eq!(packet.get_label(), Ok("abc"));
+    assert_eq!(packet.get_path(), Ok(""));
+}
@@ -0,0 +1,51 @@
+use libpacket_core::types::*;
+use libpacket_derive::Packet;
+use std::net::Ipv6Addr;
+
+#[derive(Packet)]
+pub struct Wide {
+    flags: u4,
+    timestamp: u80be,
+    counter: u100le,
+    token: u128be,
+    #[construct_from(u128be)]
+    addr: Ipv6Addr,
+    #[payload]
+    payload: Vec<u8>,
+}
+
+fn main() {
+    assert_eq!(WidePacket::minimum_packet_size(), 55);
+
+    let timestamp: u128 = 0x1234_5678_9abc_def0_1122;
+    let counter: u128 = 0xf_0123_4567_89ab_cdef_0123_4567;
+    let token: u128 = 0x0011_2233_4455_6677_8899_aabb_ccdd_eeff;
+    let addr: Ipv6Addr = "2001:db8::1".parse().unwrap();
+
+    let mut buf = [0xff; 56];
+    let mut packet = MutableWidePacket::new(&mut buf[..]).unwrap();
+    packet.set_timestamp(timestamp);
+    packet.set_counter(counter);
+    packet.set_token(token);
+    packet.set_addr(addr);
+    assert_eq!(packet.get_flags(), 0xf);
+    assert_eq!(packet.get_timestamp(), timestamp);
+    assert_eq!(packet.get_counter(), counter);
+    assert_eq!(packet.get_token(), token);
+    assert_eq!(packet.get_addr(), addr);
+
+    // The 80-bit timestamp starts half way through the first byte.
+    assert_eq!(&buf[..10], &[0xf1, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0x01, 0x12]);
+    // The little-endian counter starts with its least significant nibble.
+    assert_eq!(buf[10], 0x27);
+    assert_eq!(&buf[23..39], &token.to_be_bytes());
+    assert_eq!(&buf[39..55], &addr.octets());
+    assert_eq!(buf[55], 0xff);
+
+    let mut packet = MutableWidePacket::new(&mut buf[..]).unwrap();
+    packet.set_counter(u128::MAX >> 28);
+    packet.set_flags(0);
+    assert_eq!(packet.get_counter(), u128::MAX >> 28);
+    assert_eq!(packet.get_timestamp(), timestamp);
+    assert_eq!(packet.get_token(), token);
+}
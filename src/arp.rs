@@ -8,7 +8,7 @@
 
 //! ARP packet abstraction.
 
-use crate::ethernet::EtherType;
+use crate::ethernet::{EtherType, EtherTypes};
 use crate::{MacAddr, Packet, PrimitiveValues};
 use std::net::Ipv4Addr;
 
@@ -41,6 +41,18 @@ pub mod ArpOperations {
 
     /// ARP reply
     pub const Reply: ArpOperation = ArpOperation(2);
+
+    /// RARP request, sent with `EtherTypes::Rarp`
+    pub const ReverseRequest: ArpOperation = ArpOperation(3);
+
+    /// RARP reply, sent with `EtherTypes::Rarp`
+    pub const ReverseReply: ArpOperation = ArpOperation(4);
+
+    /// Inverse ARP request
+    pub const InverseRequest: ArpOperation = ArpOperation(8);
+
+    /// Inverse ARP reply
+    pub const InverseReply: ArpOperation = ArpOperation(9);
 }
 
 /// Represents the ARP hardware types.
@@ -69,10 +81,19 @@ pub mod ArpHardwareTypes {
 
     /// Ethernet
     pub const Ethernet: ArpHardwareType = ArpHardwareType(1);
+
+    /// IEEE 802 networks, such as Token Ring
+    pub const Ieee802: ArpHardwareType = ArpHardwareType(6);
+
+    /// Fibre Channel
+    pub const FibreChannel: ArpHardwareType = ArpHardwareType(18);
+
+    /// InfiniBand, with 20 byte hardware addresses
+    pub const InfiniBand: ArpHardwareType = ArpHardwareType(32);
 }
 
 /// Represents an ARP Packet.
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 #[allow(non_snake_case)]
 pub struct Arp {
     #[construct_with(u16)]
@@ -82,7 +103,8 @@ pub struct Arp {
     // We completely ignore hw_addr_len and
     // proto_addr_len and use values for
     // Ipv4 on top of Ethernet as it's the
-    // most common use case
+    // most common use case, see `GenericArp`
+    // for other address lengths
     pub hw_addr_len: u8,
     pub proto_addr_len: u8,
     #[construct_with(u16)]
@@ -99,3 +121,76 @@ pub struct Arp {
     #[length = "0"]
     pub payload: Vec<u8>,
 }
+
+/// Represents an ARP Packet with addresses of any length, as given by its
+/// `hw_addr_len` and `proto_addr_len` fields.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct GenericArp {
+    #[construct_with(u16)]
+    pub hardware_type: ArpHardwareType,
+    #[construct_with(u16)]
+    pub protocol_type: EtherType,
+    pub hw_addr_len: u8,
+    pub proto_addr_len: u8,
+    #[construct_with(u16)]
+    pub operation: ArpOperation,
+    #[length = "hw_addr_len"]
+    pub sender_hw_addr: Vec<u8>,
+    #[length = "proto_addr_len"]
+    pub sender_proto_addr: Vec<u8>,
+    #[length = "hw_addr_len"]
+    pub target_hw_addr: Vec<u8>,
+    #[length = "proto_addr_len"]
+    pub target_proto_addr: Vec<u8>,
+    #[payload]
+    #[length = "0"]
+    pub payload: Vec<u8>,
+}
+
+impl<'p> GenericArpPacket<'p> {
+    /// The packet as an `ArpPacket`, if its addresses are MAC and IPv4
+    /// addresses.
+    pub fn ethernet_ipv4(&self) -> Option<ArpPacket<'_>> {
+        if self.get_protocol_type() == EtherTypes::Ipv4
+            && self.get_hw_addr_len() == 6
+            && self.get_proto_addr_len() == 4
+        {
+            ArpPacket::new(self.packet())
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generic_lengths() {
+        let mut ethernet = vec![0x00, 0x01, 0x08, 0x00, 0x06, 0x04, 0x00, 0x02];
+        ethernet.extend_from_slice(&[0x02, 0, 0, 0, 0, 1, 10, 0, 0, 1]);
+        ethernet.extend_from_slice(&[0x02, 0, 0, 0, 0, 2, 10, 0, 0, 2]);
+        let arp = GenericArpPacket::new(&ethernet).unwrap();
+        assert_eq!(arp.get_sender_proto_addr_raw(), &[10, 0, 0, 1]);
+        let typed = arp.ethernet_ipv4().unwrap();
+        assert_eq!(typed.get_operation(), ArpOperations::Reply);
+        assert_eq!(typed.get_sender_hw_addr(), MacAddr(2, 0, 0, 0, 0, 1));
+        assert_eq!(typed.get_target_proto_addr(), Ipv4Addr::new(10, 0, 0, 2));
+
+        // An IPoIB request, with 20 byte hardware addresses.
+        let mut infiniband = vec![0x00, 0x20, 0x08, 0x00, 0x14, 0x04, 0x00, 0x01];
+        infiniband.extend_from_slice(&[0xaa; 20]);
+        infiniband.extend_from_slice(&[10, 0, 0, 1]);
+        infiniband.extend_from_slice(&[0x00; 20]);
+        infiniband.extend_from_slice(&[10, 0, 0, 2]);
+        let arp = GenericArpPacket::new(&infiniband).unwrap();
+        assert_eq!(arp.get_hardware_type(), ArpHardwareTypes::InfiniBand);
+        assert_eq!(arp.get_sender_hw_addr_raw(), &[0xaa; 20][..]);
+        assert_eq!(arp.get_sender_proto_addr_raw(), &[10, 0, 0, 1]);
+        assert_eq!(arp.get_target_hw_addr_raw(), &[0x00; 20][..]);
+        assert_eq!(arp.get_target_proto_addr_raw(), &[10, 0, 0, 2]);
+        assert!(arp.ethernet_ipv4().is_none());
+        assert!(GenericArpPacket::new(&infiniband[..7]).is_none());
+    }
+}
@@ -12,7 +12,7 @@ use crate::{MacAddr, Packet, PrimitiveValues};
 use std::fmt;
 
 /// Represents an Ethernet packet.
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Ethernet {
     #[construct_with(u8, u8, u8, u8, u8, u8)]
     pub destination: MacAddr,
@@ -118,6 +118,12 @@ impl EtherType {
     pub fn new(val: u16) -> EtherType {
         EtherType(val)
     }
+
+    /// Whether the value is an IEEE 802.3 length rather than a type, making the payload an LLC
+    /// frame (see `llc`).
+    pub fn is_length(&self) -> bool {
+        self.0 <= 1500
+    }
 }
 
 impl PrimitiveValues for EtherType {
@@ -34,7 +34,7 @@ use crate::{types::*, Packet};
 ///
 /// Note that routing information from RFC 1701 is not implemented, packets
 /// with `routing_present` true will currently cause a panic.
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Gre {
     pub checksum_present: u1,
     pub routing_present: u1,
@@ -60,7 +60,7 @@ pub struct Gre {
 }
 
 /// `u16be`, but we can't use that directly in a `Vec` :(
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct U16BE {
     number: u16be,
     #[length = "0"]
@@ -69,7 +69,7 @@ pub struct U16BE {
 }
 
 /// `u32be`, but we can't use that directly in a `Vec` :(
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct U32BE {
     number: u32be,
     #[length = "0"]
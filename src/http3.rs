@@ -0,0 +1,422 @@
+//! HTTP/3 frames (RFC 9114) carried in reassembled QUIC stream data.
+//!
+//! Request streams and the control stream carry a sequence of frames, see `Frames`.
+//! Unidirectional streams start with a stream type, see `UniStream`. The field sections of
+//! HEADERS and PUSH_PROMISE frames are decoded with `qpack::FieldSection`.
+
+use crate::quic::split_varint;
+use crate::PrimitiveValues;
+
+pub mod huffman;
+pub mod qpack;
+
+/// An HTTP/3 frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Frame<'a> {
+    Data(&'a [u8]),
+    /// A QPACK encoded field section.
+    Headers(&'a [u8]),
+    CancelPush(u64),
+    Settings(Settings<'a>),
+    PushPromise {
+        push_id: u64,
+        /// A QPACK encoded field section.
+        field_section: &'a [u8],
+    },
+    Goaway(u64),
+    MaxPushId(u64),
+    /// A frame of an unknown or reserved type, which receivers ignore.
+    Unknown {
+        ty: FrameType,
+        payload: &'a [u8],
+    },
+}
+
+impl<'a> Frame<'a> {
+    /// Parses the frame at the start of `buf`, returning it and the bytes following it.
+    ///
+    /// Returns `None` if the frame is incomplete or malformed.
+    pub fn parse(buf: &'a [u8]) -> Option<(Self, &'a [u8])> {
+        let (ty, rest) = split_varint(buf)?;
+        let (len, rest) = split_varint(rest)?;
+        if (rest.len() as u64) < len {
+            return None;
+        }
+        let (payload, rest) = rest.split_at(len as usize);
+        let single_varint = || match split_varint(payload)? {
+            (value, []) => Some(value),
+            _ => None,
+        };
+        let frame = match FrameType(ty) {
+            FrameTypes::Data => Self::Data(payload),
+            FrameTypes::Headers => Self::Headers(payload),
+            FrameTypes::CancelPush => Self::CancelPush(single_varint()?),
+            FrameTypes::Settings => Self::Settings(Settings::new(payload)?),
+            FrameTypes::PushPromise => {
+                let (push_id, field_section) = split_varint(payload)?;
+                Self::PushPromise {
+                    push_id,
+                    field_section,
+                }
+            }
+            FrameTypes::Goaway => Self::Goaway(single_varint()?),
+            FrameTypes::MaxPushId => Self::MaxPushId(single_varint()?),
+            ty => Self::Unknown { ty, payload },
+        };
+        Some((frame, rest))
+    }
+
+    /// The frame type.
+    pub fn ty(&self) -> FrameType {
+        match self {
+            Self::Data(_) => FrameTypes::Data,
+            Self::Headers(_) => FrameTypes::Headers,
+            Self::CancelPush(_) => FrameTypes::CancelPush,
+            Self::Settings(_) => FrameTypes::Settings,
+            Self::PushPromise { .. } => FrameTypes::PushPromise,
+            Self::Goaway(_) => FrameTypes::Goaway,
+            Self::MaxPushId(_) => FrameTypes::MaxPushId,
+            Self::Unknown { ty, .. } => *ty,
+        }
+    }
+}
+
+impl<'a> std::fmt::Display for Frame<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.ty())
+    }
+}
+
+/// Iterator over the frames of a stream.
+///
+/// Iteration stops at the first incomplete or malformed frame, which `rest` returns.
+#[derive(Clone, Debug)]
+pub struct Frames<'a>(&'a [u8]);
+
+impl<'a> Frames<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self(data)
+    }
+
+    /// The data following the frames returned so far.
+    pub fn rest(&self) -> &'a [u8] {
+        self.0
+    }
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = Frame<'a>;
+
+    fn next(&mut self) -> Option<Frame<'a>> {
+        let (frame, rest) = Frame::parse(self.0)?;
+        self.0 = rest;
+        Some(frame)
+    }
+}
+
+/// The payload of a SETTINGS frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Settings<'a>(&'a [u8]);
+
+impl<'a> Settings<'a> {
+    /// Checks that `payload` is a sequence of identifier and value pairs.
+    pub fn new(payload: &'a [u8]) -> Option<Self> {
+        let mut rest = payload;
+        while !rest.is_empty() {
+            let (_, r) = split_varint(rest)?;
+            let (_, r) = split_varint(r)?;
+            rest = r;
+        }
+        Some(Self(payload))
+    }
+
+    /// The settings in the order they were sent.
+    pub fn iter(&self) -> impl Iterator<Item = (SettingId, u64)> + 'a {
+        let mut rest = self.0;
+        std::iter::from_fn(move || {
+            let (id, r) = split_varint(rest)?;
+            let (value, r) = split_varint(r)?;
+            rest = r;
+            Some((SettingId(id), value))
+        })
+    }
+
+    /// The value of setting `id`, if sent.
+    pub fn get(&self, id: SettingId) -> Option<u64> {
+        self.iter()
+            .find(|&(setting, _)| setting == id)
+            .map(|(_, value)| value)
+    }
+}
+
+/// The start of a unidirectional stream.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UniStream<'a> {
+    pub ty: StreamType,
+    /// The push id of a push stream.
+    pub push_id: Option<u64>,
+    /// The data following the header. Control and push streams carry `Frames`, QPACK streams
+    /// carry encoder or decoder instructions.
+    pub data: &'a [u8],
+}
+
+impl<'a> UniStream<'a> {
+    /// Parses the header of a unidirectional stream, returning `None` if it is incomplete.
+    pub fn new(data: &'a [u8]) -> Option<Self> {
+        let (ty, data) = split_varint(data)?;
+        let ty = StreamType(ty);
+        let (push_id, data) = if ty == StreamTypes::Push {
+            let (push_id, data) = split_varint(data)?;
+            (Some(push_id), data)
+        } else {
+            (None, data)
+        };
+        Some(Self { ty, push_id, data })
+    }
+}
+
+/// Whether `value` is reserved to exercise the handling of unknown values (RFC 9114 section 7.2.8).
+fn is_reserved(value: u64) -> bool {
+    value >= 0x21 && (value - 0x21).is_multiple_of(0x1f)
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod FrameTypes {
+    use super::FrameType;
+
+    pub const Data: FrameType = FrameType(0x00);
+    pub const Headers: FrameType = FrameType(0x01);
+    pub const CancelPush: FrameType = FrameType(0x03);
+    pub const Settings: FrameType = FrameType(0x04);
+    pub const PushPromise: FrameType = FrameType(0x05);
+    pub const Goaway: FrameType = FrameType(0x07);
+    pub const MaxPushId: FrameType = FrameType(0x0d);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FrameType(pub u64);
+
+impl FrameType {
+    /// Construct a new `FrameType`.
+    pub fn new(val: u64) -> Self {
+        Self(val)
+    }
+
+    /// Whether the type is reserved for greasing.
+    pub fn is_reserved(&self) -> bool {
+        is_reserved(self.0)
+    }
+}
+
+impl PrimitiveValues for FrameType {
+    type T = (u64,);
+    fn to_primitive_values(&self) -> Self::T {
+        (self.0,)
+    }
+}
+
+impl std::fmt::Display for FrameType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let s = match *self {
+            FrameTypes::Data => "data",
+            FrameTypes::Headers => "headers",
+            FrameTypes::CancelPush => "cancel-push",
+            FrameTypes::Settings => "settings",
+            FrameTypes::PushPromise => "push-promise",
+            FrameTypes::Goaway => "goaway",
+            FrameTypes::MaxPushId => "max-push-id",
+            x if x.is_reserved() => "reserved",
+            _ => "unknown",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod StreamTypes {
+    use super::StreamType;
+
+    pub const Control: StreamType = StreamType(0x00);
+    pub const Push: StreamType = StreamType(0x01);
+    pub const QpackEncoder: StreamType = StreamType(0x02);
+    pub const QpackDecoder: StreamType = StreamType(0x03);
+}
+
+/// The type of a unidirectional stream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StreamType(pub u64);
+
+impl StreamType {
+    /// Construct a new `StreamType`.
+    pub fn new(val: u64) -> Self {
+        Self(val)
+    }
+
+    /// Whether the type is reserved for greasing.
+    pub fn is_reserved(&self) -> bool {
+        is_reserved(self.0)
+    }
+}
+
+impl PrimitiveValues for StreamType {
+    type T = (u64,);
+    fn to_primitive_values(&self) -> Self::T {
+        (self.0,)
+    }
+}
+
+impl std::fmt::Display for StreamType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let s = match *self {
+            StreamTypes::Control => "control",
+            StreamTypes::Push => "push",
+            StreamTypes::QpackEncoder => "qpack-encoder",
+            StreamTypes::QpackDecoder => "qpack-decoder",
+            x if x.is_reserved() => "reserved",
+            _ => "unknown",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod SettingIds {
+    use super::SettingId;
+
+    pub const QpackMaxTableCapacity: SettingId = SettingId(0x01);
+    pub const MaxFieldSectionSize: SettingId = SettingId(0x06);
+    pub const QpackBlockedStreams: SettingId = SettingId(0x07);
+    pub const EnableConnectProtocol: SettingId = SettingId(0x08);
+    pub const H3Datagram: SettingId = SettingId(0x33);
+}
+
+/// The identifier of a setting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SettingId(pub u64);
+
+impl SettingId {
+    /// Construct a new `SettingId`.
+    pub fn new(val: u64) -> Self {
+        Self(val)
+    }
+
+    /// Whether the identifier is reserved for greasing.
+    pub fn is_reserved(&self) -> bool {
+        is_reserved(self.0)
+    }
+}
+
+impl PrimitiveValues for SettingId {
+    type T = (u64,);
+    fn to_primitive_values(&self) -> Self::T {
+        (self.0,)
+    }
+}
+
+impl std::fmt::Display for SettingId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let s = match *self {
+            SettingIds::QpackMaxTableCapacity => "qpack-max-table-capacity",
+            SettingIds::MaxFieldSectionSize => "max-field-section-size",
+            SettingIds::QpackBlockedStreams => "qpack-blocked-streams",
+            SettingIds::EnableConnectProtocol => "enable-connect-protocol",
+            SettingIds::H3Datagram => "h3-datagram",
+            x if x.is_reserved() => "reserved",
+            _ => "unknown",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::qpack::FieldSection;
+    use super::*;
+
+    #[test]
+    fn control_stream() {
+        let data = [
+            0x00, /* control stream */
+            0x04, 0x09, /* settings */
+            0x01, 0x40, 0x64, /* qpack max table capacity 100 */
+            0x07, 0x10, /* qpack blocked streams 16 */
+            0x21, 0x00, /* reserved */
+            0x33, 0x01, /* h3 datagram */
+            0x07, 0x01, 0x04, /* goaway 4 */
+            0x0d, 0x02, 0x40, /* truncated max push id */
+        ];
+        let stream = UniStream::new(&data).unwrap();
+        assert_eq!(stream.ty, StreamTypes::Control);
+        assert_eq!(stream.push_id, None);
+        let mut frames = Frames::new(stream.data);
+        let settings = match frames.next() {
+            Some(Frame::Settings(settings)) => settings,
+            f => panic!("expected settings, got {:?}", f),
+        };
+        assert_eq!(settings.get(SettingIds::QpackMaxTableCapacity), Some(100));
+        assert_eq!(settings.get(SettingIds::MaxFieldSectionSize), None);
+        let ids: Vec<_> = settings.iter().map(|(id, _)| id.to_string()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "qpack-max-table-capacity",
+                "qpack-blocked-streams",
+                "reserved",
+                "h3-datagram"
+            ]
+        );
+        assert_eq!(frames.next(), Some(Frame::Goaway(4)));
+        assert_eq!(frames.next(), None);
+        assert_eq!(frames.rest(), &[0x0d, 0x02, 0x40]);
+    }
+
+    #[test]
+    fn push_stream() {
+        let data = [0x01, 0x02, 0x00, 0x03, b'a', b'b', b'c'];
+        let stream = UniStream::new(&data).unwrap();
+        assert_eq!(stream.ty.to_string(), "push");
+        assert_eq!(stream.push_id, Some(2));
+        let frames: Vec<_> = Frames::new(stream.data).collect();
+        assert_eq!(frames, vec![Frame::Data(b"abc")]);
+    }
+
+    #[test]
+    fn request_stream() {
+        let data = [
+            0x01, 0x05, /* headers */
+            0x00, 0x00, 0xd1, 0xd7, 0xc1, /* GET https / */
+            0x40, 0x40, 0x01, 0xff, /* reserved frame type 0x40 */
+            0x00, 0x02, b'h', b'i', /* data */
+        ];
+        let frames: Vec<_> = Frames::new(&data).collect();
+        assert_eq!(frames.len(), 3);
+        let section = match frames[0] {
+            Frame::Headers(section) => FieldSection::decode(section).unwrap(),
+            ref f => panic!("expected headers, got {}", f),
+        };
+        assert_eq!(section.get(b":method"), Some(&b"GET"[..]));
+        assert_eq!(section.get(b":path"), Some(&b"/"[..]));
+        assert_eq!(
+            frames[1],
+            Frame::Unknown {
+                ty: FrameType(0x40),
+                payload: &[0xff]
+            }
+        );
+        assert_eq!(frames[1].to_string(), "reserved");
+        assert_eq!(frames[2], Frame::Data(b"hi"));
+    }
+
+    #[test]
+    fn malformed_frames() {
+        // goaway with trailing data
+        assert!(Frame::parse(&[0x07, 0x02, 0x01, 0x01]).is_none());
+        // settings with a missing value
+        assert!(Frame::parse(&[0x04, 0x01, 0x01]).is_none());
+        // push promise without a push id
+        assert!(Frame::parse(&[0x05, 0x00]).is_none());
+    }
+}
@@ -0,0 +1,175 @@
+//! The Huffman code of HPACK (RFC 7541 appendix B), used by QPACK string literals.
+//!
+//! The code is canonical, so it is fully described by the length of the code of each symbol.
+
+/// The length in bits of the code of each byte, followed by the end-of-string symbol.
+const CODE_LENGTHS: [u8; 257] = [
+    13, 23, 28, 28, 28, 28, 28, 28, 28, 24, 30, 28, 28, 30, 28, 28, 28, 28, 28, 28, 28, 28, 30, 28,
+    28, 28, 28, 28, 28, 28, 28, 28, 6, 10, 10, 12, 13, 6, 8, 11, 10, 10, 8, 11, 8, 6, 6, 6, 5, 5,
+    5, 6, 6, 6, 6, 6, 6, 6, 7, 8, 15, 6, 12, 10, 13, 6, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7,
+    7, 7, 7, 7, 7, 7, 7, 7, 8, 7, 8, 13, 19, 13, 14, 6, 15, 5, 6, 5, 6, 5, 6, 6, 6, 5, 7, 7, 6, 6,
+    6, 5, 6, 7, 6, 5, 5, 6, 7, 7, 7, 7, 7, 15, 11, 14, 13, 28, 20, 22, 20, 20, 22, 22, 22, 23, 22,
+    23, 23, 23, 23, 23, 24, 23, 24, 24, 22, 23, 24, 23, 23, 23, 23, 21, 22, 23, 22, 23, 23, 24, 22,
+    21, 20, 22, 22, 23, 23, 21, 23, 22, 22, 24, 21, 22, 23, 23, 21, 21, 22, 21, 23, 22, 23, 23, 20,
+    22, 22, 22, 23, 22, 22, 23, 26, 26, 20, 19, 22, 23, 22, 25, 26, 26, 26, 27, 27, 26, 24, 25, 19,
+    21, 26, 27, 27, 26, 27, 24, 21, 21, 26, 26, 28, 27, 27, 27, 20, 24, 20, 21, 22, 21, 21, 23, 22,
+    22, 25, 25, 24, 24, 26, 23, 26, 27, 26, 26, 27, 27, 27, 27, 27, 28, 27, 27, 27, 27, 27, 26, 30,
+];
+
+const EOS: usize = 256;
+
+const MAX_CODE_LENGTH: usize = 30;
+
+struct Code {
+    /// The number of codes of each length.
+    counts: [u16; MAX_CODE_LENGTH + 1],
+    /// The symbols ordered by code.
+    symbols: [u16; 257],
+    /// The code of each symbol.
+    codes: [u32; 257],
+}
+
+const fn canonical_code() -> Code {
+    let mut code = Code {
+        counts: [0; MAX_CODE_LENGTH + 1],
+        symbols: [0; 257],
+        codes: [0; 257],
+    };
+    let mut next = 0;
+    let mut n = 0;
+    let mut len = 1;
+    while len <= MAX_CODE_LENGTH {
+        let mut symbol = 0;
+        while symbol < CODE_LENGTHS.len() {
+            if CODE_LENGTHS[symbol] as usize == len {
+                code.counts[len] += 1;
+                code.symbols[n] = symbol as u16;
+                code.codes[symbol] = next;
+                next += 1;
+                n += 1;
+            }
+            symbol += 1;
+        }
+        next <<= 1;
+        len += 1;
+    }
+    code
+}
+
+const CODE: Code = canonical_code();
+
+/// Decodes a Huffman encoded string.
+///
+/// Returns `None` if the string contains the end-of-string symbol or is not padded with at most
+/// 7 one bits.
+pub fn decode(data: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(data.len() * 8 / 5);
+    // the code read so far, its length, and the first code and symbol index of that length
+    let (mut code, mut len, mut first, mut index) = (0u32, 0, 0u32, 0usize);
+    for byte in data {
+        for shift in (0..8).rev() {
+            code = code << 1 | u32::from(byte >> shift & 1);
+            first = (first + u32::from(CODE.counts[len])) << 1;
+            index += CODE.counts[len] as usize;
+            len += 1;
+            let count = u32::from(CODE.counts[len]);
+            if code - first < count {
+                let symbol = CODE.symbols[index + (code - first) as usize];
+                if symbol as usize == EOS {
+                    return None;
+                }
+                out.push(symbol as u8);
+                code = 0;
+                len = 0;
+                first = 0;
+                index = 0;
+            } else if len == MAX_CODE_LENGTH {
+                return None;
+            }
+        }
+    }
+    // the padding is a prefix of the end-of-string symbol, which is all ones
+    if len > 7 || code != (1 << len) - 1 {
+        return None;
+    }
+    Some(out)
+}
+
+/// The length of the Huffman encoding of `data`.
+pub fn encoded_len(data: &[u8]) -> usize {
+    let bits: usize = data
+        .iter()
+        .map(|&b| CODE_LENGTHS[b as usize] as usize)
+        .sum();
+    bits.div_ceil(8)
+}
+
+/// Appends the Huffman encoding of `data`.
+pub fn encode(data: &[u8], buf: &mut Vec<u8>) {
+    let mut bits = 0u64;
+    let mut len = 0;
+    for &b in data {
+        let code_len = CODE_LENGTHS[b as usize] as u32;
+        bits = bits << code_len | u64::from(CODE.codes[b as usize]);
+        len += code_len;
+        while len >= 8 {
+            len -= 8;
+            buf.push((bits >> len) as u8);
+        }
+    }
+    if len > 0 {
+        buf.push((bits << (8 - len)) as u8 | 0xff >> len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 7541 appendix C.4 and C.6
+    const STRINGS: [(&str, &[u8]); 5] = [
+        (
+            "www.example.com",
+            &[
+                0xf1, 0xe3, 0xc2, 0xe5, 0xf2, 0x3a, 0x6b, 0xa0, 0xab, 0x90, 0xf4, 0xff,
+            ],
+        ),
+        ("no-cache", &[0xa8, 0xeb, 0x10, 0x64, 0x9c, 0xbf]),
+        (
+            "custom-key",
+            &[0x25, 0xa8, 0x49, 0xe9, 0x5b, 0xa9, 0x7d, 0x7f],
+        ),
+        ("302", &[0x64, 0x02]),
+        ("private", &[0xae, 0xc3, 0x77, 0x1a, 0x4b]),
+    ];
+
+    #[test]
+    fn rfc7541_strings() {
+        for &(s, encoded) in &STRINGS {
+            assert_eq!(decode(encoded).unwrap(), s.as_bytes());
+            let mut buf = vec![];
+            encode(s.as_bytes(), &mut buf);
+            assert_eq!(buf, encoded);
+            assert_eq!(encoded_len(s.as_bytes()), encoded.len());
+        }
+    }
+
+    #[test]
+    fn all_bytes() {
+        let data: Vec<u8> = (0..=255).collect();
+        let mut buf = vec![];
+        encode(&data, &mut buf);
+        assert_eq!(decode(&buf).unwrap(), data);
+    }
+
+    #[test]
+    fn invalid_padding() {
+        // "302" followed by a byte of padding
+        assert!(decode(&[0x64, 0x02, 0xff]).is_none());
+        // "3" padded with a zero bit
+        assert!(decode(&[0x66]).is_none());
+        assert_eq!(decode(&[0x67]).unwrap(), b"3");
+        // the end-of-string symbol
+        assert!(decode(&[0xff, 0xff, 0xff, 0xfc]).is_none());
+    }
+}
@@ -0,0 +1,386 @@
+//! QPACK field section decoding (RFC 9204).
+//!
+//! Only the static table is known: field lines referring to the dynamic table, whose contents
+//! are sent on the encoder stream, are returned as `FieldLine::Dynamic` and
+//! `FieldLine::DynamicName`.
+
+use super::huffman;
+use std::borrow::Cow;
+use std::convert::TryFrom;
+
+/// The QPACK static table (RFC 9204 appendix A).
+pub const STATIC_TABLE: [(&str, &str); 99] = [
+    (":authority", ""),
+    (":path", "/"),
+    ("age", "0"),
+    ("content-disposition", ""),
+    ("content-length", "0"),
+    ("cookie", ""),
+    ("date", ""),
+    ("etag", ""),
+    ("if-modified-since", ""),
+    ("if-none-match", ""),
+    ("last-modified", ""),
+    ("link", ""),
+    ("location", ""),
+    ("referer", ""),
+    ("set-cookie", ""),
+    (":method", "CONNECT"),
+    (":method", "DELETE"),
+    (":method", "GET"),
+    (":method", "HEAD"),
+    (":method", "OPTIONS"),
+    (":method", "POST"),
+    (":method", "PUT"),
+    (":scheme", "http"),
+    (":scheme", "https"),
+    (":status", "103"),
+    (":status", "200"),
+    (":status", "304"),
+    (":status", "404"),
+    (":status", "503"),
+    ("accept", "*/*"),
+    ("accept", "application/dns-message"),
+    ("accept-encoding", "gzip, deflate, br"),
+    ("accept-ranges", "bytes"),
+    ("access-control-allow-headers", "cache-control"),
+    ("access-control-allow-headers", "content-type"),
+    ("access-control-allow-origin", "*"),
+    ("cache-control", "max-age=0"),
+    ("cache-control", "max-age=2592000"),
+    ("cache-control", "max-age=604800"),
+    ("cache-control", "no-cache"),
+    ("cache-control", "no-store"),
+    ("cache-control", "public, max-age=31536000"),
+    ("content-encoding", "br"),
+    ("content-encoding", "gzip"),
+    ("content-type", "application/dns-message"),
+    ("content-type", "application/javascript"),
+    ("content-type", "application/json"),
+    ("content-type", "application/x-www-form-urlencoded"),
+    ("content-type", "image/gif"),
+    ("content-type", "image/jpeg"),
+    ("content-type", "image/png"),
+    ("content-type", "text/css"),
+    ("content-type", "text/html; charset=utf-8"),
+    ("content-type", "text/plain"),
+    ("content-type", "text/plain;charset=utf-8"),
+    ("range", "bytes=0-"),
+    ("strict-transport-security", "max-age=31536000"),
+    (
+        "strict-transport-security",
+        "max-age=31536000; includesubdomains",
+    ),
+    (
+        "strict-transport-security",
+        "max-age=31536000; includesubdomains; preload",
+    ),
+    ("vary", "accept-encoding"),
+    ("vary", "origin"),
+    ("x-content-type-options", "nosniff"),
+    ("x-xss-protection", "1; mode=block"),
+    (":status", "100"),
+    (":status", "204"),
+    (":status", "206"),
+    (":status", "302"),
+    (":status", "400"),
+    (":status", "403"),
+    (":status", "421"),
+    (":status", "425"),
+    (":status", "500"),
+    ("accept-language", ""),
+    ("access-control-allow-credentials", "FALSE"),
+    ("access-control-allow-credentials", "TRUE"),
+    ("access-control-allow-headers", "*"),
+    ("access-control-allow-methods", "get"),
+    ("access-control-allow-methods", "get, post, options"),
+    ("access-control-allow-methods", "options"),
+    ("access-control-expose-headers", "content-length"),
+    ("access-control-request-headers", "content-type"),
+    ("access-control-request-method", "get"),
+    ("access-control-request-method", "post"),
+    ("alt-svc", "clear"),
+    ("authorization", ""),
+    (
+        "content-security-policy",
+        "script-src 'none'; object-src 'none'; base-uri 'none'",
+    ),
+    ("early-data", "1"),
+    ("expect-ct", ""),
+    ("forwarded", ""),
+    ("if-range", ""),
+    ("origin", ""),
+    ("purpose", "prefetch"),
+    ("server", ""),
+    ("timing-allow-origin", "*"),
+    ("upgrade-insecure-requests", "1"),
+    ("user-agent", ""),
+    ("x-forwarded-for", ""),
+    ("x-frame-options", "deny"),
+    ("x-frame-options", "sameorigin"),
+];
+
+/// A field whose name and value are known.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Field<'a> {
+    pub name: Cow<'a, [u8]>,
+    pub value: Cow<'a, [u8]>,
+}
+
+impl<'a> Field<'a> {
+    fn from_static(index: u64) -> Option<Self> {
+        let (name, value) = STATIC_TABLE.get(usize::try_from(index).ok()?)?;
+        Some(Self {
+            name: Cow::Borrowed(name.as_bytes()),
+            value: Cow::Borrowed(value.as_bytes()),
+        })
+    }
+}
+
+/// A field line of an encoded field section.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FieldLine<'a> {
+    /// A field from the static table or encoded as literals.
+    Field(Field<'a>),
+    /// A field from the dynamic table, at `index` relative to the base or, if `post_base` is
+    /// set, following the base.
+    Dynamic { index: u64, post_base: bool },
+    /// A literal value whose name is taken from the dynamic table, indexed like
+    /// `FieldLine::Dynamic`.
+    DynamicName {
+        index: u64,
+        post_base: bool,
+        value: Cow<'a, [u8]>,
+    },
+}
+
+/// An encoded field section, carried by HEADERS and PUSH_PROMISE frames.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldSection<'a> {
+    /// The encoded Required Insert Count, zero if the dynamic table is not referenced.
+    pub encoded_insert_count: u64,
+    /// Whether the base is below the Required Insert Count.
+    pub base_sign: bool,
+    pub delta_base: u64,
+    pub lines: Vec<FieldLine<'a>>,
+}
+
+impl<'a> FieldSection<'a> {
+    /// Decodes a field section, returning `None` if it is malformed or references an entry
+    /// beyond the end of the static table.
+    pub fn decode(buf: &'a [u8]) -> Option<Self> {
+        let (encoded_insert_count, rest) = split_int(buf, 8)?;
+        let base_sign = rest.first()? & 0x80 != 0;
+        let (delta_base, mut rest) = split_int(rest, 7)?;
+        let mut lines = vec![];
+        while let Some(&b) = rest.first() {
+            let line = if b & 0x80 != 0 {
+                let (index, r) = split_int(rest, 6)?;
+                rest = r;
+                if b & 0x40 != 0 {
+                    FieldLine::Field(Field::from_static(index)?)
+                } else {
+                    FieldLine::Dynamic {
+                        index,
+                        post_base: false,
+                    }
+                }
+            } else if b & 0x40 != 0 {
+                let (index, r) = split_int(rest, 4)?;
+                let (value, r) = split_string(r, 7)?;
+                rest = r;
+                if b & 0x10 != 0 {
+                    let mut field = Field::from_static(index)?;
+                    field.value = value;
+                    FieldLine::Field(field)
+                } else {
+                    FieldLine::DynamicName {
+                        index,
+                        post_base: false,
+                        value,
+                    }
+                }
+            } else if b & 0x20 != 0 {
+                let (name, r) = split_string(rest, 3)?;
+                let (value, r) = split_string(r, 7)?;
+                rest = r;
+                FieldLine::Field(Field { name, value })
+            } else if b & 0x10 != 0 {
+                let (index, r) = split_int(rest, 4)?;
+                rest = r;
+                FieldLine::Dynamic {
+                    index,
+                    post_base: true,
+                }
+            } else {
+                let (index, r) = split_int(rest, 3)?;
+                let (value, r) = split_string(r, 7)?;
+                rest = r;
+                FieldLine::DynamicName {
+                    index,
+                    post_base: true,
+                    value,
+                }
+            };
+            lines.push(line);
+        }
+        Some(Self {
+            encoded_insert_count,
+            base_sign,
+            delta_base,
+            lines,
+        })
+    }
+
+    /// The fields whose name and value are known.
+    pub fn fields(&self) -> impl Iterator<Item = &Field<'a>> + '_ {
+        self.lines.iter().filter_map(|line| match line {
+            FieldLine::Field(field) => Some(field),
+            _ => None,
+        })
+    }
+
+    /// The value of the first known field called `name`, such as `b":path"`.
+    pub fn get(&self, name: &[u8]) -> Option<&[u8]> {
+        self.fields()
+            .find(|field| field.name[..] == *name)
+            .map(|field| &field.value[..])
+    }
+}
+
+/// Splits an integer with a `prefix` bit prefix (RFC 7541 section 5.1) off the front of `buf`.
+fn split_int(buf: &[u8], prefix: u32) -> Option<(u64, &[u8])> {
+    let (&first, mut rest) = buf.split_first()?;
+    let max = (1u64 << prefix) - 1;
+    let mut value = u64::from(first) & max;
+    if value < max {
+        return Some((value, rest));
+    }
+    let mut shift = 0;
+    loop {
+        let (&b, r) = rest.split_first()?;
+        rest = r;
+        // QUIC never needs more than 62 bits
+        if shift > 56 {
+            return None;
+        }
+        value = value.checked_add(u64::from(b & 0x7f) << shift)?;
+        shift += 7;
+        if b & 0x80 == 0 {
+            return Some((value, rest));
+        }
+    }
+}
+
+/// Splits a string literal whose length has a `prefix` bit prefix, preceded by the Huffman flag,
+/// off the front of `buf`.
+fn split_string(buf: &[u8], prefix: u32) -> Option<(Cow<'_, [u8]>, &[u8])> {
+    let huffman = buf.first()? & 1 << prefix != 0;
+    let (len, rest) = split_int(buf, prefix)?;
+    if (rest.len() as u64) < len {
+        return None;
+    }
+    let (data, rest) = rest.split_at(len as usize);
+    let data = if huffman {
+        Cow::Owned(huffman::decode(data)?)
+    } else {
+        Cow::Borrowed(data)
+    };
+    Some((data, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rfc9204_literal_with_name_reference() {
+        // RFC 9204 appendix B.1
+        let buf = [
+            0x00, 0x00, 0x51, 0x0b, 0x2f, 0x69, 0x6e, 0x64, 0x65, 0x78, 0x2e, 0x68, 0x74, 0x6d,
+            0x6c,
+        ];
+        let section = FieldSection::decode(&buf).unwrap();
+        assert_eq!(section.encoded_insert_count, 0);
+        assert_eq!(section.lines.len(), 1);
+        assert_eq!(section.get(b":path"), Some(&b"/index.html"[..]));
+    }
+
+    #[test]
+    fn request() {
+        let mut buf = vec![0x00, 0x00];
+        // :method GET, :scheme https, :path /
+        buf.extend_from_slice(&[0xd1, 0xd7, 0xc1]);
+        // :authority with a Huffman encoded value
+        buf.push(0x50);
+        buf.push(0x80 | huffman::encoded_len(b"www.example.com") as u8);
+        huffman::encode(b"www.example.com", &mut buf);
+        // literal name and value, the name Huffman encoded with a length of 7 + 1
+        assert_eq!(huffman::encoded_len(b"custom-key"), 8);
+        buf.extend_from_slice(&[0x2f, 0x01]);
+        huffman::encode(b"custom-key", &mut buf);
+        buf.push(0x05);
+        buf.extend_from_slice(b"value");
+        let section = FieldSection::decode(&buf).unwrap();
+        assert_eq!(section.lines.len(), 5);
+        assert_eq!(section.get(b":method"), Some(&b"GET"[..]));
+        assert_eq!(section.get(b":scheme"), Some(&b"https"[..]));
+        assert_eq!(section.get(b":path"), Some(&b"/"[..]));
+        assert_eq!(section.get(b":authority"), Some(&b"www.example.com"[..]));
+        assert_eq!(section.get(b"custom-key"), Some(&b"value"[..]));
+    }
+
+    #[test]
+    fn dynamic_references() {
+        // required insert count 2, base 1 below it
+        let buf = [0x03, 0x81, 0x80, 0x10, 0x41, 0x01, b'x', 0x00, 0x00];
+        let section = FieldSection::decode(&buf).unwrap();
+        assert_eq!(section.encoded_insert_count, 3);
+        assert!(section.base_sign);
+        assert_eq!(section.delta_base, 1);
+        assert_eq!(
+            section.lines,
+            vec![
+                FieldLine::Dynamic {
+                    index: 0,
+                    post_base: false
+                },
+                FieldLine::Dynamic {
+                    index: 0,
+                    post_base: true
+                },
+                FieldLine::DynamicName {
+                    index: 1,
+                    post_base: false,
+                    value: Cow::Borrowed(b"x"),
+                },
+                FieldLine::DynamicName {
+                    index: 0,
+                    post_base: true,
+                    value: Cow::Borrowed(b""),
+                },
+            ]
+        );
+        assert_eq!(section.fields().count(), 0);
+    }
+
+    #[test]
+    fn malformed() {
+        // static index 99 is past the end of the table
+        assert!(FieldSection::decode(&[0x00, 0x00, 0xff, 0x24]).is_none());
+        // truncated value
+        assert!(FieldSection::decode(&[0x00, 0x00, 0x51, 0x0b, b'/']).is_none());
+        // missing base
+        assert!(FieldSection::decode(&[0x00]).is_none());
+    }
+
+    #[test]
+    fn prefix_integers() {
+        // RFC 7541 appendix C.1
+        assert_eq!(split_int(&[0x0a], 5), Some((10, &[][..])));
+        assert_eq!(split_int(&[0x1f, 0x9a, 0x0a], 5), Some((1337, &[][..])));
+        assert_eq!(split_int(&[0x2a, 0xff], 8), Some((42, &[0xff][..])));
+        assert!(split_int(&[0x1f, 0x9a], 5).is_none());
+        assert!(split_int(&[0xff; 12], 8).is_none());
+    }
+}
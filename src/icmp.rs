@@ -47,7 +47,7 @@ impl PrimitiveValues for IcmpCode {
 }
 
 /// Represents a generic ICMP packet.
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Icmp {
     #[construct_with(u8)]
     pub icmp_type: IcmpType,
@@ -202,7 +202,7 @@ pub mod echo_reply {
     }
 
     /// Represents an ICMP echo reply packet.
-    #[derive(Debug, Packet)]
+    #[derive(Clone, Debug, PartialEq, Packet)]
     pub struct EchoReply {
         #[construct_with(u8)]
         pub icmp_type: IcmpType,
@@ -279,7 +279,7 @@ pub mod echo_request {
     }
 
     /// Represents an "echo request" ICMP packet.
-    #[derive(Debug, Packet)]
+    #[derive(Clone, Debug, PartialEq, Packet)]
     pub struct EchoRequest {
         #[construct_with(u8)]
         pub icmp_type: IcmpType,
@@ -349,7 +349,7 @@ pub mod destination_unreachable {
     }
 
     /// Represents an "echo request" ICMP packet.
-    #[derive(Debug, Packet)]
+    #[derive(Clone, Debug, PartialEq, Packet)]
     pub struct DestinationUnreachable {
         #[construct_with(u8)]
         pub icmp_type: IcmpType,
@@ -390,7 +390,7 @@ pub mod time_exceeded {
     }
 
     /// Represents an "echo request" ICMP packet.
-    #[derive(Debug, Packet)]
+    #[derive(Clone, Debug, PartialEq, Packet)]
     pub struct TimeExceeded {
         #[construct_with(u8)]
         pub icmp_type: IcmpType,
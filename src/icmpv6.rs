@@ -60,7 +60,7 @@ impl PrimitiveValues for Icmpv6Code {
 /// ```
 ///
 /// [RFC 4443 § 2.1]: https://tools.ietf.org/html/rfc4443#section-2.1
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Icmpv6 {
     #[construct_with(u8)]
     pub icmpv6_type: Icmpv6Type,
@@ -274,13 +274,13 @@ pub mod ndp {
     /// ```
     ///
     /// [RFC 4861 § 4.6]: https://tools.ietf.org/html/rfc4861#section-4.6
-    #[derive(Debug, Packet)]
+    #[derive(Clone, Debug, PartialEq, Packet)]
     pub struct NdpOption {
         #[construct_with(u8)]
         pub option_type: NdpOptionType,
         #[construct_with(u8)]
         pub length: u8,
-        #[length = "(length * 8).saturating_sub(2)"]
+        #[length = "(length as usize * 8).saturating_sub(2)"]
         #[payload]
         pub data: Vec<u8>,
     }
@@ -297,7 +297,7 @@ pub mod ndp {
     /// ```
     ///
     /// [RFC 4861 § 4.1]: https://tools.ietf.org/html/rfc4861#section-4.1
-    #[derive(Debug, Packet)]
+    #[derive(Clone, Debug, PartialEq, Packet)]
     pub struct RouterSolicit {
         #[construct_with(u8)]
         pub icmpv6_type: Icmpv6Type,
@@ -340,7 +340,7 @@ pub mod ndp {
     /// ```
     ///
     /// [RFC 4861 § 4.2]: https://tools.ietf.org/html/rfc4861#section-4.2
-    #[derive(Debug, Packet)]
+    #[derive(Clone, Debug, PartialEq, Packet)]
     pub struct RouterAdvert {
         #[construct_with(u8)]
         pub icmpv6_type: Icmpv6Type,
@@ -379,7 +379,7 @@ pub mod ndp {
     /// ```
     ///
     /// [RFC 4861 § 4.3]: https://tools.ietf.org/html/rfc4861#section-4.3
-    #[derive(Debug, Packet)]
+    #[derive(Clone, Debug, PartialEq, Packet)]
     pub struct NeighborSolicit {
         #[construct_with(u8)]
         pub icmpv6_type: Icmpv6Type,
@@ -387,7 +387,7 @@ pub mod ndp {
         pub icmpv6_code: Icmpv6Code,
         pub checksum: u16be,
         pub reserved: u32be,
-        #[construct_with(u16, u16, u16, u16, u16, u16, u16, u16)]
+        #[construct_from(u128be)]
         pub target_addr: Ipv6Addr,
         #[payload]
         #[length = "0"]
@@ -430,7 +430,7 @@ pub mod ndp {
     /// ```
     ///
     /// [RFC 4861 § 4.4]: https://tools.ietf.org/html/rfc4861#section-4.4
-    #[derive(Debug, Packet)]
+    #[derive(Clone, Debug, PartialEq, Packet)]
     pub struct NeighborAdvert {
         #[construct_with(u8)]
         pub icmpv6_type: Icmpv6Type,
@@ -439,7 +439,7 @@ pub mod ndp {
         pub checksum: u16be,
         pub flags: u8,
         pub reserved: u24be,
-        #[construct_with(u16, u16, u16, u16, u16, u16, u16, u16)]
+        #[construct_from(u128be)]
         pub target_addr: Ipv6Addr,
         #[payload]
         #[length = "0"]
@@ -476,7 +476,7 @@ pub mod ndp {
     /// ```
     ///
     /// [RFC 4861 § 4.5]: https://tools.ietf.org/html/rfc4861#section-4.5
-    #[derive(Debug, Packet)]
+    #[derive(Clone, Debug, PartialEq, Packet)]
     pub struct Redirect {
         #[construct_with(u8)]
         pub icmpv6_type: Icmpv6Type,
@@ -484,9 +484,9 @@ pub mod ndp {
         pub icmpv6_code: Icmpv6Code,
         pub checksum: u16be,
         pub reserved: u32be,
-        #[construct_with(u16, u16, u16, u16, u16, u16, u16, u16)]
+        #[construct_from(u128be)]
         pub target_addr: Ipv6Addr,
-        #[construct_with(u16, u16, u16, u16, u16, u16, u16, u16)]
+        #[construct_from(u128be)]
         pub dest_addr: Ipv6Addr,
         #[payload]
         #[length = "0"]
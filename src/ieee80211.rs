@@ -0,0 +1,527 @@
+//! An IEEE 802.11 MAC frame abstraction.
+
+use crate::ethernet::EtherType;
+use crate::llc::LlcPacket;
+use crate::{types::*, MacAddr, Packet, PrimitiveValues};
+
+/// The type of an 802.11 frame.
+#[derive(Hash, Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone)]
+pub struct FrameType(pub u2);
+
+impl FrameType {
+    /// Construct a new `FrameType`.
+    pub fn new(value: u2) -> Self {
+        Self(value)
+    }
+}
+
+impl PrimitiveValues for FrameType {
+    type T = (u2,);
+    fn to_primitive_values(&self) -> (u2,) {
+        (self.0,)
+    }
+}
+
+impl std::fmt::Display for FrameType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match *self {
+                FrameTypes::Management => "Management",
+                FrameTypes::Control => "Control",
+                FrameTypes::Data => "Data",
+                FrameTypes::Extension => "Extension",
+                _ => "unknown",
+            }
+        )
+    }
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod FrameTypes {
+    use super::FrameType;
+
+    pub const Management: FrameType = FrameType(0);
+    pub const Control: FrameType = FrameType(1);
+    pub const Data: FrameType = FrameType(2);
+    pub const Extension: FrameType = FrameType(3);
+}
+
+/// The subtype of an 802.11 frame, whose meaning depends on the frame type.
+#[derive(Hash, Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone)]
+pub struct FrameSubtype(pub u4);
+
+impl FrameSubtype {
+    /// Construct a new `FrameSubtype`.
+    pub fn new(value: u4) -> Self {
+        Self(value)
+    }
+}
+
+impl PrimitiveValues for FrameSubtype {
+    type T = (u4,);
+    fn to_primitive_values(&self) -> (u4,) {
+        (self.0,)
+    }
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod ManagementSubtypes {
+    use super::FrameSubtype;
+
+    pub const AssociationRequest: FrameSubtype = FrameSubtype(0);
+    pub const AssociationResponse: FrameSubtype = FrameSubtype(1);
+    pub const ReassociationRequest: FrameSubtype = FrameSubtype(2);
+    pub const ReassociationResponse: FrameSubtype = FrameSubtype(3);
+    pub const ProbeRequest: FrameSubtype = FrameSubtype(4);
+    pub const ProbeResponse: FrameSubtype = FrameSubtype(5);
+    pub const Beacon: FrameSubtype = FrameSubtype(8);
+    pub const Atim: FrameSubtype = FrameSubtype(9);
+    pub const Disassociation: FrameSubtype = FrameSubtype(10);
+    pub const Authentication: FrameSubtype = FrameSubtype(11);
+    pub const Deauthentication: FrameSubtype = FrameSubtype(12);
+    pub const Action: FrameSubtype = FrameSubtype(13);
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod ControlSubtypes {
+    use super::FrameSubtype;
+
+    pub const BlockAckRequest: FrameSubtype = FrameSubtype(8);
+    pub const BlockAck: FrameSubtype = FrameSubtype(9);
+    pub const PsPoll: FrameSubtype = FrameSubtype(10);
+    pub const Rts: FrameSubtype = FrameSubtype(11);
+    pub const Cts: FrameSubtype = FrameSubtype(12);
+    pub const Ack: FrameSubtype = FrameSubtype(13);
+    pub const CfEnd: FrameSubtype = FrameSubtype(14);
+}
+
+/// Data subtypes. Subtypes with bit 3 set carry a QoS control field, and those with bit 2 set
+/// carry no data.
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod DataSubtypes {
+    use super::FrameSubtype;
+
+    pub const Data: FrameSubtype = FrameSubtype(0);
+    pub const Null: FrameSubtype = FrameSubtype(4);
+    pub const QosData: FrameSubtype = FrameSubtype(8);
+    pub const QosNull: FrameSubtype = FrameSubtype(12);
+}
+
+/// The flags of the frame control field.
+#[derive(Hash, Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone)]
+pub struct FrameControlFlags(pub u8);
+
+impl FrameControlFlags {
+    /// Construct a new `FrameControlFlags`.
+    pub fn new(value: u8) -> Self {
+        Self(value)
+    }
+
+    pub fn to_ds(&self) -> bool {
+        self.0 & 0x01 != 0
+    }
+
+    pub fn from_ds(&self) -> bool {
+        self.0 & 0x02 != 0
+    }
+
+    pub fn more_fragments(&self) -> bool {
+        self.0 & 0x04 != 0
+    }
+
+    pub fn retry(&self) -> bool {
+        self.0 & 0x08 != 0
+    }
+
+    pub fn power_management(&self) -> bool {
+        self.0 & 0x10 != 0
+    }
+
+    pub fn more_data(&self) -> bool {
+        self.0 & 0x20 != 0
+    }
+
+    /// Whether the body is encrypted.
+    pub fn protected(&self) -> bool {
+        self.0 & 0x40 != 0
+    }
+
+    /// Whether QoS data and management frames carry an HT control field.
+    pub fn order(&self) -> bool {
+        self.0 & 0x80 != 0
+    }
+}
+
+impl PrimitiveValues for FrameControlFlags {
+    type T = (u8,);
+    fn to_primitive_values(&self) -> (u8,) {
+        (self.0,)
+    }
+}
+
+/// The header fields common to all 802.11 frames. The rest of the header depends on the frame
+/// type and flags, see `Ieee80211Packet::header_length`. The frame control field is
+/// little-endian, so its subfields are listed from the most significant bits of each byte.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct Ieee80211 {
+    #[construct_with(u4)]
+    pub subtype: FrameSubtype,
+    #[construct_with(u2)]
+    pub frame_type: FrameType,
+    pub protocol_version: u2,
+    #[construct_with(u8)]
+    pub flags: FrameControlFlags,
+    pub duration: u16le,
+    #[construct_with(u8, u8, u8, u8, u8, u8)]
+    pub address1: MacAddr,
+    #[payload]
+    pub payload: Vec<u8>,
+}
+
+impl<'p> Ieee80211Packet<'p> {
+    fn has_qos_control(&self) -> bool {
+        self.get_frame_type() == FrameTypes::Data && self.get_subtype().0 & 0x08 != 0
+    }
+
+    fn has_address2(&self) -> bool {
+        match self.get_frame_type() {
+            FrameTypes::Management | FrameTypes::Data => true,
+            FrameTypes::Control => {
+                let subtype = self.get_subtype();
+                subtype != ControlSubtypes::Cts && subtype != ControlSubtypes::Ack
+            }
+            _ => false,
+        }
+    }
+
+    fn has_address3(&self) -> bool {
+        let frame_type = self.get_frame_type();
+        frame_type == FrameTypes::Management || frame_type == FrameTypes::Data
+    }
+
+    fn has_address4(&self) -> bool {
+        let flags = self.get_flags();
+        self.get_frame_type() == FrameTypes::Data && flags.to_ds() && flags.from_ds()
+    }
+
+    /// The length of the MAC header, up to the frame body.
+    pub fn header_length(&self) -> usize {
+        let mut length = 10;
+        if self.has_address2() {
+            length += 6;
+        }
+        if self.has_address3() {
+            // Address 3 and sequence control.
+            length += 8;
+        }
+        if self.has_address4() {
+            length += 6;
+        }
+        if self.has_qos_control() {
+            length += 2;
+        }
+        let ht_control = self.has_qos_control() || self.get_frame_type() == FrameTypes::Management;
+        if ht_control && self.get_flags().order() {
+            length += 4;
+        }
+        length
+    }
+
+    fn mac_at(&self, offset: usize) -> Option<MacAddr> {
+        match *self.packet().get(offset..offset + 6)? {
+            [a, b, c, d, e, f] => Some(MacAddr(a, b, c, d, e, f)),
+            _ => None,
+        }
+    }
+
+    fn u16_at(&self, offset: usize) -> Option<u16> {
+        let bytes = self.packet().get(offset..offset + 2)?;
+        Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    /// Address 2, usually the transmitter.
+    pub fn address2(&self) -> Option<MacAddr> {
+        if self.has_address2() {
+            self.mac_at(10)
+        } else {
+            None
+        }
+    }
+
+    /// Address 3, usually the BSSID, source or destination depending on the DS flags.
+    pub fn address3(&self) -> Option<MacAddr> {
+        if self.has_address3() {
+            self.mac_at(16)
+        } else {
+            None
+        }
+    }
+
+    /// Address 4, the source of frames relayed between access points.
+    pub fn address4(&self) -> Option<MacAddr> {
+        if self.has_address4() {
+            self.mac_at(24)
+        } else {
+            None
+        }
+    }
+
+    /// The sequence number.
+    pub fn sequence_number(&self) -> Option<u12be> {
+        if self.has_address3() {
+            Some(self.u16_at(22)? >> 4)
+        } else {
+            None
+        }
+    }
+
+    /// The fragment number.
+    pub fn fragment_number(&self) -> Option<u4> {
+        if self.has_address3() {
+            Some(self.u16_at(22)? as u8 & 0x0f)
+        } else {
+            None
+        }
+    }
+
+    /// The QoS control field of QoS data frames, whose low 4 bits are the traffic identifier.
+    pub fn qos_control(&self) -> Option<u16> {
+        if self.has_qos_control() {
+            self.u16_at(if self.has_address4() { 30 } else { 24 })
+        } else {
+            None
+        }
+    }
+
+    /// The frame body. Frames captured with their FCS have it at the end of the body.
+    pub fn body(&self) -> &[u8] {
+        self.packet().get(self.header_length()..).unwrap_or(&[])
+    }
+
+    /// The fixed fields and elements of a beacon or probe response.
+    pub fn beacon(&self) -> Option<BeaconPacket<'_>> {
+        let subtype = self.get_subtype();
+        if self.get_frame_type() == FrameTypes::Management
+            && (subtype == ManagementSubtypes::Beacon
+                || subtype == ManagementSubtypes::ProbeResponse)
+        {
+            BeaconPacket::new(self.body())
+        } else {
+            None
+        }
+    }
+
+    /// The elements of a probe request.
+    pub fn probe_request(&self) -> Option<InformationElementIterable<'_>> {
+        if self.get_frame_type() == FrameTypes::Management
+            && self.get_subtype() == ManagementSubtypes::ProbeRequest
+        {
+            Some(elements(self.body()))
+        } else {
+            None
+        }
+    }
+
+    /// The protocol of an unprotected data frame carrying an LLC/SNAP encapsulated ethertype,
+    /// and the offset of its payload in the frame.
+    pub fn ethertype(&self) -> Option<(EtherType, usize)> {
+        let flags = self.get_flags();
+        if self.get_frame_type() != FrameTypes::Data
+            || self.get_subtype().0 & 0x04 != 0
+            || flags.protected()
+        {
+            return None;
+        }
+        let llc = LlcPacket::new(self.body())?;
+        let ethertype = llc.snap()?.ethertype()?;
+        Some((ethertype, self.header_length() + 8))
+    }
+}
+
+/// The identifier of an information element.
+#[derive(Hash, Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone)]
+pub struct ElementId(pub u8);
+
+impl ElementId {
+    /// Construct a new `ElementId`.
+    pub fn new(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+impl PrimitiveValues for ElementId {
+    type T = (u8,);
+    fn to_primitive_values(&self) -> (u8,) {
+        (self.0,)
+    }
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod ElementIds {
+    use super::ElementId;
+
+    pub const Ssid: ElementId = ElementId(0);
+    pub const SupportedRates: ElementId = ElementId(1);
+    /// The current channel of DSSS and ERP networks.
+    pub const DsParameterSet: ElementId = ElementId(3);
+    /// Traffic Indication Map.
+    pub const Tim: ElementId = ElementId(5);
+    pub const Country: ElementId = ElementId(7);
+    pub const BssLoad: ElementId = ElementId(11);
+    pub const HtCapabilities: ElementId = ElementId(45);
+    /// Robust Security Network.
+    pub const Rsn: ElementId = ElementId(48);
+    pub const ExtendedSupportedRates: ElementId = ElementId(50);
+    pub const HtOperation: ElementId = ElementId(61);
+    pub const ExtendedCapabilities: ElementId = ElementId(127);
+    pub const VhtCapabilities: ElementId = ElementId(191);
+    pub const VhtOperation: ElementId = ElementId(192);
+    /// Starts with an OUI, e.g. for WPA and WMM.
+    pub const VendorSpecific: ElementId = ElementId(221);
+    /// Followed by an element ID extension byte.
+    pub const Extension: ElementId = ElementId(255);
+}
+
+/// An information element of a management frame body.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct InformationElement {
+    #[construct_with(u8)]
+    pub id: ElementId,
+    pub length: u8,
+    #[length = "length"]
+    #[payload]
+    pub data: Vec<u8>,
+}
+
+/// Iterates over the information elements in `data`.
+pub fn elements(data: &[u8]) -> InformationElementIterable<'_> {
+    InformationElementIterable { buf: data }
+}
+
+/// The fixed fields of beacons and probe responses, followed by the information elements.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct Beacon {
+    pub timestamp: u64le,
+    // In time units of 1024 microseconds.
+    pub beacon_interval: u16le,
+    pub capabilities: u16le,
+    #[payload]
+    pub elements: Vec<u8>,
+}
+
+impl<'p> BeaconPacket<'p> {
+    /// Iterates over the information elements.
+    pub fn elements(&self) -> InformationElementIterable<'_> {
+        elements(self.payload())
+    }
+
+    /// The data of the first element with identifier `id`.
+    pub fn element(&self, id: ElementId) -> Option<&[u8]> {
+        let mut data = self.payload();
+        while let [element_id, length, rest @ ..] = data {
+            let value = rest.get(..*length as usize)?;
+            if ElementId(*element_id) == id {
+                return Some(value);
+            }
+            data = &rest[value.len()..];
+        }
+        None
+    }
+
+    /// The SSID, empty for hidden networks.
+    pub fn ssid(&self) -> Option<&[u8]> {
+        self.element(ElementIds::Ssid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ethernet::EtherTypes;
+
+    #[test]
+    fn beacon() {
+        let mut frame = vec![
+            0x80, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x11, 0x22, 0x33,
+            0x44, 0x55, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x30, 0x01,
+        ];
+        frame.extend_from_slice(&[0x01, 0, 0, 0, 0, 0, 0, 0, 0x64, 0x00, 0x11, 0x04]);
+        frame.extend_from_slice(&[0x00, 0x04, b't', b'e', b's', b't']);
+        frame.extend_from_slice(&[0x01, 0x02, 0x82, 0x84, 0x03, 0x01, 0x06]);
+
+        let packet = Ieee80211Packet::new(&frame).unwrap();
+        assert_eq!(packet.get_frame_type(), FrameTypes::Management);
+        assert_eq!(packet.get_subtype(), ManagementSubtypes::Beacon);
+        assert_eq!(packet.get_address1(), MacAddr::broadcast());
+        assert_eq!(
+            packet.address2(),
+            Some(MacAddr(0, 0x11, 0x22, 0x33, 0x44, 0x55))
+        );
+        assert_eq!(packet.address4(), None);
+        assert_eq!(packet.sequence_number(), Some(19));
+        assert_eq!(packet.fragment_number(), Some(0));
+        assert_eq!(packet.header_length(), 24);
+        assert!(packet.probe_request().is_none());
+
+        let beacon = packet.beacon().unwrap();
+        assert_eq!(beacon.get_timestamp(), 1);
+        assert_eq!(beacon.get_beacon_interval(), 100);
+        assert_eq!(beacon.ssid(), Some(&b"test"[..]));
+        assert_eq!(beacon.element(ElementIds::DsParameterSet), Some(&[6][..]));
+        assert_eq!(beacon.element(ElementIds::Rsn), None);
+        let ids: Vec<_> = beacon.elements().map(|e| e.get_id()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                ElementIds::Ssid,
+                ElementIds::SupportedRates,
+                ElementIds::DsParameterSet
+            ]
+        );
+    }
+
+    #[test]
+    fn qos_data_with_address4() {
+        let mut frame = vec![
+            0x88, 0x03, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00, 0x00,
+            0x00, 0x02, 0x02, 0x00, 0x00, 0x00, 0x00, 0x03, 0x12, 0x00, 0x02, 0x00, 0x00, 0x00,
+            0x00, 0x04, 0x05, 0x00,
+        ];
+        frame.extend_from_slice(&[0xaa, 0xaa, 0x03, 0x00, 0x00, 0x00, 0x08, 0x00, 0x45]);
+
+        let packet = Ieee80211Packet::new(&frame).unwrap();
+        assert_eq!(packet.get_frame_type(), FrameTypes::Data);
+        assert_eq!(packet.get_subtype(), DataSubtypes::QosData);
+        assert!(packet.get_flags().to_ds() && packet.get_flags().from_ds());
+        assert_eq!(packet.address3(), Some(MacAddr(2, 0, 0, 0, 0, 3)));
+        assert_eq!(packet.address4(), Some(MacAddr(2, 0, 0, 0, 0, 4)));
+        assert_eq!(packet.sequence_number(), Some(1));
+        assert_eq!(packet.fragment_number(), Some(2));
+        assert_eq!(packet.qos_control(), Some(5));
+        assert_eq!(packet.header_length(), 32);
+        assert_eq!(packet.ethertype(), Some((EtherTypes::Ipv4, 40)));
+
+        frame[1] |= 0x40;
+        let packet = Ieee80211Packet::new(&frame).unwrap();
+        assert_eq!(packet.ethertype(), None);
+    }
+
+    #[test]
+    fn ack() {
+        let frame = [0xd4, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x01];
+        let packet = Ieee80211Packet::new(&frame).unwrap();
+        assert_eq!(packet.get_frame_type(), FrameTypes::Control);
+        assert_eq!(packet.get_subtype(), ControlSubtypes::Ack);
+        assert_eq!(packet.header_length(), 10);
+        assert_eq!(packet.address2(), None);
+        assert_eq!(packet.sequence_number(), None);
+        assert!(packet.body().is_empty());
+    }
+}
@@ -129,7 +129,7 @@ impl PrimitiveValues for Ipv4OptionNumber {
 }
 
 /// Represents an IPv4 Packet.
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Ipv4 {
     pub version: u4,
     pub header_length: u4,
@@ -238,7 +238,7 @@ fn ipv4_payload_length_test() {
 }
 
 /// Represents the IPv4 Option field.
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Ipv4Option {
     copied: u1,
     class: u2,
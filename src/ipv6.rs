@@ -13,7 +13,7 @@ use crate::{types::*, Packet};
 use std::net::Ipv6Addr;
 
 /// Represents an IPv6 Packet.
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Ipv6 {
     pub version: u4,
     pub traffic_class: u8,
@@ -22,9 +22,9 @@ pub struct Ipv6 {
     #[construct_with(u8)]
     pub next_header: IpNextHeaderProtocol,
     pub hop_limit: u8,
-    #[construct_with(u16, u16, u16, u16, u16, u16, u16, u16)]
+    #[construct_from(u128be)]
     pub source: Ipv6Addr,
-    #[construct_with(u16, u16, u16, u16, u16, u16, u16, u16)]
+    #[construct_from(u128be)]
     pub destination: Ipv6Addr,
     #[length = "payload_length"]
     #[payload]
@@ -38,7 +38,7 @@ impl<'p> ExtensionIterable<'p> {
 }
 
 /// Represents an IPv6 Extension.
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Extension {
     #[construct_with(u8)]
     pub next_header: IpNextHeaderProtocol,
@@ -60,7 +60,7 @@ pub type HopByHopPacket<'p> = ExtensionPacket<'p>;
 pub type MutableHopByHopPacket<'p> = MutableExtensionPacket<'p>;
 
 /// Represents an IPv6 Routing Extension.
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Routing {
     #[construct_with(u8)]
     pub next_header: IpNextHeaderProtocol,
@@ -77,7 +77,7 @@ fn routing_extension_length(hdr_ext_len: u8) -> usize {
 }
 
 /// Represents an IPv6 Fragment Extension.
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Fragment {
     #[construct_with(u8)]
     pub next_header: IpNextHeaderProtocol,
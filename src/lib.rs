@@ -16,14 +16,31 @@ pub use libpacket_derive::*;
 pub mod arp;
 pub mod ethernet;
 pub mod gre;
+pub mod http3;
 pub mod icmp;
 pub mod icmpv6;
+pub mod ieee80211;
 pub mod ip;
 pub mod ipv4;
 pub mod ipv6;
+pub mod linktype;
+pub mod llc;
+pub mod lldp;
+pub mod mpls;
+pub mod null;
+pub mod ppp;
+pub mod pppoe;
 pub mod quic;
+pub mod radiotap;
+pub mod raw;
+pub mod sll;
+pub mod sll2;
+pub mod stp;
 pub mod tcp;
 pub mod udp;
 pub mod vlan;
 
+#[cfg(all(test, feature = "proptest"))]
+mod round_trip;
+
 pub mod util;
@@ -0,0 +1,205 @@
+//! Link-layer header types of pcap and pcapng captures, and decoding of their headers.
+
+use crate::ethernet::{EtherType, EthernetPacket};
+use crate::ieee80211::Ieee80211Packet;
+use crate::null::{LoopPacket, NullPacket};
+use crate::radiotap::RadiotapPacket;
+use crate::raw::RawIp;
+use crate::sll::SllPacket;
+use crate::sll2::Sll2Packet;
+use crate::{Packet, PrimitiveValues};
+
+/// A `LINKTYPE_*` value, as found in pcap file headers and pcapng interface descriptions.
+#[derive(Hash, Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone)]
+pub struct LinkType(pub u32);
+
+impl LinkType {
+    /// Construct a new `LinkType`.
+    pub fn new(value: u32) -> Self {
+        Self(value)
+    }
+}
+
+impl PrimitiveValues for LinkType {
+    type T = (u32,);
+    fn to_primitive_values(&self) -> (u32,) {
+        (self.0,)
+    }
+}
+
+impl std::fmt::Display for LinkType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match *self {
+                LinkTypes::Null => "Null",
+                LinkTypes::Ethernet => "Ethernet",
+                LinkTypes::Raw => "Raw",
+                LinkTypes::Ieee80211 => "Ieee80211",
+                LinkTypes::Loop => "Loop",
+                LinkTypes::LinuxSll => "LinuxSll",
+                LinkTypes::Ieee80211Radiotap => "Ieee80211Radiotap",
+                LinkTypes::Ipv4 => "Ipv4",
+                LinkTypes::Ipv6 => "Ipv6",
+                LinkTypes::LinuxSll2 => "LinuxSll2",
+                _ => "unknown",
+            }
+        )
+    }
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod LinkTypes {
+    use super::LinkType;
+
+    /// BSD loopback, see `null::NullPacket`.
+    pub const Null: LinkType = LinkType(0);
+    pub const Ethernet: LinkType = LinkType(1);
+    /// Raw IPv4 or IPv6, see `raw::RawIp`.
+    pub const Raw: LinkType = LinkType(101);
+    /// 802.11 frames, see `ieee80211::Ieee80211Packet`.
+    pub const Ieee80211: LinkType = LinkType(105);
+    /// OpenBSD loopback, see `null::LoopPacket`.
+    pub const Loop: LinkType = LinkType(108);
+    /// Linux cooked capture, see `sll::SllPacket`.
+    pub const LinuxSll: LinkType = LinkType(113);
+    /// 802.11 frames preceded by a radiotap header, see `radiotap::RadiotapPacket`.
+    pub const Ieee80211Radiotap: LinkType = LinkType(127);
+    /// Raw IPv4.
+    pub const Ipv4: LinkType = LinkType(228);
+    /// Raw IPv6.
+    pub const Ipv6: LinkType = LinkType(229);
+    /// Linux cooked capture v2, see `sll2::Sll2Packet`.
+    pub const LinuxSll2: LinkType = LinkType(276);
+}
+
+/// A captured frame, decoded according to its link type.
+#[derive(Debug)]
+pub enum LinkPacket<'a> {
+    Ethernet(EthernetPacket<'a>),
+    Null(NullPacket<'a>),
+    Loop(LoopPacket<'a>),
+    Raw(RawIp<'a>),
+    LinuxSll(SllPacket<'a>),
+    LinuxSll2(Sll2Packet<'a>),
+    Ieee80211(Ieee80211Packet<'a>),
+    Ieee80211Radiotap(RadiotapPacket<'a>),
+}
+
+impl<'a> LinkPacket<'a> {
+    /// Decodes `data` as a frame of type `link_type`. Returns `None` for unsupported link types
+    /// or if the frame is too short for its header.
+    pub fn new(link_type: LinkType, data: &'a [u8]) -> Option<Self> {
+        Some(match link_type {
+            LinkTypes::Ethernet => Self::Ethernet(EthernetPacket::new(data)?),
+            LinkTypes::Null => Self::Null(NullPacket::new(data)?),
+            LinkTypes::Loop => Self::Loop(LoopPacket::new(data)?),
+            LinkTypes::Raw => Self::Raw(RawIp::new(data)?),
+            LinkTypes::Ipv4 => match RawIp::new(data)? {
+                ip @ RawIp::Ipv4(_) => Self::Raw(ip),
+                _ => return None,
+            },
+            LinkTypes::Ipv6 => match RawIp::new(data)? {
+                ip @ RawIp::Ipv6(_) => Self::Raw(ip),
+                _ => return None,
+            },
+            LinkTypes::LinuxSll => Self::LinuxSll(SllPacket::new(data)?),
+            LinkTypes::LinuxSll2 => Self::LinuxSll2(Sll2Packet::new(data)?),
+            LinkTypes::Ieee80211 => Self::Ieee80211(Ieee80211Packet::new(data)?),
+            LinkTypes::Ieee80211Radiotap => Self::Ieee80211Radiotap(RadiotapPacket::new(data)?),
+            _ => return None,
+        })
+    }
+
+    /// The protocol of the frame's payload as an ethertype, and the payload. Ethernet frames
+    /// are looked into past VLAN tags and LLC/SNAP headers, see
+    /// `EthernetPacket::effective_ethertype`, and 802.11 frames past their LLC/SNAP header, see
+    /// `Ieee80211Packet::ethertype`.
+    pub fn network(&self) -> Option<(EtherType, &[u8])> {
+        match self {
+            Self::Ethernet(frame) => {
+                let (ethertype, offset) = frame.effective_ethertype()?;
+                Some((ethertype, frame.packet().get(offset..)?))
+            }
+            Self::Null(null) => Some((null.address_family().ethertype()?, null.payload())),
+            Self::Loop(lo) => Some((lo.get_family().ethertype()?, lo.payload())),
+            Self::Raw(ip) => Some((ip.ethertype(), ip.packet())),
+            Self::LinuxSll(sll) => Some((sll.get_protocol(), sll.payload())),
+            Self::LinuxSll2(sll) => Some((sll.get_protocol(), sll.payload())),
+            Self::Ieee80211(frame) => {
+                let (ethertype, offset) = frame.ethertype()?;
+                Some((ethertype, frame.packet().get(offset..)?))
+            }
+            Self::Ieee80211Radiotap(radiotap) => {
+                let frame = radiotap.frame();
+                let (ethertype, offset) = Ieee80211Packet::new(frame)?.ethertype()?;
+                Some((ethertype, frame.get(offset..)?))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ethernet::EtherTypes;
+
+    const IPV4: [u8; 20] = [
+        0x45, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x00, 0x40, 0x01, 0x00, 0x00, 0x7f, 0x00, 0x00,
+        0x01, 0x7f, 0x00, 0x00, 0x01,
+    ];
+
+    #[test]
+    fn network() {
+        let mut ethernet = vec![0; 12];
+        ethernet.extend_from_slice(&[0x08, 0x00]);
+        ethernet.extend_from_slice(&IPV4);
+        let mut null = vec![0x02, 0x00, 0x00, 0x00];
+        null.extend_from_slice(&IPV4);
+        let mut sll = vec![0, 0, 0, 1, 0, 6, 0, 0, 0, 0, 0, 0, 0, 0, 0x08, 0x00];
+        sll.extend_from_slice(&IPV4);
+        let mut sll2 = vec![
+            0x08, 0x00, 0, 0, 0, 0, 0, 1, 0, 1, 0, 6, 0, 0, 0, 0, 0, 0, 0, 0,
+        ];
+        sll2.extend_from_slice(&IPV4);
+        let mut wlan = vec![0x08, 0x02, 0, 0];
+        wlan.extend_from_slice(&[0x02; 20]);
+        wlan.extend_from_slice(&[0xaa, 0xaa, 0x03, 0x00, 0x00, 0x00, 0x08, 0x00]);
+        wlan.extend_from_slice(&IPV4);
+        let mut radiotap = vec![0x00, 0x00, 0x0a, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00];
+        radiotap.extend_from_slice(&wlan);
+
+        for (link_type, frame) in &[
+            (LinkTypes::Ethernet, &ethernet[..]),
+            (LinkTypes::Null, &null[..]),
+            (LinkTypes::Raw, &IPV4[..]),
+            (LinkTypes::Ipv4, &IPV4[..]),
+            (LinkTypes::LinuxSll, &sll[..]),
+            (LinkTypes::LinuxSll2, &sll2[..]),
+            (LinkTypes::Ieee80211, &wlan[..]),
+            (LinkTypes::Ieee80211Radiotap, &radiotap[..]),
+        ] {
+            let packet = LinkPacket::new(*link_type, frame).unwrap();
+            assert_eq!(packet.network(), Some((EtherTypes::Ipv4, &IPV4[..])));
+            // short frames are rejected or decoded without panicking
+            let header_len = frame.len() - IPV4.len();
+            for len in 0..frame.len() {
+                if let Some(packet) = LinkPacket::new(*link_type, &frame[..len]) {
+                    let network = packet.network();
+                    assert!(
+                        len >= header_len || network.is_none(),
+                        "{} {}",
+                        link_type,
+                        len
+                    );
+                }
+            }
+        }
+        assert!(LinkPacket::new(LinkTypes::LinuxSll, &[0; 12]).is_none());
+        assert!(LinkPacket::new(LinkTypes::LinuxSll2, &[0; 12]).is_none());
+        assert!(LinkPacket::new(LinkTypes::Ipv6, &IPV4).is_none());
+        assert!(LinkPacket::new(LinkType(147), &IPV4).is_none());
+    }
+}
@@ -0,0 +1,260 @@
+//! An IEEE 802.2 LLC and SNAP packet abstraction, for IEEE 802.3 frames whose type field is a
+//! length.
+
+use crate::ethernet::{EtherType, EtherTypes, EthernetPacket};
+use crate::{types::*, Packet, PrimitiveValues};
+use std::cmp::min;
+
+/// The SNAP OUI of encapsulated Ethernet II frames (RFC 1042), whose protocol ID is an ethertype.
+pub const OUI_ENCAPSULATED_ETHERNET: u24be = 0x000000;
+/// The SNAP OUI of IEEE 802.1H bridge tunnel encapsulation, whose protocol ID is an ethertype.
+pub const OUI_BRIDGE_TUNNEL: u24be = 0x0000f8;
+/// The SNAP OUI of Cisco protocols such as CDP.
+pub const OUI_CISCO: u24be = 0x00000c;
+
+/// An LLC service access point. The low bit is the individual/group bit of a DSAP or the
+/// command/response bit of an SSAP.
+#[derive(Hash, Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone)]
+pub struct LlcSap(pub u8);
+
+impl LlcSap {
+    /// Construct a new `LlcSap`.
+    pub fn new(value: u8) -> Self {
+        Self(value)
+    }
+
+    /// The address without the individual/group or command/response bit.
+    pub fn address(&self) -> LlcSap {
+        LlcSap(self.0 & 0xfe)
+    }
+}
+
+impl PrimitiveValues for LlcSap {
+    type T = (u8,);
+    fn to_primitive_values(&self) -> (u8,) {
+        (self.0,)
+    }
+}
+
+impl std::fmt::Display for LlcSap {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self.address() {
+                LlcSaps::Null => "Null",
+                LlcSaps::Ip => "Ip",
+                LlcSaps::Stp => "Stp",
+                LlcSaps::Snap => "Snap",
+                LlcSaps::Ipx => "Ipx",
+                LlcSaps::NetBios => "NetBios",
+                LlcSaps::Iso => "Iso",
+                LlcSaps::Global => "Global",
+                _ => "unknown",
+            }
+        )
+    }
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod LlcSaps {
+    use super::LlcSap;
+
+    pub const Null: LlcSap = LlcSap(0x00);
+    /// Internet Protocol, rarely used in favour of SNAP.
+    pub const Ip: LlcSap = LlcSap(0x06);
+    /// Spanning Tree Protocol (IEEE 802.1D).
+    pub const Stp: LlcSap = LlcSap(0x42);
+    /// Subnetwork Access Protocol, see `SnapPacket`.
+    pub const Snap: LlcSap = LlcSap(0xaa);
+    /// Novell NetWare IPX.
+    pub const Ipx: LlcSap = LlcSap(0xe0);
+    pub const NetBios: LlcSap = LlcSap(0xf0);
+    /// ISO network layer protocols such as IS-IS.
+    pub const Iso: LlcSap = LlcSap(0xfe);
+    pub const Global: LlcSap = LlcSap(0xff);
+}
+
+/// The control field of an unnumbered information (UI) frame, used by connectionless LLC.
+pub const LLC_UI: u8 = 0x03;
+
+/// An IEEE 802.2 LLC header.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct Llc {
+    #[construct_with(u8)]
+    pub dsap: LlcSap,
+    #[construct_with(u8)]
+    pub ssap: LlcSap,
+    pub control: u8,
+    // The second control byte of information and supervisory frames.
+    #[length = "llc_control_extension_length(control)"]
+    pub control_extension: Vec<u8>,
+    #[payload]
+    pub payload: Vec<u8>,
+}
+
+/// Only unnumbered frames, whose control field ends in `0b11`, have a single byte control field.
+#[inline]
+fn llc_control_extension_length(control: u8) -> usize {
+    if control & 0x03 == 0x03 {
+        0
+    } else {
+        1
+    }
+}
+
+/// A SNAP header, following an LLC header with `LlcSaps::Snap` addresses.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct Snap {
+    pub oui: u24be,
+    pub protocol_id: u16be,
+    #[payload]
+    pub payload: Vec<u8>,
+}
+
+impl<'p> LlcPacket<'p> {
+    /// The SNAP header of a UI frame between SNAP addresses.
+    pub fn snap(&self) -> Option<SnapPacket<'_>> {
+        if self.get_dsap().address() == LlcSaps::Snap
+            && self.get_ssap().address() == LlcSaps::Snap
+            && self.get_control() == LLC_UI
+        {
+            SnapPacket::new(self.payload())
+        } else {
+            None
+        }
+    }
+}
+
+impl<'p> SnapPacket<'p> {
+    /// The protocol ID as an ethertype, if the OUI makes it one.
+    pub fn ethertype(&self) -> Option<EtherType> {
+        match self.get_oui() {
+            OUI_ENCAPSULATED_ETHERNET | OUI_BRIDGE_TUNNEL => {
+                Some(EtherType(self.get_protocol_id()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'p> EthernetPacket<'p> {
+    /// Whether the type field following any VLAN tags is a length, making this an IEEE 802.3
+    /// frame rather than an Ethernet II frame.
+    pub fn is_ieee802_3(&self) -> bool {
+        self.inner_ethertype().0.is_length()
+    }
+
+    /// The data of an IEEE 802.3 frame, without padding, and its offset in the frame.
+    fn ieee802_3_data(&self) -> Option<(&[u8], usize)> {
+        let (length, offset) = self.inner_ethertype();
+        if !length.is_length() {
+            return None;
+        }
+        let data = self.packet().get(offset..)?;
+        Some((&data[..min(length.0 as usize, data.len())], offset))
+    }
+
+    /// The LLC header of an IEEE 802.3 frame. Novell's raw 802.3 frames, which carry IPX
+    /// without LLC, have none.
+    pub fn llc(&self) -> Option<LlcPacket<'_>> {
+        let (data, _) = self.ieee802_3_data()?;
+        if data.starts_with(&[0xff, 0xff]) {
+            return None;
+        }
+        LlcPacket::new(data)
+    }
+
+    /// The protocol of the frame as an ethertype, and the offset of its payload in the frame.
+    /// IEEE 802.3 frames have one if they are SNAP encapsulated with an ethertype or raw IPX.
+    pub fn effective_ethertype(&self) -> Option<(EtherType, usize)> {
+        let (ethertype, offset) = self.inner_ethertype();
+        if !ethertype.is_length() {
+            return Some((ethertype, offset));
+        }
+        let (data, offset) = self.ieee802_3_data()?;
+        if data.starts_with(&[0xff, 0xff]) {
+            return Some((EtherTypes::Ipx, offset));
+        }
+        let llc = LlcPacket::new(data)?;
+        let snap = llc.snap()?;
+        let header_len = LlcPacket::minimum_packet_size() + SnapPacket::minimum_packet_size();
+        Some((snap.ethertype()?, offset + header_len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(type_or_length: u16, payload: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0x01, 0x80, 0xc2, 0x00, 0x00, 0x00, 0x02, 0, 0, 0, 0, 1];
+        frame.extend_from_slice(&type_or_length.to_be_bytes());
+        frame.extend_from_slice(payload);
+        frame.resize(frame.len().max(60), 0);
+        frame
+    }
+
+    #[test]
+    fn stp() {
+        let mut payload = vec![0x42, 0x42, 0x03];
+        payload.extend_from_slice(&[0; 35]);
+        let data = frame(payload.len() as u16, &payload);
+        let packet = EthernetPacket::new(&data).unwrap();
+        assert!(packet.is_ieee802_3());
+        let llc = packet.llc().unwrap();
+        assert_eq!(llc.get_dsap(), LlcSaps::Stp);
+        assert_eq!(llc.get_ssap(), LlcSaps::Stp);
+        assert_eq!(llc.get_control(), LLC_UI);
+        assert_eq!(llc.payload().len(), 35);
+        assert!(llc.snap().is_none());
+        assert_eq!(packet.effective_ethertype(), None);
+    }
+
+    #[test]
+    fn snap() {
+        let payload = [0xaa, 0xaa, 0x03, 0x00, 0x00, 0x00, 0x08, 0x00, 0x45];
+        let data = frame(payload.len() as u16, &payload);
+        let packet = EthernetPacket::new(&data).unwrap();
+        let llc = packet.llc().unwrap();
+        let snap = llc.snap().unwrap();
+        assert_eq!(snap.get_oui(), OUI_ENCAPSULATED_ETHERNET);
+        assert_eq!(snap.payload(), &[0x45]);
+        assert_eq!(packet.effective_ethertype(), Some((EtherTypes::Ipv4, 22)));
+
+        let cdp = [0xaa, 0xaa, 0x03, 0x00, 0x00, 0x0c, 0x20, 0x00, 0x02];
+        let data = frame(cdp.len() as u16, &cdp);
+        let packet = EthernetPacket::new(&data).unwrap();
+        let llc = packet.llc().unwrap();
+        assert_eq!(llc.snap().unwrap().get_protocol_id(), 0x2000);
+        assert_eq!(packet.effective_ethertype(), None);
+    }
+
+    #[test]
+    fn ethernet_ii_and_raw_ipx() {
+        let data = frame(0x0800, &[0x45]);
+        let packet = EthernetPacket::new(&data).unwrap();
+        assert!(!packet.is_ieee802_3());
+        assert!(packet.llc().is_none());
+        assert_eq!(packet.effective_ethertype(), Some((EtherTypes::Ipv4, 14)));
+
+        let data = frame(30, &[0xff, 0xff, 0x00, 0x1e]);
+        let packet = EthernetPacket::new(&data).unwrap();
+        assert!(packet.llc().is_none());
+        assert_eq!(packet.effective_ethertype(), Some((EtherTypes::Ipx, 14)));
+    }
+
+    #[test]
+    fn vlan_tagged() {
+        let tagged = [0x00, 0x0a, 0x00, 0x04, 0xfe, 0xfe, 0x00, 0x01];
+        let data = frame(0x8100, &tagged);
+        let packet = EthernetPacket::new(&data).unwrap();
+        assert!(packet.is_ieee802_3());
+        let llc = packet.llc().unwrap();
+        assert_eq!(llc.get_dsap(), LlcSaps::Iso);
+        assert_eq!(llc.get_control(), 0x00);
+        assert_eq!(llc.get_control_extension(), [0x01]);
+        assert!(llc.payload().is_empty());
+    }
+}
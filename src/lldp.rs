@@ -0,0 +1,696 @@
+//! An LLDP packet abstraction (IEEE 802.1AB).
+
+use crate::{types::*, MacAddr, Packet, PrimitiveValues};
+use std::convert::{TryFrom, TryInto};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// The nearest bridge multicast address, the usual destination of LLDPDUs.
+pub const LLDP_MULTICAST: MacAddr = MacAddr(0x01, 0x80, 0xc2, 0x00, 0x00, 0x0e);
+
+/// The OUI of IEEE 802.1 organizationally specific TLVs.
+pub const OUI_IEEE_802_1: [u8; 3] = [0x00, 0x80, 0xc2];
+/// The OUI of IEEE 802.3 organizationally specific TLVs.
+pub const OUI_IEEE_802_3: [u8; 3] = [0x00, 0x12, 0x0f];
+/// The OUI of LLDP-MED (ANSI/TIA-1057) organizationally specific TLVs.
+pub const OUI_TIA_MED: [u8; 3] = [0x00, 0x12, 0xbb];
+
+/// The type of an LLDP TLV.
+#[derive(Hash, Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone)]
+pub struct LldpTlvType(pub u7);
+
+impl LldpTlvType {
+    /// Construct a new `LldpTlvType`.
+    pub fn new(value: u7) -> Self {
+        Self(value)
+    }
+}
+
+impl PrimitiveValues for LldpTlvType {
+    type T = (u7,);
+    fn to_primitive_values(&self) -> (u7,) {
+        (self.0,)
+    }
+}
+
+impl std::fmt::Display for LldpTlvType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match *self {
+                LldpTlvTypes::EndOfLldpdu => "End Of LLDPDU",
+                LldpTlvTypes::ChassisId => "Chassis ID",
+                LldpTlvTypes::PortId => "Port ID",
+                LldpTlvTypes::Ttl => "Time To Live",
+                LldpTlvTypes::PortDescription => "Port Description",
+                LldpTlvTypes::SystemName => "System Name",
+                LldpTlvTypes::SystemDescription => "System Description",
+                LldpTlvTypes::SystemCapabilities => "System Capabilities",
+                LldpTlvTypes::ManagementAddress => "Management Address",
+                LldpTlvTypes::OrganizationallySpecific => "Organizationally Specific",
+                _ => "unknown",
+            }
+        )
+    }
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod LldpTlvTypes {
+    use super::LldpTlvType;
+
+    pub const EndOfLldpdu: LldpTlvType = LldpTlvType(0);
+    pub const ChassisId: LldpTlvType = LldpTlvType(1);
+    pub const PortId: LldpTlvType = LldpTlvType(2);
+    pub const Ttl: LldpTlvType = LldpTlvType(3);
+    pub const PortDescription: LldpTlvType = LldpTlvType(4);
+    pub const SystemName: LldpTlvType = LldpTlvType(5);
+    pub const SystemDescription: LldpTlvType = LldpTlvType(6);
+    pub const SystemCapabilities: LldpTlvType = LldpTlvType(7);
+    pub const ManagementAddress: LldpTlvType = LldpTlvType(8);
+    pub const OrganizationallySpecific: LldpTlvType = LldpTlvType(127);
+}
+
+/// How a chassis ID identifies the chassis.
+#[derive(Hash, Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone)]
+pub struct ChassisIdSubtype(pub u8);
+
+impl ChassisIdSubtype {
+    /// Construct a new `ChassisIdSubtype`.
+    pub fn new(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod ChassisIdSubtypes {
+    use super::ChassisIdSubtype;
+
+    pub const ChassisComponent: ChassisIdSubtype = ChassisIdSubtype(1);
+    pub const InterfaceAlias: ChassisIdSubtype = ChassisIdSubtype(2);
+    pub const PortComponent: ChassisIdSubtype = ChassisIdSubtype(3);
+    pub const MacAddress: ChassisIdSubtype = ChassisIdSubtype(4);
+    /// An IANA address family number followed by the address.
+    pub const NetworkAddress: ChassisIdSubtype = ChassisIdSubtype(5);
+    pub const InterfaceName: ChassisIdSubtype = ChassisIdSubtype(6);
+    pub const Local: ChassisIdSubtype = ChassisIdSubtype(7);
+}
+
+/// How a port ID identifies the port.
+#[derive(Hash, Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone)]
+pub struct PortIdSubtype(pub u8);
+
+impl PortIdSubtype {
+    /// Construct a new `PortIdSubtype`.
+    pub fn new(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod PortIdSubtypes {
+    use super::PortIdSubtype;
+
+    pub const InterfaceAlias: PortIdSubtype = PortIdSubtype(1);
+    pub const PortComponent: PortIdSubtype = PortIdSubtype(2);
+    pub const MacAddress: PortIdSubtype = PortIdSubtype(3);
+    /// An IANA address family number followed by the address.
+    pub const NetworkAddress: PortIdSubtype = PortIdSubtype(4);
+    pub const InterfaceName: PortIdSubtype = PortIdSubtype(5);
+    pub const AgentCircuitId: PortIdSubtype = PortIdSubtype(6);
+    pub const Local: PortIdSubtype = PortIdSubtype(7);
+}
+
+/// An LLDP TLV.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct LldpTlv {
+    #[construct_with(u7)]
+    pub tlv_type: LldpTlvType,
+    pub length: u9be,
+    #[length = "length"]
+    #[payload]
+    pub value: Vec<u8>,
+}
+
+/// A Management Address TLV.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ManagementAddress {
+    /// The IANA address family number of `address`, 1 for IPv4 and 2 for IPv6.
+    pub address_subtype: u8,
+    pub address: Vec<u8>,
+    /// How `interface_number` is assigned: 1 for unknown, 2 for ifIndex and 3 for system port
+    /// number.
+    pub interface_subtype: u8,
+    pub interface_number: u32,
+    /// The ASN.1 encoded object identifier of the hardware component or protocol entity.
+    pub oid: Vec<u8>,
+}
+
+impl ManagementAddress {
+    /// A management address for `ip`, with the interface given by its ifIndex.
+    pub fn from_ip(ip: IpAddr, if_index: u32) -> Self {
+        let (address_subtype, address) = match ip {
+            IpAddr::V4(ip) => (1, ip.octets().to_vec()),
+            IpAddr::V6(ip) => (2, ip.octets().to_vec()),
+        };
+        Self {
+            address_subtype,
+            address,
+            interface_subtype: 2,
+            interface_number: if_index,
+            oid: vec![],
+        }
+    }
+
+    /// The address, if it is an IPv4 or IPv6 address.
+    pub fn ip(&self) -> Option<IpAddr> {
+        match self.address_subtype {
+            1 => Some(Ipv4Addr::from(<[u8; 4]>::try_from(&self.address[..]).ok()?).into()),
+            2 => Some(Ipv6Addr::from(<[u8; 16]>::try_from(&self.address[..]).ok()?).into()),
+            _ => None,
+        }
+    }
+
+    fn decode(value: &[u8]) -> Option<Self> {
+        let address_len = *value.first()? as usize;
+        if address_len < 1 {
+            return None;
+        }
+        let address_subtype = *value.get(1)?;
+        let address = value.get(2..1 + address_len)?.to_vec();
+        let rest = &value[1 + address_len..];
+        let interface_subtype = *rest.first()?;
+        let interface_number = u32::from_be_bytes(rest.get(1..5)?.try_into().unwrap());
+        let oid_len = *rest.get(5)? as usize;
+        let oid = rest.get(6..6 + oid_len)?.to_vec();
+        Some(Self {
+            address_subtype,
+            address,
+            interface_subtype,
+            interface_number,
+            oid,
+        })
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) -> Option<()> {
+        buf.push((self.address.len() + 1).try_into().ok()?);
+        buf.push(self.address_subtype);
+        buf.extend_from_slice(&self.address);
+        buf.push(self.interface_subtype);
+        buf.extend_from_slice(&self.interface_number.to_be_bytes());
+        buf.push(self.oid.len().try_into().ok()?);
+        buf.extend_from_slice(&self.oid);
+        Some(())
+    }
+}
+
+/// An LLDP-MED Network Policy TLV.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NetworkPolicy {
+    /// The application, e.g. 1 for voice and 2 for voice signaling.
+    pub application_type: u8,
+    /// Whether the policy is required by the device but unknown.
+    pub unknown: bool,
+    /// Whether the application uses tagged frames.
+    pub tagged: bool,
+    pub vlan_id: u12be,
+    pub priority: u3,
+    pub dscp: u6,
+}
+
+/// An organizationally specific TLV.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OrgTlv {
+    /// The PVID of the port (IEEE 802.1).
+    PortVlanId(u16),
+    /// A protocol VLAN of the port (IEEE 802.1), with the supported and enabled flags.
+    PortProtocolVlanId {
+        flags: u8,
+        vlan_id: u16,
+    },
+    /// The name of a VLAN of the port (IEEE 802.1).
+    VlanName {
+        vlan_id: u16,
+        name: String,
+    },
+    /// The MAC/PHY Configuration/Status TLV (IEEE 802.3).
+    MacPhy {
+        /// The auto-negotiation support (bit 0) and status (bit 1).
+        autoneg: u8,
+        /// The advertised PMD auto-negotiation capabilities.
+        advertised: u16,
+        /// The operational MAU type (RFC 4836).
+        mau_type: u16,
+    },
+    /// The Maximum Frame Size TLV (IEEE 802.3).
+    MaximumFrameSize(u16),
+    /// The LLDP-MED Capabilities TLV, with the supported capabilities and the device class.
+    MedCapabilities {
+        capabilities: u16,
+        device_type: u8,
+    },
+    MedNetworkPolicy(NetworkPolicy),
+    /// An LLDP-MED inventory TLV, from hardware revision (5) to asset ID (11).
+    MedInventory {
+        subtype: u8,
+        value: String,
+    },
+    Unknown {
+        oui: [u8; 3],
+        subtype: u8,
+        info: Vec<u8>,
+    },
+}
+
+impl OrgTlv {
+    /// The OUI and subtype of the TLV.
+    pub fn id(&self) -> ([u8; 3], u8) {
+        match self {
+            Self::PortVlanId(_) => (OUI_IEEE_802_1, 1),
+            Self::PortProtocolVlanId { .. } => (OUI_IEEE_802_1, 2),
+            Self::VlanName { .. } => (OUI_IEEE_802_1, 3),
+            Self::MacPhy { .. } => (OUI_IEEE_802_3, 1),
+            Self::MaximumFrameSize(_) => (OUI_IEEE_802_3, 4),
+            Self::MedCapabilities { .. } => (OUI_TIA_MED, 1),
+            Self::MedNetworkPolicy(_) => (OUI_TIA_MED, 2),
+            Self::MedInventory { subtype, .. } => (OUI_TIA_MED, *subtype),
+            Self::Unknown { oui, subtype, .. } => (*oui, *subtype),
+        }
+    }
+
+    fn decode(value: &[u8]) -> Option<Self> {
+        let oui: [u8; 3] = value.get(..3)?.try_into().unwrap();
+        let subtype = *value.get(3)?;
+        let info = &value[4..];
+        let u16_at = |i: usize| -> Option<u16> {
+            Some(u16::from_be_bytes(info.get(i..i + 2)?.try_into().unwrap()))
+        };
+        Some(match (oui, subtype) {
+            (OUI_IEEE_802_1, 1) => Self::PortVlanId(u16_at(0)?),
+            (OUI_IEEE_802_1, 2) => Self::PortProtocolVlanId {
+                flags: *info.first()?,
+                vlan_id: u16_at(1)?,
+            },
+            (OUI_IEEE_802_1, 3) => {
+                let name_len = *info.get(2)? as usize;
+                Self::VlanName {
+                    vlan_id: u16_at(0)?,
+                    name: String::from_utf8_lossy(info.get(3..3 + name_len)?).into_owned(),
+                }
+            }
+            (OUI_IEEE_802_3, 1) => Self::MacPhy {
+                autoneg: *info.first()?,
+                advertised: u16_at(1)?,
+                mau_type: u16_at(3)?,
+            },
+            (OUI_IEEE_802_3, 4) => Self::MaximumFrameSize(u16_at(0)?),
+            (OUI_TIA_MED, 1) => Self::MedCapabilities {
+                capabilities: u16_at(0)?,
+                device_type: *info.get(2)?,
+            },
+            (OUI_TIA_MED, 2) => {
+                let policy = u32::from_be_bytes(info.get(..4)?.try_into().unwrap());
+                Self::MedNetworkPolicy(NetworkPolicy {
+                    application_type: (policy >> 24) as u8,
+                    unknown: policy & 0x80_0000 != 0,
+                    tagged: policy & 0x40_0000 != 0,
+                    vlan_id: (policy >> 9) as u16 & 0xfff,
+                    priority: (policy >> 6) as u8 & 0x7,
+                    dscp: policy as u8 & 0x3f,
+                })
+            }
+            (OUI_TIA_MED, 5..=11) => Self::MedInventory {
+                subtype,
+                value: String::from_utf8_lossy(info).into_owned(),
+            },
+            _ => Self::Unknown {
+                oui,
+                subtype,
+                info: info.to_vec(),
+            },
+        })
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) -> Option<()> {
+        let (oui, subtype) = self.id();
+        buf.extend_from_slice(&oui);
+        buf.push(subtype);
+        match self {
+            Self::PortVlanId(vlan_id) => buf.extend_from_slice(&vlan_id.to_be_bytes()),
+            Self::PortProtocolVlanId { flags, vlan_id } => {
+                buf.push(*flags);
+                buf.extend_from_slice(&vlan_id.to_be_bytes());
+            }
+            Self::VlanName { vlan_id, name } => {
+                buf.extend_from_slice(&vlan_id.to_be_bytes());
+                buf.push(name.len().try_into().ok()?);
+                buf.extend_from_slice(name.as_bytes());
+            }
+            Self::MacPhy {
+                autoneg,
+                advertised,
+                mau_type,
+            } => {
+                buf.push(*autoneg);
+                buf.extend_from_slice(&advertised.to_be_bytes());
+                buf.extend_from_slice(&mau_type.to_be_bytes());
+            }
+            Self::MaximumFrameSize(size) => buf.extend_from_slice(&size.to_be_bytes()),
+            Self::MedCapabilities {
+                capabilities,
+                device_type,
+            } => {
+                buf.extend_from_slice(&capabilities.to_be_bytes());
+                buf.push(*device_type);
+            }
+            Self::MedNetworkPolicy(policy) => {
+                let value = (policy.application_type as u32) << 24
+                    | (policy.unknown as u32) << 23
+                    | (policy.tagged as u32) << 22
+                    | ((policy.vlan_id & 0xfff) as u32) << 9
+                    | ((policy.priority & 0x7) as u32) << 6
+                    | (policy.dscp & 0x3f) as u32;
+                buf.extend_from_slice(&value.to_be_bytes());
+            }
+            Self::MedInventory { value, .. } => buf.extend_from_slice(value.as_bytes()),
+            Self::Unknown { info, .. } => buf.extend_from_slice(info),
+        }
+        Some(())
+    }
+}
+
+/// A decoded LLDP TLV.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Tlv {
+    ChassisId {
+        subtype: ChassisIdSubtype,
+        id: Vec<u8>,
+    },
+    PortId {
+        subtype: PortIdSubtype,
+        id: Vec<u8>,
+    },
+    /// The number of seconds the information remains valid, 0 to withdraw it.
+    Ttl(u16),
+    PortDescription(String),
+    SystemName(String),
+    SystemDescription(String),
+    /// The supported and enabled system capabilities.
+    SystemCapabilities {
+        capabilities: u16,
+        enabled: u16,
+    },
+    ManagementAddress(ManagementAddress),
+    Org(OrgTlv),
+    Unknown {
+        tlv_type: LldpTlvType,
+        value: Vec<u8>,
+    },
+}
+
+impl Tlv {
+    /// Decodes `tlv`. Returns `None` if it is truncated or too short for its type.
+    pub fn decode(tlv: &LldpTlvPacket) -> Option<Self> {
+        let value = tlv.payload();
+        if value.len() != tlv.get_length() as usize {
+            return None;
+        }
+        let string = |value: &[u8]| String::from_utf8_lossy(value).into_owned();
+        Some(match tlv.get_tlv_type() {
+            LldpTlvTypes::ChassisId => Self::ChassisId {
+                subtype: ChassisIdSubtype(*value.first()?),
+                id: value[1..].to_vec(),
+            },
+            LldpTlvTypes::PortId => Self::PortId {
+                subtype: PortIdSubtype(*value.first()?),
+                id: value[1..].to_vec(),
+            },
+            LldpTlvTypes::Ttl => Self::Ttl(u16::from_be_bytes(value.get(..2)?.try_into().unwrap())),
+            LldpTlvTypes::PortDescription => Self::PortDescription(string(value)),
+            LldpTlvTypes::SystemName => Self::SystemName(string(value)),
+            LldpTlvTypes::SystemDescription => Self::SystemDescription(string(value)),
+            LldpTlvTypes::SystemCapabilities => Self::SystemCapabilities {
+                capabilities: u16::from_be_bytes(value.get(..2)?.try_into().unwrap()),
+                enabled: u16::from_be_bytes(value.get(2..4)?.try_into().unwrap()),
+            },
+            LldpTlvTypes::ManagementAddress => {
+                Self::ManagementAddress(ManagementAddress::decode(value)?)
+            }
+            LldpTlvTypes::OrganizationallySpecific => Self::Org(OrgTlv::decode(value)?),
+            tlv_type => Self::Unknown {
+                tlv_type,
+                value: value.to_vec(),
+            },
+        })
+    }
+
+    /// The type of the TLV.
+    pub fn tlv_type(&self) -> LldpTlvType {
+        match self {
+            Self::ChassisId { .. } => LldpTlvTypes::ChassisId,
+            Self::PortId { .. } => LldpTlvTypes::PortId,
+            Self::Ttl(_) => LldpTlvTypes::Ttl,
+            Self::PortDescription(_) => LldpTlvTypes::PortDescription,
+            Self::SystemName(_) => LldpTlvTypes::SystemName,
+            Self::SystemDescription(_) => LldpTlvTypes::SystemDescription,
+            Self::SystemCapabilities { .. } => LldpTlvTypes::SystemCapabilities,
+            Self::ManagementAddress(_) => LldpTlvTypes::ManagementAddress,
+            Self::Org(_) => LldpTlvTypes::OrganizationallySpecific,
+            Self::Unknown { tlv_type, .. } => *tlv_type,
+        }
+    }
+
+    /// Appends the TLV to `buf`. Returns `None` if the value doesn't fit its length field.
+    pub fn encode(&self, buf: &mut Vec<u8>) -> Option<()> {
+        let mut value = vec![];
+        match self {
+            Self::ChassisId { subtype, id } => {
+                value.push(subtype.0);
+                value.extend_from_slice(id);
+            }
+            Self::PortId { subtype, id } => {
+                value.push(subtype.0);
+                value.extend_from_slice(id);
+            }
+            Self::Ttl(ttl) => value.extend_from_slice(&ttl.to_be_bytes()),
+            Self::PortDescription(s) | Self::SystemName(s) | Self::SystemDescription(s) => {
+                value.extend_from_slice(s.as_bytes())
+            }
+            Self::SystemCapabilities {
+                capabilities,
+                enabled,
+            } => {
+                value.extend_from_slice(&capabilities.to_be_bytes());
+                value.extend_from_slice(&enabled.to_be_bytes());
+            }
+            Self::ManagementAddress(address) => address.encode(&mut value)?,
+            Self::Org(org) => org.encode(&mut value)?,
+            Self::Unknown { value: v, .. } => value.extend_from_slice(v),
+        }
+        encode_tlv(self.tlv_type(), &value, buf)
+    }
+}
+
+fn encode_tlv(tlv_type: LldpTlvType, value: &[u8], buf: &mut Vec<u8>) -> Option<()> {
+    if tlv_type.0 > 0x7f || value.len() > 0x1ff {
+        return None;
+    }
+    let offset = buf.len();
+    buf.resize(offset + LldpTlvPacket::minimum_packet_size(), 0);
+    let mut tlv = MutableLldpTlvPacket::new(&mut buf[offset..]).unwrap();
+    tlv.set_tlv_type(tlv_type);
+    tlv.set_length(value.len() as u16);
+    buf.extend_from_slice(value);
+    Some(())
+}
+
+/// An LLDP data unit, the payload of an ethernet frame of type `EtherTypes::Lldp`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Lldpdu {
+    /// The TLVs, without the End Of LLDPDU TLV. The Chassis ID, Port ID and TTL TLVs come first.
+    pub tlvs: Vec<Tlv>,
+}
+
+impl Lldpdu {
+    /// An LLDPDU with the mandatory TLVs.
+    pub fn new(chassis_id: Tlv, port_id: Tlv, ttl: u16) -> Self {
+        Self {
+            tlvs: vec![chassis_id, port_id, Tlv::Ttl(ttl)],
+        }
+    }
+
+    /// Decodes the TLVs of `data` up to the End Of LLDPDU TLV, which may be missing. Returns
+    /// `None` if a TLV is malformed.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let mut tlvs = vec![];
+        for tlv in (LldpTlvIterable { buf: data }) {
+            if tlv.get_tlv_type() == LldpTlvTypes::EndOfLldpdu {
+                break;
+            }
+            tlvs.push(Tlv::decode(&tlv)?);
+        }
+        Some(Self { tlvs })
+    }
+
+    /// Encodes the TLVs followed by the End Of LLDPDU TLV. Returns `None` if a value doesn't fit
+    /// its length field.
+    pub fn to_bytes(&self) -> Option<Vec<u8>> {
+        let mut buf = vec![];
+        for tlv in &self.tlvs {
+            tlv.encode(&mut buf)?;
+        }
+        encode_tlv(LldpTlvTypes::EndOfLldpdu, &[], &mut buf)?;
+        Some(buf)
+    }
+
+    /// The first TLV of type `tlv_type`.
+    pub fn get(&self, tlv_type: LldpTlvType) -> Option<&Tlv> {
+        self.tlvs.iter().find(|tlv| tlv.tlv_type() == tlv_type)
+    }
+
+    pub fn ttl(&self) -> Option<u16> {
+        match self.get(LldpTlvTypes::Ttl)? {
+            Tlv::Ttl(ttl) => Some(*ttl),
+            _ => None,
+        }
+    }
+
+    pub fn system_name(&self) -> Option<&str> {
+        match self.get(LldpTlvTypes::SystemName)? {
+            Tlv::SystemName(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// The organizationally specific TLVs.
+    pub fn org_tlvs(&self) -> impl Iterator<Item = &OrgTlv> {
+        self.tlvs.iter().filter_map(|tlv| match tlv {
+            Tlv::Org(org) => Some(org),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LLDPDU: [u8; 90] = [
+        0x02, 0x07, 0x04, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, // Chassis ID
+        0x04, 0x05, 0x05, b'g', b'e', b'0', b'1', // Port ID
+        0x06, 0x02, 0x00, 0x78, // TTL
+        0x0a, 0x04, b's', b'w', b'-', b'1', // System Name
+        0x0e, 0x04, 0x00, 0x14, 0x00, 0x04, // System Capabilities
+        0x10, 0x0c, 0x05, 0x01, 0x0a, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00, 0x00, 0x03,
+        0x00, // Management Address
+        0xfe, 0x0b, 0x00, 0x80, 0xc2, 0x03, 0x00, 0x0a, 0x04, b'd', b'a', b't',
+        b'a', // VLAN Name
+        0xfe, 0x09, 0x00, 0x12, 0x0f, 0x01, 0x03, 0x6c, 0x00, 0x00, 0x10, // MAC/PHY
+        0xfe, 0x08, 0x00, 0x12, 0xbb, 0x02, 0x01, 0x40, 0x15, 0x6e, // Network Policy
+        0xfe, 0x06, 0x00, 0x12, 0x0f, 0x04, 0x05, 0xee, // Maximum Frame Size
+        0x00, 0x00, // End Of LLDPDU
+    ];
+
+    #[test]
+    fn decode() {
+        let lldpdu = Lldpdu::parse(&LLDPDU).unwrap();
+        assert_eq!(lldpdu.tlvs.len(), 10);
+        assert_eq!(
+            lldpdu.tlvs[0],
+            Tlv::ChassisId {
+                subtype: ChassisIdSubtypes::MacAddress,
+                id: vec![0x00, 0x11, 0x22, 0x33, 0x44, 0x55],
+            }
+        );
+        assert_eq!(
+            lldpdu.tlvs[1],
+            Tlv::PortId {
+                subtype: PortIdSubtypes::InterfaceName,
+                id: b"ge01".to_vec(),
+            }
+        );
+        assert_eq!(lldpdu.ttl(), Some(120));
+        assert_eq!(lldpdu.system_name(), Some("sw-1"));
+        assert_eq!(
+            lldpdu.tlvs[4],
+            Tlv::SystemCapabilities {
+                capabilities: 0x14,
+                enabled: 0x04
+            }
+        );
+        match &lldpdu.tlvs[5] {
+            Tlv::ManagementAddress(address) => {
+                assert_eq!(address.ip(), Some(Ipv4Addr::new(10, 0, 0, 1).into()));
+                assert_eq!(address.interface_number, 3);
+                assert!(address.oid.is_empty());
+            }
+            tlv => panic!("unexpected {:?}", tlv),
+        }
+        let org: Vec<_> = lldpdu.org_tlvs().cloned().collect();
+        assert_eq!(
+            org,
+            [
+                OrgTlv::VlanName {
+                    vlan_id: 10,
+                    name: "data".into()
+                },
+                OrgTlv::MacPhy {
+                    autoneg: 0x03,
+                    advertised: 0x6c00,
+                    mau_type: 0x0010,
+                },
+                OrgTlv::MedNetworkPolicy(NetworkPolicy {
+                    application_type: 1,
+                    unknown: false,
+                    tagged: true,
+                    vlan_id: 10,
+                    priority: 5,
+                    dscp: 46,
+                }),
+                OrgTlv::MaximumFrameSize(1518),
+            ]
+        );
+    }
+
+    #[test]
+    fn encode() {
+        let lldpdu = Lldpdu::parse(&LLDPDU).unwrap();
+        assert_eq!(lldpdu.to_bytes().unwrap(), &LLDPDU[..]);
+
+        let mut lldpdu = Lldpdu::new(
+            Tlv::ChassisId {
+                subtype: ChassisIdSubtypes::Local,
+                id: b"host".to_vec(),
+            },
+            Tlv::PortId {
+                subtype: PortIdSubtypes::MacAddress,
+                id: vec![0x02, 0, 0, 0, 0, 1],
+            },
+            60,
+        );
+        lldpdu
+            .tlvs
+            .push(Tlv::ManagementAddress(ManagementAddress::from_ip(
+                Ipv6Addr::LOCALHOST.into(),
+                1,
+            )));
+        lldpdu.tlvs.push(Tlv::Org(OrgTlv::MedInventory {
+            subtype: 8,
+            value: "SN123".into(),
+        }));
+        let bytes = lldpdu.to_bytes().unwrap();
+        assert_eq!(Lldpdu::parse(&bytes), Some(lldpdu.clone()));
+
+        lldpdu.tlvs.push(Tlv::SystemDescription("x".repeat(512)));
+        assert_eq!(lldpdu.to_bytes(), None);
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(Lldpdu::parse(&LLDPDU[..40]), None);
+        assert_eq!(Lldpdu::parse(&[0x06, 0x01, 0x00]), None);
+        assert_eq!(Lldpdu::parse(&LLDPDU[..20]).unwrap().tlvs.len(), 3);
+    }
+}
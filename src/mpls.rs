@@ -0,0 +1,276 @@
+//! An MPLS packet abstraction.
+
+use crate::ethernet::EtherTypes;
+use crate::gre::{GrePacket, MutableGrePacket};
+use crate::udp::UdpPacket;
+use crate::{types::*, Packet};
+
+/// The UDP destination port of MPLS-in-UDP (RFC 7510).
+pub const MPLS_UDP_PORT: u16 = 6635;
+
+/// An MPLS label stack entry (RFC 3032).
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct MplsLabel {
+    pub label: u20be,
+    pub traffic_class: u3,
+    pub bottom_of_stack: u1,
+    pub ttl: u8,
+    #[payload]
+    pub payload: Vec<u8>,
+}
+
+/// A pseudowire control word (RFC 4385).
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct ControlWord {
+    pub zero: u4,
+    pub flags: u4,
+    pub fragmentation: u2,
+    pub length: u6,
+    pub sequence_number: u16be,
+    #[payload]
+    pub payload: Vec<u8>,
+}
+
+/// Iterator over an MPLS label stack, ending with the entry whose bottom-of-stack bit is set.
+#[derive(Clone, Debug)]
+pub struct LabelStack<'a> {
+    data: &'a [u8],
+    done: bool,
+}
+
+impl<'a> LabelStack<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, done: false }
+    }
+
+    /// The data following the entries returned so far. Once the iterator is exhausted, this is
+    /// the payload of the stack.
+    pub fn payload(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+impl<'a> Iterator for LabelStack<'a> {
+    type Item = MplsLabelPacket<'a>;
+
+    fn next(&mut self) -> Option<MplsLabelPacket<'a>> {
+        if self.done {
+            return None;
+        }
+        let entry = MplsLabelPacket::new(self.data)?;
+        self.data = &self.data[MplsLabelPacket::minimum_packet_size()..];
+        self.done = entry.get_bottom_of_stack() == 1;
+        Some(entry)
+    }
+}
+
+/// The protocol following a label stack, guessed from its first nibble since MPLS does not
+/// identify it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MplsPayload {
+    Ipv4,
+    Ipv6,
+    /// A pseudowire with a control word, see `ControlWordPacket`.
+    ControlWord,
+    Unknown,
+}
+
+impl MplsPayload {
+    /// Guesses the protocol of `payload`. Pseudowires without a control word may be mistaken
+    /// for IP, which is why RFC 4385 recommends the control word.
+    pub fn guess(payload: &[u8]) -> Self {
+        match payload.first().map(|b| b >> 4) {
+            Some(4) => Self::Ipv4,
+            Some(6) => Self::Ipv6,
+            Some(0) => Self::ControlWord,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// The fields of a label stack entry other than the bottom-of-stack bit, which depends on its
+/// position in the stack.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Label {
+    pub label: u20be,
+    pub traffic_class: u3,
+    pub ttl: u8,
+}
+
+impl<'p> From<&MplsLabelPacket<'p>> for Label {
+    fn from(entry: &MplsLabelPacket<'p>) -> Self {
+        Self {
+            label: entry.get_label(),
+            traffic_class: entry.get_traffic_class(),
+            ttl: entry.get_ttl(),
+        }
+    }
+}
+
+/// Builds a label stack, setting the bottom-of-stack bit of the innermost label.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MplsBuilder {
+    /// The labels, outermost first.
+    labels: Vec<Label>,
+}
+
+impl MplsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes the label stack at the start of `packet`, returning the builder and the payload.
+    pub fn parse(packet: &[u8]) -> (Self, &[u8]) {
+        let mut stack = LabelStack::new(packet);
+        let labels = stack.by_ref().map(|entry| Label::from(&entry)).collect();
+        (Self { labels }, stack.payload())
+    }
+
+    /// Pushes `label` onto the stack, making it the outermost label.
+    pub fn push(&mut self, label: Label) -> &mut Self {
+        self.labels.insert(0, label);
+        self
+    }
+
+    /// Pops the outermost label.
+    pub fn pop(&mut self) -> Option<Label> {
+        if self.labels.is_empty() {
+            None
+        } else {
+            Some(self.labels.remove(0))
+        }
+    }
+
+    /// The labels, outermost first.
+    pub fn labels(&self) -> &[Label] {
+        &self.labels
+    }
+
+    /// Assembles the label stack followed by `payload`.
+    pub fn build(&self, payload: &[u8]) -> Vec<u8> {
+        let stack_len = self.labels.len() * MplsLabelPacket::minimum_packet_size();
+        let mut packet = vec![0; stack_len + payload.len()];
+        for (i, label) in self.labels.iter().enumerate() {
+            let offset = i * MplsLabelPacket::minimum_packet_size();
+            let mut entry = MutableMplsLabelPacket::new(&mut packet[offset..]).unwrap();
+            entry.set_label(label.label);
+            entry.set_traffic_class(label.traffic_class);
+            entry.set_bottom_of_stack((i + 1 == self.labels.len()) as u1);
+            entry.set_ttl(label.ttl);
+        }
+        packet[stack_len..].copy_from_slice(payload);
+        packet
+    }
+
+    /// Assembles an MPLS-in-GRE packet (RFC 4023) carrying the label stack followed by `payload`.
+    pub fn build_gre(&self, payload: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0; MutableGrePacket::minimum_packet_size()];
+        MutableGrePacket::new(&mut packet)
+            .unwrap()
+            .set_protocol_type(EtherTypes::Mpls.0);
+        packet.extend(self.build(payload));
+        packet
+    }
+}
+
+impl<'p> GrePacket<'p> {
+    /// The label stack of an MPLS-in-GRE packet (RFC 4023).
+    pub fn mpls_labels(&self) -> Option<LabelStack<'_>> {
+        let protocol = self.get_protocol_type();
+        if protocol == EtherTypes::Mpls.0 || protocol == EtherTypes::MplsMcast.0 {
+            Some(LabelStack::new(self.payload()))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'p> UdpPacket<'p> {
+    /// The label stack of an MPLS-in-UDP packet (RFC 7510) sent to `MPLS_UDP_PORT`.
+    pub fn mpls_labels(&self) -> Option<LabelStack<'_>> {
+        if self.get_destination() == MPLS_UDP_PORT {
+            Some(LabelStack::new(self.payload()))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STACK: [u8; 10] = [
+        0x00, 0x01, 0x44, 0x40, /* label 20, tc 2, ttl 64 */
+        0xff, 0xff, 0xf1, 0x01, /* label 0xfffff, bottom of stack, ttl 1 */
+        0x45, 0x00, /* ipv4 */
+    ];
+
+    #[test]
+    fn label_stack() {
+        let mut stack = LabelStack::new(&STACK);
+        let outer = stack.next().unwrap();
+        assert_eq!(outer.get_label(), 20);
+        assert_eq!(outer.get_traffic_class(), 2);
+        assert_eq!(outer.get_bottom_of_stack(), 0);
+        assert_eq!(outer.get_ttl(), 64);
+        let inner = stack.next().unwrap();
+        assert_eq!(inner.get_label(), 0xfffff);
+        assert_eq!(inner.get_bottom_of_stack(), 1);
+        assert!(stack.next().is_none());
+        assert_eq!(stack.payload(), &[0x45, 0x00]);
+        assert_eq!(MplsPayload::guess(stack.payload()), MplsPayload::Ipv4);
+
+        // truncated stack
+        assert_eq!(LabelStack::new(&STACK[..6]).count(), 1);
+    }
+
+    #[test]
+    fn control_word() {
+        let payload = [0x00, 0x00, 0x00, 0x2a, 0xff];
+        assert_eq!(MplsPayload::guess(&payload), MplsPayload::ControlWord);
+        let cw = ControlWordPacket::new(&payload).unwrap();
+        assert_eq!(cw.get_sequence_number(), 42);
+        assert_eq!(cw.payload(), &[0xff]);
+        assert_eq!(MplsPayload::guess(&[0x60]), MplsPayload::Ipv6);
+        assert_eq!(MplsPayload::guess(&[]), MplsPayload::Unknown);
+    }
+
+    #[test]
+    fn push_and_pop() {
+        let (mut builder, payload) = MplsBuilder::parse(&STACK);
+        assert_eq!(builder.labels().len(), 2);
+        assert_eq!(builder.build(payload), STACK);
+
+        let popped = builder.pop().unwrap();
+        assert_eq!(popped.label, 20);
+        assert_eq!(&builder.build(payload)[..], &STACK[4..]);
+        builder.push(Label {
+            label: 16,
+            traffic_class: 0,
+            ttl: 255,
+        });
+        assert_eq!(&builder.build(&[])[..4], &[0x00, 0x01, 0x00, 0xff]);
+
+        let mut empty = MplsBuilder::new();
+        assert!(empty.pop().is_none());
+        assert_eq!(empty.build(b"x"), b"x");
+    }
+
+    #[test]
+    fn mpls_in_gre_and_udp() {
+        let (builder, payload) = MplsBuilder::parse(&STACK);
+        let packet = builder.build_gre(payload);
+        let gre = GrePacket::new(&packet).unwrap();
+        let mut stack = gre.mpls_labels().unwrap();
+        assert_eq!(stack.by_ref().count(), 2);
+        assert_eq!(stack.payload(), payload);
+
+        let mut packet = vec![0x12, 0x34, 0x19, 0xeb, 0x00, 0x12, 0x00, 0x00];
+        packet.extend_from_slice(&STACK);
+        let udp = UdpPacket::new(&packet).unwrap();
+        assert_eq!(udp.mpls_labels().unwrap().count(), 2);
+        packet[3] = 0xec;
+        assert!(UdpPacket::new(&packet).unwrap().mpls_labels().is_none());
+    }
+}
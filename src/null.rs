@@ -0,0 +1,129 @@
+//! A BSD loopback (`LINKTYPE_NULL` and `LINKTYPE_LOOP`) header abstraction.
+
+use crate::ethernet::{EtherType, EtherTypes};
+use crate::{types::*, Packet, PrimitiveValues};
+
+/// The address family of a loopback header. IPv6 has a different value on each family of
+/// operating systems.
+#[derive(Hash, Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone)]
+pub struct AddressFamily(pub u32);
+
+impl AddressFamily {
+    /// Construct a new `AddressFamily`.
+    pub fn new(value: u32) -> Self {
+        Self(value)
+    }
+
+    /// The ethertype of the protocol, for the IP families.
+    pub fn ethertype(&self) -> Option<EtherType> {
+        match *self {
+            AddressFamilies::Inet => Some(EtherTypes::Ipv4),
+            AddressFamilies::Inet6Linux
+            | AddressFamilies::Inet6Bsd
+            | AddressFamilies::Inet6FreeBsd
+            | AddressFamilies::Inet6Darwin => Some(EtherTypes::Ipv6),
+            _ => None,
+        }
+    }
+}
+
+impl PrimitiveValues for AddressFamily {
+    type T = (u32,);
+    fn to_primitive_values(&self) -> (u32,) {
+        (self.0,)
+    }
+}
+
+impl std::fmt::Display for AddressFamily {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match *self {
+                AddressFamilies::Inet => "Inet",
+                AddressFamilies::Inet6Linux
+                | AddressFamilies::Inet6Bsd
+                | AddressFamilies::Inet6FreeBsd
+                | AddressFamilies::Inet6Darwin => "Inet6",
+                _ => "unknown",
+            }
+        )
+    }
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod AddressFamilies {
+    use super::AddressFamily;
+
+    pub const Inet: AddressFamily = AddressFamily(2);
+    pub const Inet6Linux: AddressFamily = AddressFamily(10);
+    /// `AF_INET6` on NetBSD, OpenBSD and BSD/OS.
+    pub const Inet6Bsd: AddressFamily = AddressFamily(24);
+    pub const Inet6FreeBsd: AddressFamily = AddressFamily(28);
+    /// `AF_INET6` on macOS and iOS.
+    pub const Inet6Darwin: AddressFamily = AddressFamily(30);
+}
+
+/// A `LINKTYPE_NULL` header, whose family is in the byte order of the capturing host.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct Null {
+    pub family: u32le,
+    #[payload]
+    pub payload: Vec<u8>,
+}
+
+/// A `LINKTYPE_LOOP` header, whose family is in network byte order.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct Loop {
+    #[construct_with(u32be)]
+    pub family: AddressFamily,
+    #[payload]
+    pub payload: Vec<u8>,
+}
+
+impl<'p> NullPacket<'p> {
+    /// The family, whatever the byte order of the capturing host. Families fit in 16 bits, so a
+    /// value that doesn't was written in big-endian.
+    pub fn address_family(&self) -> AddressFamily {
+        let family = self.get_family();
+        if family > 0xffff {
+            AddressFamily(family.swap_bytes())
+        } else {
+            AddressFamily(family)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipv4::Ipv4Packet;
+
+    #[test]
+    fn null_byte_orders() {
+        let little = [0x02, 0x00, 0x00, 0x00, 0x45];
+        let null = NullPacket::new(&little).unwrap();
+        assert_eq!(null.address_family(), AddressFamilies::Inet);
+        assert_eq!(null.payload(), &[0x45]);
+
+        let big = [0x00, 0x00, 0x00, 0x1e, 0x60];
+        let null = NullPacket::new(&big).unwrap();
+        assert_eq!(null.address_family(), AddressFamilies::Inet6Darwin);
+        assert_eq!(null.address_family().ethertype(), Some(EtherTypes::Ipv6));
+    }
+
+    #[test]
+    fn loop_header() {
+        let mut packet = vec![0x00, 0x00, 0x00, 0x02];
+        packet.extend_from_slice(&[
+            0x45, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x00, 0x40, 0x01, 0x00, 0x00, 0x7f, 0x00,
+            0x00, 0x01, 0x7f, 0x00, 0x00, 0x01,
+        ]);
+        let lo = LoopPacket::new(&packet).unwrap();
+        assert_eq!(lo.get_family(), AddressFamilies::Inet);
+        assert_eq!(lo.get_family().ethertype(), Some(EtherTypes::Ipv4));
+        assert!(Ipv4Packet::new(lo.payload()).is_some());
+        assert_eq!(AddressFamily(7).ethertype(), None);
+    }
+}
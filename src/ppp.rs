@@ -0,0 +1,483 @@
+//! A PPP packet abstraction (RFC 1661), as carried by PPPoE.
+
+use crate::ipv4::Ipv4Packet;
+use crate::ipv6::Ipv6Packet;
+use crate::{types::*, Packet, PrimitiveValues};
+use std::convert::TryInto;
+use std::net::Ipv4Addr;
+
+/// The protocol of a PPP frame.
+#[derive(Hash, Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone)]
+pub struct PppProtocol(pub u16);
+
+impl PppProtocol {
+    /// Construct a new `PppProtocol`.
+    pub fn new(value: u16) -> Self {
+        Self(value)
+    }
+}
+
+impl PrimitiveValues for PppProtocol {
+    type T = (u16,);
+    fn to_primitive_values(&self) -> (u16,) {
+        (self.0,)
+    }
+}
+
+impl std::fmt::Display for PppProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match *self {
+                PppProtocols::Ipv4 => "Ipv4",
+                PppProtocols::Ipv6 => "Ipv6",
+                PppProtocols::Mpls => "Mpls",
+                PppProtocols::Ipcp => "Ipcp",
+                PppProtocols::Ipv6cp => "Ipv6cp",
+                PppProtocols::Ccp => "Ccp",
+                PppProtocols::Lcp => "Lcp",
+                PppProtocols::Pap => "Pap",
+                PppProtocols::Lqr => "Lqr",
+                PppProtocols::Chap => "Chap",
+                PppProtocols::Eap => "Eap",
+                _ => "unknown",
+            }
+        )
+    }
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod PppProtocols {
+    use super::PppProtocol;
+
+    /// Internet Protocol version 4.
+    pub const Ipv4: PppProtocol = PppProtocol(0x0021);
+    /// Internet Protocol version 6.
+    pub const Ipv6: PppProtocol = PppProtocol(0x0057);
+    /// MPLS unicast.
+    pub const Mpls: PppProtocol = PppProtocol(0x0281);
+    /// IP Control Protocol (RFC 1332).
+    pub const Ipcp: PppProtocol = PppProtocol(0x8021);
+    /// IPv6 Control Protocol (RFC 5072).
+    pub const Ipv6cp: PppProtocol = PppProtocol(0x8057);
+    /// Compression Control Protocol (RFC 1962).
+    pub const Ccp: PppProtocol = PppProtocol(0x80fd);
+    /// Link Control Protocol.
+    pub const Lcp: PppProtocol = PppProtocol(0xc021);
+    /// Password Authentication Protocol (RFC 1334).
+    pub const Pap: PppProtocol = PppProtocol(0xc023);
+    /// Link Quality Report (RFC 1989).
+    pub const Lqr: PppProtocol = PppProtocol(0xc025);
+    /// Challenge Handshake Authentication Protocol (RFC 1994).
+    pub const Chap: PppProtocol = PppProtocol(0xc223);
+    /// Extensible Authentication Protocol (RFC 3748).
+    pub const Eap: PppProtocol = PppProtocol(0xc227);
+}
+
+/// The code of an LCP, IPCP or IPV6CP packet.
+#[derive(Hash, Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone)]
+pub struct ControlCode(pub u8);
+
+impl ControlCode {
+    /// Construct a new `ControlCode`.
+    pub fn new(value: u8) -> Self {
+        Self(value)
+    }
+
+    /// Whether the data of a packet with this code is a list of configuration options.
+    pub fn has_options(&self) -> bool {
+        (1..=4).contains(&self.0)
+    }
+}
+
+impl PrimitiveValues for ControlCode {
+    type T = (u8,);
+    fn to_primitive_values(&self) -> (u8,) {
+        (self.0,)
+    }
+}
+
+impl std::fmt::Display for ControlCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match *self {
+                ControlCodes::ConfigureRequest => "Configure-Request",
+                ControlCodes::ConfigureAck => "Configure-Ack",
+                ControlCodes::ConfigureNak => "Configure-Nak",
+                ControlCodes::ConfigureReject => "Configure-Reject",
+                ControlCodes::TerminateRequest => "Terminate-Request",
+                ControlCodes::TerminateAck => "Terminate-Ack",
+                ControlCodes::CodeReject => "Code-Reject",
+                ControlCodes::ProtocolReject => "Protocol-Reject",
+                ControlCodes::EchoRequest => "Echo-Request",
+                ControlCodes::EchoReply => "Echo-Reply",
+                ControlCodes::DiscardRequest => "Discard-Request",
+                _ => "unknown",
+            }
+        )
+    }
+}
+
+/// Codes shared by the control protocols. Those from `ProtocolReject` on are only valid in LCP.
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod ControlCodes {
+    use super::ControlCode;
+
+    pub const ConfigureRequest: ControlCode = ControlCode(1);
+    pub const ConfigureAck: ControlCode = ControlCode(2);
+    pub const ConfigureNak: ControlCode = ControlCode(3);
+    pub const ConfigureReject: ControlCode = ControlCode(4);
+    pub const TerminateRequest: ControlCode = ControlCode(5);
+    pub const TerminateAck: ControlCode = ControlCode(6);
+    pub const CodeReject: ControlCode = ControlCode(7);
+    pub const ProtocolReject: ControlCode = ControlCode(8);
+    pub const EchoRequest: ControlCode = ControlCode(9);
+    pub const EchoReply: ControlCode = ControlCode(10);
+    pub const DiscardRequest: ControlCode = ControlCode(11);
+}
+
+/// A PPP frame without the HDLC address and control fields, as carried by PPPoE. The protocol
+/// field is assumed not to be compressed.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct Ppp {
+    #[construct_with(u16be)]
+    pub protocol: PppProtocol,
+    #[payload]
+    pub payload: Vec<u8>,
+}
+
+/// An LCP, IPCP or IPV6CP packet.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct Control {
+    #[construct_with(u8)]
+    pub code: ControlCode,
+    pub identifier: u8,
+    pub length: u16be,
+    #[length = "length.saturating_sub(4)"]
+    #[payload]
+    pub data: Vec<u8>,
+}
+
+impl<'p> ControlPacket<'p> {
+    /// Iterates over the configuration options of a packet, see `ControlCode::has_options`.
+    pub fn options(&self) -> ControlOptions<'_> {
+        ControlOptions(self.payload())
+    }
+}
+
+/// Iterator over the raw configuration options of a control packet, yielding the option type
+/// and its data. Stops at the first malformed option.
+#[derive(Clone, Debug)]
+pub struct ControlOptions<'a>(&'a [u8]);
+
+impl<'a> ControlOptions<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self(data)
+    }
+}
+
+impl<'a> Iterator for ControlOptions<'a> {
+    type Item = (u8, &'a [u8]);
+
+    fn next(&mut self) -> Option<(u8, &'a [u8])> {
+        let len = *self.0.get(1)? as usize;
+        if len < 2 || len > self.0.len() {
+            self.0 = &[];
+            return None;
+        }
+        let option = (self.0[0], &self.0[2..len]);
+        self.0 = &self.0[len..];
+        Some(option)
+    }
+}
+
+/// An LCP configuration option.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LcpOption<'a> {
+    /// Maximum-Receive-Unit.
+    Mru(u16),
+    /// Async-Control-Character-Map (RFC 1662).
+    Accm(u32),
+    /// Authentication-Protocol, with protocol specific data such as the CHAP algorithm.
+    AuthProtocol {
+        protocol: PppProtocol,
+        data: &'a [u8],
+    },
+    /// Quality-Protocol.
+    QualityProtocol {
+        protocol: PppProtocol,
+        data: &'a [u8],
+    },
+    /// Magic-Number.
+    MagicNumber(u32),
+    /// Protocol-Field-Compression.
+    Pfc,
+    /// Address-and-Control-Field-Compression.
+    Acfc,
+    Unknown {
+        ty: u8,
+        data: &'a [u8],
+    },
+}
+
+impl<'a> LcpOption<'a> {
+    /// Decodes an option yielded by `ControlOptions`. Returns `None` if a known option has the
+    /// wrong length.
+    pub fn new(ty: u8, data: &'a [u8]) -> Option<Self> {
+        Some(match ty {
+            1 => Self::Mru(u16::from_be_bytes(data.try_into().ok()?)),
+            2 => Self::Accm(u32::from_be_bytes(data.try_into().ok()?)),
+            3 | 4 => {
+                if data.len() < 2 {
+                    return None;
+                }
+                let protocol = PppProtocol(u16::from_be_bytes([data[0], data[1]]));
+                let data = &data[2..];
+                if ty == 3 {
+                    Self::AuthProtocol { protocol, data }
+                } else {
+                    Self::QualityProtocol { protocol, data }
+                }
+            }
+            5 => Self::MagicNumber(u32::from_be_bytes(data.try_into().ok()?)),
+            7 if data.is_empty() => Self::Pfc,
+            8 if data.is_empty() => Self::Acfc,
+            7 | 8 => return None,
+            _ => Self::Unknown { ty, data },
+        })
+    }
+}
+
+/// An IPCP configuration option (RFC 1332, RFC 1877).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IpcpOption<'a> {
+    /// IP-Compression-Protocol, with protocol specific data.
+    CompressionProtocol {
+        protocol: u16,
+        data: &'a [u8],
+    },
+    /// IP-Address, `0.0.0.0` to request an address from the peer.
+    IpAddress(Ipv4Addr),
+    PrimaryDns(Ipv4Addr),
+    PrimaryNbns(Ipv4Addr),
+    SecondaryDns(Ipv4Addr),
+    SecondaryNbns(Ipv4Addr),
+    Unknown {
+        ty: u8,
+        data: &'a [u8],
+    },
+}
+
+impl<'a> IpcpOption<'a> {
+    /// Decodes an option yielded by `ControlOptions`. Returns `None` if a known option has the
+    /// wrong length.
+    pub fn new(ty: u8, data: &'a [u8]) -> Option<Self> {
+        let addr = || -> Option<Ipv4Addr> {
+            let octets: [u8; 4] = data.try_into().ok()?;
+            Some(octets.into())
+        };
+        Some(match ty {
+            2 => {
+                if data.len() < 2 {
+                    return None;
+                }
+                Self::CompressionProtocol {
+                    protocol: u16::from_be_bytes([data[0], data[1]]),
+                    data: &data[2..],
+                }
+            }
+            3 => Self::IpAddress(addr()?),
+            129 => Self::PrimaryDns(addr()?),
+            130 => Self::PrimaryNbns(addr()?),
+            131 => Self::SecondaryDns(addr()?),
+            132 => Self::SecondaryNbns(addr()?),
+            _ => Self::Unknown { ty, data },
+        })
+    }
+}
+
+/// An IPV6CP configuration option (RFC 5072).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ipv6cpOption<'a> {
+    /// Interface-Identifier, the lower 64 bits of the link-local address.
+    InterfaceIdentifier([u8; 8]),
+    /// IPv6-Compression-Protocol, with protocol specific data.
+    CompressionProtocol {
+        protocol: u16,
+        data: &'a [u8],
+    },
+    Unknown {
+        ty: u8,
+        data: &'a [u8],
+    },
+}
+
+impl<'a> Ipv6cpOption<'a> {
+    /// Decodes an option yielded by `ControlOptions`. Returns `None` if a known option has the
+    /// wrong length.
+    pub fn new(ty: u8, data: &'a [u8]) -> Option<Self> {
+        Some(match ty {
+            1 => Self::InterfaceIdentifier(data.try_into().ok()?),
+            2 => {
+                if data.len() < 2 {
+                    return None;
+                }
+                Self::CompressionProtocol {
+                    protocol: u16::from_be_bytes([data[0], data[1]]),
+                    data: &data[2..],
+                }
+            }
+            _ => Self::Unknown { ty, data },
+        })
+    }
+}
+
+/// The payload of a PPP frame, dispatched on its protocol field.
+#[derive(Debug)]
+pub enum PppPayload<'a> {
+    Ipv4(Ipv4Packet<'a>),
+    Ipv6(Ipv6Packet<'a>),
+    Lcp(ControlPacket<'a>),
+    Ipcp(ControlPacket<'a>),
+    Ipv6cp(ControlPacket<'a>),
+    Unknown {
+        protocol: PppProtocol,
+        payload: &'a [u8],
+    },
+}
+
+impl<'a> PppPayload<'a> {
+    /// Decodes the payload of `packet`. Returns `None` if it is too short for its protocol.
+    pub fn new(packet: &'a PppPacket) -> Option<Self> {
+        let payload = packet.payload();
+        Some(match packet.get_protocol() {
+            PppProtocols::Ipv4 => Self::Ipv4(Ipv4Packet::new(payload)?),
+            PppProtocols::Ipv6 => Self::Ipv6(Ipv6Packet::new(payload)?),
+            PppProtocols::Lcp => Self::Lcp(ControlPacket::new(payload)?),
+            PppProtocols::Ipcp => Self::Ipcp(ControlPacket::new(payload)?),
+            PppProtocols::Ipv6cp => Self::Ipv6cp(ControlPacket::new(payload)?),
+            protocol => Self::Unknown { protocol, payload },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lcp() {
+        let frame = [
+            0xc0, 0x21, 0x01, 0x01, 0x00, 0x15, // Configure-Request
+            0x01, 0x04, 0x05, 0xd4, // MRU
+            0x05, 0x06, 0x12, 0x34, 0x56, 0x78, // Magic-Number
+            0x03, 0x05, 0xc2, 0x23, 0x05, // CHAP with MD5
+            0x07, 0x02, // PFC
+            0xff, 0xff, // trailing garbage, outside the length
+        ];
+        let ppp = PppPacket::new(&frame).unwrap();
+        let lcp = match PppPayload::new(&ppp).unwrap() {
+            PppPayload::Lcp(lcp) => lcp,
+            payload => panic!("unexpected {:?}", payload),
+        };
+        assert_eq!(lcp.get_code(), ControlCodes::ConfigureRequest);
+        assert!(lcp.get_code().has_options());
+        assert_eq!(lcp.get_identifier(), 1);
+        let options: Vec<_> = lcp
+            .options()
+            .map(|(ty, data)| LcpOption::new(ty, data).unwrap())
+            .collect();
+        assert_eq!(
+            options,
+            [
+                LcpOption::Mru(1492),
+                LcpOption::MagicNumber(0x12345678),
+                LcpOption::AuthProtocol {
+                    protocol: PppProtocols::Chap,
+                    data: &[0x05]
+                },
+                LcpOption::Pfc,
+            ]
+        );
+    }
+
+    #[test]
+    fn ipcp() {
+        let frame = [
+            0x80, 0x21, 0x03, 0x02, 0x00, 0x16, // Configure-Nak
+            0x03, 0x06, 0x0a, 0x00, 0x00, 0x01, // IP-Address
+            0x81, 0x06, 0x08, 0x08, 0x08, 0x08, // Primary DNS
+            0x83, 0x06, 0x08, 0x08, 0x04, 0x04, // Secondary DNS
+        ];
+        let ppp = PppPacket::new(&frame).unwrap();
+        let ipcp = match PppPayload::new(&ppp).unwrap() {
+            PppPayload::Ipcp(ipcp) => ipcp,
+            payload => panic!("unexpected {:?}", payload),
+        };
+        assert_eq!(ipcp.get_code(), ControlCodes::ConfigureNak);
+        let options: Vec<_> = ipcp
+            .options()
+            .map(|(ty, data)| IpcpOption::new(ty, data).unwrap())
+            .collect();
+        assert_eq!(
+            options,
+            [
+                IpcpOption::IpAddress(Ipv4Addr::new(10, 0, 0, 1)),
+                IpcpOption::PrimaryDns(Ipv4Addr::new(8, 8, 8, 8)),
+                IpcpOption::SecondaryDns(Ipv4Addr::new(8, 8, 4, 4)),
+            ]
+        );
+        assert_eq!(IpcpOption::new(3, &[10, 0, 0]), None);
+    }
+
+    #[test]
+    fn ipv6cp() {
+        let frame = [
+            0x80, 0x57, 0x01, 0x03, 0x00, 0x0e, // Configure-Request
+            0x01, 0x0a, 0x02, 0x11, 0x22, 0xff, 0xfe, 0x33, 0x44, 0x55,
+        ];
+        let ppp = PppPacket::new(&frame).unwrap();
+        let ipv6cp = match PppPayload::new(&ppp).unwrap() {
+            PppPayload::Ipv6cp(ipv6cp) => ipv6cp,
+            payload => panic!("unexpected {:?}", payload),
+        };
+        let (ty, data) = ipv6cp.options().next().unwrap();
+        assert_eq!(
+            Ipv6cpOption::new(ty, data),
+            Some(Ipv6cpOption::InterfaceIdentifier([
+                0x02, 0x11, 0x22, 0xff, 0xfe, 0x33, 0x44, 0x55
+            ]))
+        );
+    }
+
+    #[test]
+    fn malformed_options() {
+        let options: Vec<_> =
+            ControlOptions::new(&[0x01, 0x04, 0x05, 0xd4, 0x05, 0x01, 0x00]).collect();
+        assert_eq!(options, [(1, &[0x05, 0xd4][..])]);
+        assert_eq!(ControlOptions::new(&[0x05, 0x08, 0x00]).count(), 0);
+    }
+
+    #[test]
+    fn ip_dispatch() {
+        let mut frame = vec![0x00, 0x57, 0x60, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3b, 0x40];
+        frame.extend_from_slice(&[0; 32]);
+        let ppp = PppPacket::new(&frame).unwrap();
+        assert!(matches!(PppPayload::new(&ppp), Some(PppPayload::Ipv6(_))));
+        let ppp = PppPacket::new(&frame[..10]).unwrap();
+        assert!(PppPayload::new(&ppp).is_none());
+        let ppp = PppPacket::new(&[0xc0, 0x23, 0x01]).unwrap();
+        assert!(matches!(
+            PppPayload::new(&ppp),
+            Some(PppPayload::Unknown {
+                protocol: PppProtocols::Pap,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,270 @@
+//! A PPPoE packet abstraction (RFC 2516).
+
+use crate::{types::*, Packet, PrimitiveValues};
+
+/// The code of a PPPoE packet, identifying the discovery stage or session data.
+#[derive(Hash, Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone)]
+pub struct PppoeCode(pub u8);
+
+impl PppoeCode {
+    /// Construct a new `PppoeCode`.
+    pub fn new(value: u8) -> Self {
+        Self(value)
+    }
+
+    /// Whether the code belongs to the discovery stage, whose payload is a list of tags.
+    pub fn is_discovery(&self) -> bool {
+        *self != PppoeCodes::Session
+    }
+}
+
+impl PrimitiveValues for PppoeCode {
+    type T = (u8,);
+    fn to_primitive_values(&self) -> (u8,) {
+        (self.0,)
+    }
+}
+
+impl std::fmt::Display for PppoeCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match *self {
+                PppoeCodes::Session => "Session",
+                PppoeCodes::Pado => "PADO",
+                PppoeCodes::Padi => "PADI",
+                PppoeCodes::Padr => "PADR",
+                PppoeCodes::Pads => "PADS",
+                PppoeCodes::Padt => "PADT",
+                _ => "unknown",
+            }
+        )
+    }
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod PppoeCodes {
+    use super::PppoeCode;
+
+    /// Session data, carrying a PPP frame.
+    pub const Session: PppoeCode = PppoeCode(0x00);
+    /// PPPoE Active Discovery Offer.
+    pub const Pado: PppoeCode = PppoeCode(0x07);
+    /// PPPoE Active Discovery Initiation.
+    pub const Padi: PppoeCode = PppoeCode(0x09);
+    /// PPPoE Active Discovery Request.
+    pub const Padr: PppoeCode = PppoeCode(0x19);
+    /// PPPoE Active Discovery Session-confirmation.
+    pub const Pads: PppoeCode = PppoeCode(0x65);
+    /// PPPoE Active Discovery Terminate.
+    pub const Padt: PppoeCode = PppoeCode(0xa7);
+}
+
+/// The type of a PPPoE discovery tag.
+#[derive(Hash, Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone)]
+pub struct PppoeTagType(pub u16);
+
+impl PppoeTagType {
+    /// Construct a new `PppoeTagType`.
+    pub fn new(value: u16) -> Self {
+        Self(value)
+    }
+}
+
+impl PrimitiveValues for PppoeTagType {
+    type T = (u16,);
+    fn to_primitive_values(&self) -> (u16,) {
+        (self.0,)
+    }
+}
+
+impl std::fmt::Display for PppoeTagType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match *self {
+                PppoeTagTypes::EndOfList => "End-Of-List",
+                PppoeTagTypes::ServiceName => "Service-Name",
+                PppoeTagTypes::AcName => "AC-Name",
+                PppoeTagTypes::HostUniq => "Host-Uniq",
+                PppoeTagTypes::AcCookie => "AC-Cookie",
+                PppoeTagTypes::VendorSpecific => "Vendor-Specific",
+                PppoeTagTypes::RelaySessionId => "Relay-Session-Id",
+                PppoeTagTypes::PppMaxPayload => "PPP-Max-Payload",
+                PppoeTagTypes::ServiceNameError => "Service-Name-Error",
+                PppoeTagTypes::AcSystemError => "AC-System-Error",
+                PppoeTagTypes::GenericError => "Generic-Error",
+                _ => "unknown",
+            }
+        )
+    }
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod PppoeTagTypes {
+    use super::PppoeTagType;
+
+    /// Marks the end of the tag list; optional.
+    pub const EndOfList: PppoeTagType = PppoeTagType(0x0000);
+    /// The requested or offered service, empty for any service.
+    pub const ServiceName: PppoeTagType = PppoeTagType(0x0101);
+    /// The name of the access concentrator.
+    pub const AcName: PppoeTagType = PppoeTagType(0x0102);
+    /// Opaque host data, echoed by the access concentrator.
+    pub const HostUniq: PppoeTagType = PppoeTagType(0x0103);
+    /// Opaque access concentrator data, echoed by the host.
+    pub const AcCookie: PppoeTagType = PppoeTagType(0x0104);
+    /// Vendor data, starting with the vendor's 4 byte IANA enterprise number.
+    pub const VendorSpecific: PppoeTagType = PppoeTagType(0x0105);
+    /// Opaque relay data, echoed by the peer.
+    pub const RelaySessionId: PppoeTagType = PppoeTagType(0x0110);
+    /// The maximum PPP payload supported by the host (RFC 4638).
+    pub const PppMaxPayload: PppoeTagType = PppoeTagType(0x0120);
+    /// The requested service is not available.
+    pub const ServiceNameError: PppoeTagType = PppoeTagType(0x0201);
+    /// The access concentrator experienced an error.
+    pub const AcSystemError: PppoeTagType = PppoeTagType(0x0202);
+    /// An unrecoverable error occurred.
+    pub const GenericError: PppoeTagType = PppoeTagType(0x0203);
+}
+
+/// A PPPoE packet, carried in ethernet frames of type `EtherTypes::PppoeDiscovery` or
+/// `EtherTypes::PppoeSession`.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct Pppoe {
+    pub version: u4,
+    pub pppoe_type: u4,
+    #[construct_with(u8)]
+    pub code: PppoeCode,
+    pub session_id: u16be,
+    pub length: u16be,
+    #[length = "length"]
+    #[payload]
+    pub payload: Vec<u8>,
+}
+
+/// A PPPoE discovery tag.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct PppoeTag {
+    #[construct_with(u16be)]
+    pub tag_type: PppoeTagType,
+    pub length: u16be,
+    #[length = "length"]
+    #[payload]
+    pub value: Vec<u8>,
+}
+
+impl<'p> PppoePacket<'p> {
+    /// Iterates over the tags of a discovery packet.
+    pub fn tags(&self) -> PppoeTagIterable<'_> {
+        PppoeTagIterable {
+            buf: self.payload(),
+        }
+    }
+
+    /// The value of the first tag of type `tag_type`.
+    pub fn tag(&self, tag_type: PppoeTagType) -> Option<&[u8]> {
+        let mut buf = self.payload();
+        while let Some(tag) = PppoeTagPacket::new(buf) {
+            let len = tag.get_length() as usize;
+            let end = PppoeTagPacket::minimum_packet_size() + len;
+            if end > buf.len() {
+                return None;
+            }
+            if tag.get_tag_type() == tag_type {
+                return Some(&buf[PppoeTagPacket::minimum_packet_size()..end]);
+            }
+            buf = &buf[end..];
+        }
+        None
+    }
+
+    /// The Service-Name tag, as UTF-8.
+    pub fn service_name(&self) -> Option<&str> {
+        std::str::from_utf8(self.tag(PppoeTagTypes::ServiceName)?).ok()
+    }
+
+    /// The AC-Name tag, as UTF-8.
+    pub fn ac_name(&self) -> Option<&str> {
+        std::str::from_utf8(self.tag(PppoeTagTypes::AcName)?).ok()
+    }
+
+    /// The Host-Uniq tag.
+    pub fn host_uniq(&self) -> Option<&[u8]> {
+        self.tag(PppoeTagTypes::HostUniq)
+    }
+
+    /// The AC-Cookie tag.
+    pub fn ac_cookie(&self) -> Option<&[u8]> {
+        self.tag(PppoeTagTypes::AcCookie)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ppp::{PppPacket, PppProtocols};
+
+    #[test]
+    fn discovery() {
+        let packet = [
+            0x11, 0x07, 0x00, 0x00, 0x00, 0x18, // PADO
+            0x01, 0x01, 0x00, 0x00, // Service-Name
+            0x01, 0x02, 0x00, 0x04, b'a', b'c', b'-', b'1', // AC-Name
+            0x01, 0x03, 0x00, 0x02, 0xbe, 0xef, // Host-Uniq
+            0x01, 0x04, 0x00, 0x02, 0x12, 0x34, // AC-Cookie
+            0x00, 0x00, // Ethernet padding
+        ];
+        let pppoe = PppoePacket::new(&packet).unwrap();
+        assert_eq!(pppoe.get_version(), 1);
+        assert_eq!(pppoe.get_pppoe_type(), 1);
+        assert_eq!(pppoe.get_code(), PppoeCodes::Pado);
+        assert!(pppoe.get_code().is_discovery());
+        assert_eq!(pppoe.payload().len(), 24);
+
+        let types: Vec<_> = pppoe.tags().map(|tag| tag.get_tag_type()).collect();
+        assert_eq!(
+            types,
+            [
+                PppoeTagTypes::ServiceName,
+                PppoeTagTypes::AcName,
+                PppoeTagTypes::HostUniq,
+                PppoeTagTypes::AcCookie,
+            ]
+        );
+        assert_eq!(pppoe.service_name(), Some(""));
+        assert_eq!(pppoe.ac_name(), Some("ac-1"));
+        assert_eq!(pppoe.host_uniq(), Some(&[0xbe, 0xef][..]));
+        assert_eq!(pppoe.ac_cookie(), Some(&[0x12, 0x34][..]));
+        assert_eq!(pppoe.tag(PppoeTagTypes::RelaySessionId), None);
+    }
+
+    #[test]
+    fn truncated_tag() {
+        let packet = [
+            0x11, 0x09, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03, 0x00, 0x04, 0xbe, 0xef,
+        ];
+        let pppoe = PppoePacket::new(&packet).unwrap();
+        assert_eq!(pppoe.host_uniq(), None);
+        assert_eq!(pppoe.tags().count(), 1);
+    }
+
+    #[test]
+    fn session() {
+        let packet = [
+            0x11, 0x00, 0x12, 0x34, 0x00, 0x16, 0x00, 0x21, // IPv4
+            0x45, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00, 0x0a, 0x00,
+            0x00, 0x01, 0x0a, 0x00, 0x00, 0x02,
+        ];
+        let pppoe = PppoePacket::new(&packet).unwrap();
+        assert_eq!(pppoe.get_code(), PppoeCodes::Session);
+        assert_eq!(pppoe.get_session_id(), 0x1234);
+        let ppp = PppPacket::new(pppoe.payload()).unwrap();
+        assert_eq!(ppp.get_protocol(), PppProtocols::Ipv4);
+        assert_eq!(ppp.payload().len(), 20);
+    }
+}
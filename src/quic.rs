@@ -1,4 +1,12 @@
-use crate::{types::*, Packet, PrimitiveValues};
+use crate::{types::*, Packet, PacketSize, PrimitiveValues};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::net::SocketAddr;
+
+pub mod builder;
+#[cfg(feature = "crypto")]
+pub mod crypto;
+pub mod frame;
+pub mod tls;
 
 #[derive(Debug)]
 pub enum QuicPacket<'a> {
@@ -7,45 +15,14 @@ pub enum QuicPacket<'a> {
     ZeroRtt(ZeroRttPacket<'a>),
     Handshake(HandshakePacket<'a>),
     Retry(RetryPacket<'a>),
-    OneRtt(OneRttPacket<'a>),
+    /// A short header packet and the length of its destination connection id.
+    OneRtt(OneRttPacket<'a>, usize),
 }
 
 impl<'a> QuicPacket<'a> {
-    pub fn new(mut packet: &'a [u8]) -> Option<Vec<Self>> {
-        let mut packets = vec![];
-        while !packet.is_empty() {
-            if packet.len() < 5 {
-                return None;
-            }
-            let header_form = packet[0] & (1 << 7) > 0;
-            let fixed_bit = packet[0] & (1 << 6) > 0;
-            let quic = if fixed_bit && !header_form {
-                Self::OneRtt(OneRttPacket::new(packet)?)
-            } else {
-                if packet[1..5] == [0u8; 4] {
-                    Self::VersionNegotiation(VersionNegotiationPacket::new(packet)?)
-                } else {
-                    match (LongPacketType::new((packet[0] >> 4) & 0b11), fixed_bit) {
-                        (LongPacketTypes::Initial, true) => {
-                            Self::Initial(InitialPacket::new(packet)?)
-                        }
-                        (LongPacketTypes::ZeroRtt, true) => {
-                            Self::ZeroRtt(ZeroRttPacket::new(packet)?)
-                        }
-                        (LongPacketTypes::Handshake, true) => {
-                            Self::Handshake(HandshakePacket::new(packet)?)
-                        }
-                        (LongPacketTypes::Retry, true) => Self::Retry(RetryPacket::new(packet)?),
-                        _ => return None,
-                    }
-                }
-            };
-            // lifetime of payload is 'a so this is safe to do.
-            let remaining = unsafe { std::mem::transmute(quic.remaining()) };
-            packets.push(quic);
-            packet = remaining;
-        }
-        Some(packets)
+    /// Parses the coalesced packets of a datagram with a fresh `QuicParser`.
+    pub fn new(packet: &'a [u8]) -> Option<Vec<Self>> {
+        QuicParser::new().parse(packet)
     }
 
     pub fn version(&self) -> Option<Version> {
@@ -55,17 +32,29 @@ impl<'a> QuicPacket<'a> {
             Self::ZeroRtt(packet) => Some(packet.get_version()),
             Self::Handshake(packet) => Some(packet.get_version()),
             Self::Retry(packet) => Some(packet.get_version()),
-            Self::OneRtt(_) => None,
+            Self::OneRtt(..) => None,
+        }
+    }
+
+    /// The token of an Initial packet.
+    pub fn token(&self) -> Option<&[u8]> {
+        match self {
+            Self::Initial(packet) => Some(packet.get_token_raw()),
+            _ => None,
         }
     }
 
-    pub fn token(&self) -> Option<Vec<u8>> {
+    /// The token of an Initial packet, borrowed for the lifetime of the buffer passed to
+    /// `QuicPacket::new`. Returns `None` if the wrapped packet owns its data.
+    pub fn token_borrowed(&self) -> Option<&'a [u8]> {
         match self {
-            Self::Initial(packet) => Some(packet.get_token()),
+            Self::Initial(packet) => packet.get_token_borrowed(),
             _ => None,
         }
     }
 
+    /// The truncated packet number. Only meaningful once header protection has been removed,
+    /// since the packet number length is protected; see `crypto::HeaderProtectionKey`.
     pub fn packet_number(&self) -> Option<u64> {
         match self {
             Self::VersionNegotiation(_) => None,
@@ -73,33 +62,65 @@ impl<'a> QuicPacket<'a> {
             Self::ZeroRtt(packet) => Some(packet.get_packet_number_raw()),
             Self::Handshake(packet) => Some(packet.get_packet_number_raw()),
             Self::Retry(_) => None,
-            Self::OneRtt(packet) => Some(packet.get_packet_number_raw()),
+            Self::OneRtt(packet, len) => {
+                let pn_len = packet.get_packet_number_len() as usize + 1;
+                packet.payload().get(*len..len + pn_len)
+            }
         }
         .map(packet_number)
     }
 
-    pub fn dest_id(&self) -> Vec<u8> {
+    /// The destination connection id.
+    pub fn dest_id(&self) -> &[u8] {
         match self {
-            Self::VersionNegotiation(packet) => packet.get_dest_id(),
-            Self::Initial(packet) => packet.get_dest_id(),
-            Self::ZeroRtt(packet) => packet.get_dest_id(),
-            Self::Handshake(packet) => packet.get_dest_id(),
-            Self::Retry(packet) => packet.get_dest_id(),
-            Self::OneRtt(packet) => packet.get_dest_id(),
+            Self::VersionNegotiation(packet) => packet.get_dest_id_raw(),
+            Self::Initial(packet) => packet.get_dest_id_raw(),
+            Self::ZeroRtt(packet) => packet.get_dest_id_raw(),
+            Self::Handshake(packet) => packet.get_dest_id_raw(),
+            Self::Retry(packet) => packet.get_dest_id_raw(),
+            Self::OneRtt(packet, len) => packet.payload().get(..*len).unwrap_or(&[]),
         }
     }
 
-    pub fn src_id(&self) -> Option<Vec<u8>> {
+    /// The destination connection id, borrowed for the lifetime of the buffer passed to
+    /// `QuicPacket::new`. Returns `None` if the wrapped packet owns its data.
+    pub fn dest_id_borrowed(&self) -> Option<&'a [u8]> {
+        match self {
+            Self::VersionNegotiation(packet) => packet.get_dest_id_borrowed(),
+            Self::Initial(packet) => packet.get_dest_id_borrowed(),
+            Self::ZeroRtt(packet) => packet.get_dest_id_borrowed(),
+            Self::Handshake(packet) => packet.get_dest_id_borrowed(),
+            Self::Retry(packet) => packet.get_dest_id_borrowed(),
+            Self::OneRtt(packet, len) => packet.get_payload_borrowed().and_then(|p| p.get(..*len)),
+        }
+    }
+
+    /// The source connection id of a long header packet.
+    pub fn src_id(&self) -> Option<&[u8]> {
         Some(match self {
-            Self::VersionNegotiation(packet) => packet.get_src_id(),
-            Self::Initial(packet) => packet.get_src_id(),
-            Self::ZeroRtt(packet) => packet.get_src_id(),
-            Self::Handshake(packet) => packet.get_src_id(),
-            Self::Retry(packet) => packet.get_src_id(),
-            Self::OneRtt(_) => return None,
+            Self::VersionNegotiation(packet) => packet.get_src_id_raw(),
+            Self::Initial(packet) => packet.get_src_id_raw(),
+            Self::ZeroRtt(packet) => packet.get_src_id_raw(),
+            Self::Handshake(packet) => packet.get_src_id_raw(),
+            Self::Retry(packet) => packet.get_src_id_raw(),
+            Self::OneRtt(..) => return None,
         })
     }
 
+    /// The source connection id of a long header packet, borrowed for the lifetime of the buffer
+    /// passed to `QuicPacket::new`. Returns `None` for short header packets and if the wrapped
+    /// packet owns its data.
+    pub fn src_id_borrowed(&self) -> Option<&'a [u8]> {
+        match self {
+            Self::VersionNegotiation(packet) => packet.get_src_id_borrowed(),
+            Self::Initial(packet) => packet.get_src_id_borrowed(),
+            Self::ZeroRtt(packet) => packet.get_src_id_borrowed(),
+            Self::Handshake(packet) => packet.get_src_id_borrowed(),
+            Self::Retry(packet) => packet.get_src_id_borrowed(),
+            Self::OneRtt(..) => None,
+        }
+    }
+
     pub fn packet(&self) -> &[u8] {
         match self {
             Self::VersionNegotiation(p) => p.packet(),
@@ -107,7 +128,7 @@ impl<'a> QuicPacket<'a> {
             Self::ZeroRtt(p) => p.packet(),
             Self::Handshake(p) => p.packet(),
             Self::Retry(p) => p.packet(),
-            Self::OneRtt(p) => p.packet(),
+            Self::OneRtt(p, _) => p.packet(),
         }
     }
 
@@ -118,7 +139,10 @@ impl<'a> QuicPacket<'a> {
             Self::ZeroRtt(packet) => Some(packet.get_frames_raw()),
             Self::Handshake(packet) => Some(packet.get_frames_raw()),
             Self::Retry(_) => None,
-            Self::OneRtt(packet) => Some(packet.payload()),
+            Self::OneRtt(packet, len) => {
+                let pn_len = packet.get_packet_number_len() as usize + 1;
+                packet.payload().get(len + pn_len..)
+            }
         }
     }
 
@@ -129,7 +153,7 @@ impl<'a> QuicPacket<'a> {
             Self::ZeroRtt(packet) => packet.get_remaining_raw(),
             Self::Handshake(packet) => packet.get_remaining_raw(),
             Self::Retry(_) => &[],
-            Self::OneRtt(_) => &[],
+            Self::OneRtt(..) => &[],
         }
     }
 }
@@ -142,7 +166,7 @@ impl<'a> std::fmt::Display for QuicPacket<'a> {
             Self::ZeroRtt(_) => "0rtt",
             Self::Handshake(_) => "handshake",
             Self::Retry(_) => "retry",
-            Self::OneRtt(_) => "1rtt",
+            Self::OneRtt(..) => "1rtt",
         };
         writeln!(f, "packet-type: {}", ty)?;
         if let Some(version) = self.version() {
@@ -166,18 +190,302 @@ impl<'a> std::fmt::Display for QuicPacket<'a> {
     }
 }
 
+/// Parses QUIC packets, tracking the connection ids observed in long headers.
+///
+/// Short header packets do not encode the length of their destination connection id. The parser
+/// remembers the source and destination connection ids of every long header packet it parses and
+/// matches short headers against them, preferring the longest match, and falls back to a default
+/// length for unknown connection ids. A zero length connection id is only assumed when the
+/// default doesn't fit in the packet.
+///
+/// Connection ids are only meaningful within a flow: `parse_flow` keeps them per UDP 4-tuple,
+/// while `parse` shares a single set for callers that already use one parser per flow.
+#[derive(Clone, Debug)]
+pub struct QuicParser {
+    dest_id_len: usize,
+    flows: HashMap<Option<(SocketAddr, SocketAddr)>, ConnectionIds>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct ConnectionIds {
+    ids: HashSet<Vec<u8>>,
+    lengths: BTreeSet<usize>,
+}
+
+impl ConnectionIds {
+    fn insert(&mut self, id: &[u8]) {
+        self.lengths.insert(id.len());
+        self.ids.insert(id.to_vec());
+    }
+
+    fn dest_id_len(&self, rest: &[u8], default: usize) -> usize {
+        self.lengths
+            .iter()
+            .rev()
+            .filter(|&&len| len > 0)
+            .find(|&&len| rest.get(..len).is_some_and(|id| self.ids.contains(id)))
+            .copied()
+            .unwrap_or(if rest.len() <= default && self.lengths.contains(&0) {
+                0
+            } else {
+                default
+            })
+    }
+}
+
+impl Default for QuicParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Orders the endpoints of a flow so both directions share the same key.
+fn flow_key(a: SocketAddr, b: SocketAddr) -> Option<(SocketAddr, SocketAddr)> {
+    Some(if a <= b { (a, b) } else { (b, a) })
+}
+
+impl QuicParser {
+    /// Creates a parser assuming 8 byte connection ids until it learns otherwise.
+    pub fn new() -> Self {
+        Self::with_dest_id_len(8)
+    }
+
+    /// Creates a parser assuming `len` byte connection ids until it learns otherwise.
+    pub fn with_dest_id_len(len: usize) -> Self {
+        Self {
+            dest_id_len: len,
+            flows: HashMap::new(),
+        }
+    }
+
+    /// Registers a connection id that may appear in short header packets parsed by `parse`.
+    pub fn add_connection_id(&mut self, id: &[u8]) {
+        self.flows.entry(None).or_default().insert(id);
+    }
+
+    /// The length of the destination connection id at the start of `rest`, for packets parsed
+    /// by `parse`.
+    pub fn dest_id_len(&self, rest: &[u8]) -> usize {
+        self.flow_dest_id_len(None, rest)
+    }
+
+    fn flow_dest_id_len(&self, flow: Option<(SocketAddr, SocketAddr)>, rest: &[u8]) -> usize {
+        match self.flows.get(&flow) {
+            Some(ids) => ids.dest_id_len(rest, self.dest_id_len),
+            None => self.dest_id_len,
+        }
+    }
+
+    /// Forgets the connection ids learned on the flow between `src` and `dst`, in either
+    /// direction, e.g. once the connection has been closed.
+    pub fn forget_flow(&mut self, src: SocketAddr, dst: SocketAddr) {
+        self.flows.remove(&flow_key(src, dst));
+    }
+
+    /// Forgets the connection ids learned by `parse`.
+    pub fn forget(&mut self) {
+        self.flows.remove(&None);
+    }
+
+    /// Parses the coalesced packets of a datagram sent from `src` to `dst`, matching short
+    /// headers against the connection ids learned on that flow only.
+    pub fn parse_flow<'a>(
+        &mut self,
+        src: SocketAddr,
+        dst: SocketAddr,
+        packet: &'a [u8],
+    ) -> Option<Vec<QuicPacket<'a>>> {
+        self.parse_with(flow_key(src, dst), packet)
+    }
+
+    /// Parses the coalesced packets of a datagram.
+    pub fn parse<'a>(&mut self, packet: &'a [u8]) -> Option<Vec<QuicPacket<'a>>> {
+        self.parse_with(None, packet)
+    }
+
+    fn parse_with<'a>(
+        &mut self,
+        flow: Option<(SocketAddr, SocketAddr)>,
+        mut packet: &'a [u8],
+    ) -> Option<Vec<QuicPacket<'a>>> {
+        let mut packets = vec![];
+        while !packet.is_empty() {
+            let header_form = packet[0] & (1 << 7) > 0;
+            let fixed_bit = packet[0] & (1 << 6) > 0;
+            let quic = if fixed_bit && !header_form {
+                let len = self.flow_dest_id_len(flow, &packet[1..]);
+                let one_rtt = OneRttPacket::new(packet)?;
+                let pn_len = one_rtt.get_packet_number_len() as usize + 1;
+                if one_rtt.payload().len() < len + pn_len {
+                    return None;
+                }
+                QuicPacket::OneRtt(one_rtt, len)
+            } else {
+                if packet.len() < 5 {
+                    return None;
+                }
+                let version = Version(u32::from_be_bytes([
+                    packet[1], packet[2], packet[3], packet[4],
+                ]));
+                let quic = if version == Versions::Negotiation {
+                    QuicPacket::VersionNegotiation(VersionNegotiationPacket::new(packet)?)
+                } else {
+                    let bits = (packet[0] >> 4) & 0b11;
+                    match (LongPacketType::from_bits(bits, version), fixed_bit) {
+                        (LongPacketTypes::Initial, true) => {
+                            QuicPacket::Initial(InitialPacket::new(packet)?)
+                        }
+                        (LongPacketTypes::ZeroRtt, true) => {
+                            QuicPacket::ZeroRtt(ZeroRttPacket::new(packet)?)
+                        }
+                        (LongPacketTypes::Handshake, true) => {
+                            QuicPacket::Handshake(HandshakePacket::new(packet)?)
+                        }
+                        (LongPacketTypes::Retry, true) => {
+                            let retry = RetryPacket::new(packet)?;
+                            if retry.get_integrity_tag_raw().len() < 16 {
+                                return None;
+                            }
+                            QuicPacket::Retry(retry)
+                        }
+                        _ => return None,
+                    }
+                };
+                let ids = self.flows.entry(flow).or_default();
+                ids.insert(quic.dest_id());
+                if let Some(src_id) = quic.src_id() {
+                    ids.insert(src_id);
+                }
+                quic
+            };
+            let consumed = quic.packet().len() - quic.remaining().len();
+            packets.push(quic);
+            packet = &packet[consumed..];
+        }
+        Some(packets)
+    }
+}
+
+/// The stateless reset tokens a peer has issued, used to recognize stateless resets (RFC 9000
+/// section 10.3).
+///
+/// A stateless reset looks like a short header packet whose last 16 bytes are the token
+/// associated with one of the connection ids issued by the peer, either in a NEW_CONNECTION_ID
+/// frame or in the `stateless_reset_token` transport parameter.
+#[derive(Clone, Debug, Default)]
+pub struct StatelessResetTokens {
+    tokens: HashSet<[u8; 16]>,
+}
+
+impl StatelessResetTokens {
+    /// The smallest datagram that can be recognized as a stateless reset.
+    pub const MIN_LEN: usize = 21;
+
+    /// Creates an empty set of tokens.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a token. Returns `false` if `token` is not 16 bytes long.
+    pub fn insert(&mut self, token: &[u8]) -> bool {
+        let mut buf = [0; 16];
+        if token.len() != buf.len() {
+            return false;
+        }
+        buf.copy_from_slice(token);
+        self.tokens.insert(buf);
+        true
+    }
+
+    /// Registers the tokens of the NEW_CONNECTION_ID frames among `frames`.
+    pub fn insert_frames(&mut self, frames: &[Frame]) {
+        for frame in frames {
+            if let Some(ncid) = match frame {
+                Frame::NewConnectionId(packet) => frame::NewConnectionIdFrame::new(packet),
+                _ => None,
+            } {
+                self.insert(ncid.stateless_reset_token);
+            }
+        }
+    }
+
+    /// Whether `datagram` is a short header packet ending with a known token.
+    pub fn is_stateless_reset(&self, datagram: &[u8]) -> bool {
+        if datagram.len() < Self::MIN_LEN || datagram[0] & 0xc0 != 0x40 {
+            return false;
+        }
+        let mut token = [0; 16];
+        token.copy_from_slice(&datagram[datagram.len() - 16..]);
+        self.tokens.contains(&token)
+    }
+}
+
+/// The encoded length of the variable-length integer at the start of `rest`. An empty `rest`
+/// still needs the one byte of the shortest encoding, so truncated frames overrun their buffer.
 pub fn varint_length(rest: &[u8]) -> usize {
-    let prefix = rest[0] >> 6;
-    let length = 1 << prefix;
-    length
+    match rest.first() {
+        Some(first) => 1 << (first >> 6),
+        None => 1,
+    }
 }
 
 pub fn varint(bytes: &[u8]) -> usize {
-    let mut length = (bytes[0] & 0x3f) as u64;
-    for v in &bytes[1..] {
-        length = (length << 8) + *v as u64;
+    varint_u64(bytes) as usize
+}
+
+/// Decodes a variable-length integer occupying all of `bytes`.
+pub fn varint_u64(bytes: &[u8]) -> u64 {
+    let (first, rest) = match bytes.split_first() {
+        Some(split) => split,
+        None => return 0,
+    };
+    let mut value = (first & 0x3f) as u64;
+    for v in rest {
+        value = (value << 8) + *v as u64;
+    }
+    value
+}
+
+/// Decodes a variable-length integer occupying all of `bytes`. Returns `None` if `bytes` is empty
+/// or isn't as long as its encoding.
+pub fn checked_varint_u64(bytes: &[u8]) -> Option<u64> {
+    (!bytes.is_empty() && bytes.len() == varint_length(bytes)).then(|| varint_u64(bytes))
+}
+
+/// Splits a variable-length integer off the front of `buf`.
+pub fn split_varint(buf: &[u8]) -> Option<(u64, &[u8])> {
+    if buf.is_empty() || buf.len() < varint_length(buf) {
+        return None;
+    }
+    let (int, rest) = buf.split_at(varint_length(buf));
+    Some((varint_u64(int), rest))
+}
+
+/// Appends the shortest encoding of `value` as a variable-length integer.
+///
+/// # Panics
+///
+/// Panics if `value` does not fit in 62 bits.
+pub fn encode_varint(value: u64, buf: &mut Vec<u8>) {
+    match value {
+        0..=0x3f => buf.push(value as u8),
+        0x40..=0x3fff => buf.extend_from_slice(&(value as u16 | 0x4000).to_be_bytes()),
+        0x4000..=0x3fff_ffff => buf.extend_from_slice(&(value as u32 | 0x8000_0000).to_be_bytes()),
+        0x4000_0000..=0x3fff_ffff_ffff_ffff => {
+            buf.extend_from_slice(&(value | 0xc000_0000_0000_0000).to_be_bytes())
+        }
+        _ => panic!("varint out of range: {}", value),
+    }
+}
+
+/// The length of the shortest encoding of `value` as a variable-length integer.
+pub fn encoded_varint_length(value: u64) -> usize {
+    match value {
+        0..=0x3f => 1,
+        0x40..=0x3fff => 2,
+        0x4000..=0x3fff_ffff => 4,
+        _ => 8,
     }
-    length as usize
 }
 
 pub fn packet_number(bytes: &[u8]) -> u64 {
@@ -186,19 +494,49 @@ pub fn packet_number(bytes: &[u8]) -> u64 {
     u64::from_be_bytes(pn)
 }
 
+/// Reconstructs a full packet number from the `pn_len` byte truncated packet number and the
+/// largest packet number acknowledged in the same packet number space (RFC 9000 Appendix A.3).
+/// Returns `None` if `pn_len` isn't between 1 and 4.
+pub fn decode_packet_number(
+    largest_pn: Option<u64>,
+    truncated_pn: u64,
+    pn_len: usize,
+) -> Option<u64> {
+    if !(1..=4).contains(&pn_len) {
+        return None;
+    }
+    let expected_pn = largest_pn.map(|pn| pn + 1).unwrap_or(0);
+    let pn_win = 1u64 << (pn_len * 8);
+    let pn_hwin = pn_win / 2;
+    let pn_mask = pn_win - 1;
+    let candidate_pn = (expected_pn & !pn_mask) | truncated_pn;
+    Some(
+        if candidate_pn + pn_hwin <= expected_pn && candidate_pn < (1 << 62) - pn_win {
+            candidate_pn + pn_win
+        } else if candidate_pn > expected_pn + pn_hwin && candidate_pn >= pn_win {
+            candidate_pn - pn_win
+        } else {
+            candidate_pn
+        },
+    )
+}
+
+/// The encoded length of the `n` variable-length integers at the start of `bytes`, or one more
+/// byte than `bytes` holds if they are truncated.
 pub fn n_varints(n: usize, mut bytes: &[u8]) -> usize {
     let mut length = 0;
     for _ in 0..n {
-        let prefix = bytes[0] >> 6;
-        let vlen = 1 << prefix;
+        let vlen = varint_length(bytes);
         length += vlen;
-        let (_, rest) = bytes.split_at(vlen);
-        bytes = rest;
+        bytes = match bytes.get(vlen..) {
+            Some(rest) => rest,
+            None => return length - vlen + bytes.len() + 1,
+        };
     }
     length
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct VersionNegotiation {
     #[construct_with(u1)]
     header_form: HeaderForm,
@@ -213,7 +551,7 @@ pub struct VersionNegotiation {
     supported_versions: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Initial {
     #[construct_with(u1)]
     header_form: HeaderForm,
@@ -244,7 +582,7 @@ pub struct Initial {
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct ZeroRtt {
     #[construct_with(u1)]
     header_form: HeaderForm,
@@ -271,7 +609,7 @@ pub struct ZeroRtt {
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Handshake {
     #[construct_with(u1)]
     header_form: HeaderForm,
@@ -298,7 +636,7 @@ pub struct Handshake {
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Retry {
     #[construct_with(u1)]
     header_form: HeaderForm,
@@ -315,10 +653,13 @@ pub struct Retry {
     src_id_len: u8,
     #[length = "src_id_len"]
     src_id: Vec<u8>,
+    #[length = "(...).len().saturating_sub(16)"]
     retry_token: Vec<u8>,
+    #[length = "16"]
+    integrity_tag: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct OneRtt {
     header_form: u1,
     fixed_bit: u1,
@@ -326,10 +667,8 @@ pub struct OneRtt {
     reserved_bits: u2,
     key_phase: u1,
     packet_number_len: u2,
-    #[length = "8"] // TODO: dest_id len
-    dest_id: Vec<u8>,
-    #[length = "packet_number_len + 1"]
-    packet_number: Vec<u8>,
+    // The destination connection id, whose length is not encoded in the packet, followed by the
+    // packet number and the frames.
     #[payload]
     payload: Vec<u8>,
 }
@@ -362,9 +701,9 @@ impl PrimitiveValues for HeaderForm {
 
 impl std::fmt::Display for HeaderForm {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let s = match self {
-            &HeaderForms::Short => "short",
-            &HeaderForms::Long => "long",
+        let s = match *self {
+            HeaderForms::Short => "short",
+            HeaderForms::Long => "long",
             _ => "unknown",
         };
         write!(f, "{}", s)
@@ -417,6 +756,26 @@ impl LongPacketType {
     pub fn new(val: u8) -> Self {
         Self(val)
     }
+
+    /// The packet type encoded as `bits` in a long header of `version`. QUIC v2 reassigns the
+    /// type codes (RFC 9369 section 3.2); the `long_packet_type` field of the packets holds the
+    /// encoded bits.
+    pub fn from_bits(bits: u8, version: Version) -> Self {
+        if version == Versions::V2 {
+            Self((bits + 3) & 0b11)
+        } else {
+            Self(bits)
+        }
+    }
+
+    /// The bits encoding this packet type in a long header of `version`.
+    pub fn to_bits(self, version: Version) -> u8 {
+        if version == Versions::V2 {
+            (self.0 + 1) & 0b11
+        } else {
+            self.0
+        }
+    }
 }
 
 impl PrimitiveValues for LongPacketType {
@@ -428,11 +787,11 @@ impl PrimitiveValues for LongPacketType {
 
 impl std::fmt::Display for LongPacketType {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let s = match self {
-            &LongPacketTypes::Initial => "initial",
-            &LongPacketTypes::ZeroRtt => "0-rtt",
-            &LongPacketTypes::Handshake => "handshake",
-            &LongPacketTypes::Retry => "retry",
+        let s = match *self {
+            LongPacketTypes::Initial => "initial",
+            LongPacketTypes::ZeroRtt => "0-rtt",
+            LongPacketTypes::Handshake => "handshake",
+            LongPacketTypes::Retry => "retry",
             _ => "unknown",
         };
         write!(f, "{}", s)
@@ -443,10 +802,42 @@ impl std::fmt::Display for LongPacketType {
 pub struct Version(pub u32);
 
 impl Version {
-    /// Construct a new `LongPacketType`.
+    /// Construct a new `Version`.
     pub fn new(val: u32) -> Self {
         Self(val)
     }
+
+    /// Whether this is a reserved version of the form `0x?a?a?a?a`, used to exercise version
+    /// negotiation (RFC 9000 section 15).
+    pub fn is_greased(&self) -> bool {
+        self.0 & 0x0f0f_0f0f == 0x0a0a_0a0a
+    }
+
+    /// Whether this is an IETF draft version.
+    pub fn is_draft(&self) -> bool {
+        self.0 & 0xffff_ff00 == 0xff00_0000
+    }
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod Versions {
+    use super::Version;
+
+    /// Reserved for Version Negotiation packets.
+    pub const Negotiation: Version = Version(0x0000_0000);
+    pub const V1: Version = Version(0x0000_0001);
+    pub const V2: Version = Version(0x6b33_43cf);
+    pub const Draft27: Version = Version(0xff00_001b);
+    pub const Draft28: Version = Version(0xff00_001c);
+    pub const Draft29: Version = Version(0xff00_001d);
+    pub const Draft30: Version = Version(0xff00_001e);
+    pub const Draft31: Version = Version(0xff00_001f);
+    pub const Draft32: Version = Version(0xff00_0020);
+    pub const Draft33: Version = Version(0xff00_0021);
+    pub const Draft34: Version = Version(0xff00_0022);
+    /// A reserved version; any version matching `0x?a?a?a?a` is reserved likewise.
+    pub const Greased: Version = Version(0x1a2a_3a4a);
 }
 
 impl PrimitiveValues for Version {
@@ -462,6 +853,15 @@ impl std::fmt::Display for Version {
     }
 }
 
+impl<'a> VersionNegotiationPacket<'a> {
+    /// The versions supported by the server.
+    pub fn supported_versions(&self) -> impl Iterator<Item = Version> + '_ {
+        self.get_supported_versions_raw()
+            .chunks_exact(4)
+            .map(|v| Version(u32::from_be_bytes([v[0], v[1], v[2], v[3]])))
+    }
+}
+
 #[derive(Debug)]
 pub enum Frame<'a> {
     Padding(PaddingPacket<'a>, usize),
@@ -484,49 +884,134 @@ pub enum Frame<'a> {
     PathResponse(PathResponsePacket<'a>),
     ConnectionClose(ConnectionClosePacket<'a>),
     HandshakeDone(HandshakeDonePacket<'a>),
+    Datagram(DatagramPacket<'a>),
+    ImmediateAck(ImmediateAckPacket<'a>),
+    AckFrequency(AckFrequencyPacket<'a>),
+    PathAck(PathAckPacket<'a>),
+    PathAbandon(PathAbandonPacket<'a>),
+    PathStatus(PathStatusPacket<'a>),
+    PathNewConnectionId(PathNewConnectionIdPacket<'a>),
+    PathRetireConnectionId(PathRetireConnectionIdPacket<'a>),
+    MaxPathId(MaxPathIdPacket<'a>),
+    PathsBlocked(PathsBlockedPacket<'a>),
+    PathCidsBlocked(PathCidsBlockedPacket<'a>),
+    /// A frame of an unknown type, or a known type with an overlong encoding. Its length is
+    /// unknown, so `rest` holds everything following the frame type.
+    Unknown {
+        ty: FrameType,
+        rest: &'a [u8],
+    },
+}
+
+/// Parses a frame with `new`, rejecting it if its fields extend past the end of `packet`.
+fn parse_frame<'a, P: PacketSize>(new: fn(&'a [u8]) -> Option<P>, packet: &'a [u8]) -> Option<P> {
+    new(packet).filter(|frame| frame.packet_size() <= packet.len())
 }
 
 impl<'a> Frame<'a> {
+    /// Parses the frames of a packet payload. Parsing stops at the first unknown frame, which is
+    /// returned as `Frame::Unknown`.
     pub fn new(mut packet: &'a [u8]) -> Option<Vec<Self>> {
         let mut frames = vec![];
-        while packet.len() > 0 {
-            let frame = match FrameType(packet[0]) {
-                FrameTypes::Padding => Self::Padding(PaddingPacket::new(packet)?, 1),
-                FrameTypes::Ping => Self::Ping(PingPacket::new(packet)?),
-                FrameTypes::Ack0 | FrameTypes::Ack1 => Self::Ack(AckPacket::new(packet)?),
-                FrameTypes::ResetStream => Self::ResetStream(ResetStreamPacket::new(packet)?),
-                FrameTypes::StopSending => Self::StopSending(StopSendingPacket::new(packet)?),
-                FrameTypes::Crypto => Self::Crypto(CryptoPacket::new(packet)?),
-                FrameTypes::NewToken => Self::NewToken(NewTokenPacket::new(packet)?),
-                x if (0x8..=0xf).contains(&x.0) => Self::Stream(StreamPacket::new(packet)?),
-                FrameTypes::MaxData => Self::MaxData(MaxDataPacket::new(packet)?),
-                FrameTypes::MaxStreamData => Self::MaxStreamData(MaxStreamDataPacket::new(packet)?),
+        while !packet.is_empty() {
+            let ty_len = varint_length(packet);
+            let ty = FrameType(varint_u64(packet.get(..ty_len)?));
+            let frame = match ty {
+                // the layouts of frame types below 0x40 expect a single byte type
+                _ if ty.0 < 0x40 && ty_len > 1 => Self::Unknown {
+                    ty,
+                    rest: &packet[ty_len..],
+                },
+                FrameTypes::Padding => Self::Padding(parse_frame(PaddingPacket::new, packet)?, 1),
+                FrameTypes::Ping => Self::Ping(parse_frame(PingPacket::new, packet)?),
+                FrameTypes::Ack0 | FrameTypes::Ack1 => {
+                    Self::Ack(parse_frame(AckPacket::new, packet)?)
+                }
+                FrameTypes::ResetStream => {
+                    Self::ResetStream(parse_frame(ResetStreamPacket::new, packet)?)
+                }
+                FrameTypes::StopSending => {
+                    Self::StopSending(parse_frame(StopSendingPacket::new, packet)?)
+                }
+                FrameTypes::Crypto => Self::Crypto(parse_frame(CryptoPacket::new, packet)?),
+                FrameTypes::NewToken => Self::NewToken(parse_frame(NewTokenPacket::new, packet)?),
+                x if (0x8..=0xf).contains(&x.0) => {
+                    Self::Stream(parse_frame(StreamPacket::new, packet)?)
+                }
+                FrameTypes::MaxData => Self::MaxData(parse_frame(MaxDataPacket::new, packet)?),
+                FrameTypes::MaxStreamData => {
+                    Self::MaxStreamData(parse_frame(MaxStreamDataPacket::new, packet)?)
+                }
                 FrameTypes::MaxStreams0 | FrameTypes::MaxStreams1 => {
-                    Self::MaxStreams(MaxStreamsPacket::new(packet)?)
+                    Self::MaxStreams(parse_frame(MaxStreamsPacket::new, packet)?)
+                }
+                FrameTypes::DataBlocked => {
+                    Self::DataBlocked(parse_frame(DataBlockedPacket::new, packet)?)
                 }
-                FrameTypes::DataBlocked => Self::DataBlocked(DataBlockedPacket::new(packet)?),
                 FrameTypes::StreamDataBlocked => {
-                    Self::StreamDataBlocked(StreamDataBlockedPacket::new(packet)?)
+                    Self::StreamDataBlocked(parse_frame(StreamDataBlockedPacket::new, packet)?)
                 }
                 FrameTypes::StreamsBlocked0 | FrameTypes::StreamsBlocked1 => {
-                    Self::StreamsBlocked(StreamsBlockedPacket::new(packet)?)
+                    Self::StreamsBlocked(parse_frame(StreamsBlockedPacket::new, packet)?)
                 }
                 FrameTypes::NewConnectionId => {
-                    Self::NewConnectionId(NewConnectionIdPacket::new(packet)?)
+                    Self::NewConnectionId(parse_frame(NewConnectionIdPacket::new, packet)?)
                 }
                 FrameTypes::RetireConnectionId => {
-                    Self::RetireConnectionId(RetireConnectionIdPacket::new(packet)?)
+                    Self::RetireConnectionId(parse_frame(RetireConnectionIdPacket::new, packet)?)
+                }
+                FrameTypes::PathChallenge => {
+                    Self::PathChallenge(parse_frame(PathChallengePacket::new, packet)?)
+                }
+                FrameTypes::PathResponse => {
+                    Self::PathResponse(parse_frame(PathResponsePacket::new, packet)?)
                 }
-                FrameTypes::PathChallenge => Self::PathChallenge(PathChallengePacket::new(packet)?),
-                FrameTypes::PathResponse => Self::PathResponse(PathResponsePacket::new(packet)?),
                 FrameTypes::ConnectionClose0 | FrameTypes::ConnectionClose1 => {
-                    Self::ConnectionClose(ConnectionClosePacket::new(packet)?)
+                    Self::ConnectionClose(parse_frame(ConnectionClosePacket::new, packet)?)
+                }
+                FrameTypes::HandshakeDone => {
+                    Self::HandshakeDone(parse_frame(HandshakeDonePacket::new, packet)?)
                 }
-                FrameTypes::HandshakeDone => Self::HandshakeDone(HandshakeDonePacket::new(packet)?),
-                _ => return None,
+                FrameTypes::Datagram0 | FrameTypes::Datagram1 => {
+                    Self::Datagram(parse_frame(DatagramPacket::new, packet)?)
+                }
+                FrameTypes::ImmediateAck => {
+                    Self::ImmediateAck(parse_frame(ImmediateAckPacket::new, packet)?)
+                }
+                FrameTypes::AckFrequency => {
+                    Self::AckFrequency(parse_frame(AckFrequencyPacket::new, packet)?)
+                }
+                FrameTypes::PathAck0 | FrameTypes::PathAck1 => {
+                    Self::PathAck(parse_frame(PathAckPacket::new, packet)?)
+                }
+                FrameTypes::PathAbandon => {
+                    Self::PathAbandon(parse_frame(PathAbandonPacket::new, packet)?)
+                }
+                FrameTypes::PathStatusBackup | FrameTypes::PathStatusAvailable => {
+                    Self::PathStatus(parse_frame(PathStatusPacket::new, packet)?)
+                }
+                FrameTypes::PathNewConnectionId => {
+                    Self::PathNewConnectionId(parse_frame(PathNewConnectionIdPacket::new, packet)?)
+                }
+                FrameTypes::PathRetireConnectionId => Self::PathRetireConnectionId(parse_frame(
+                    PathRetireConnectionIdPacket::new,
+                    packet,
+                )?),
+                FrameTypes::MaxPathId => {
+                    Self::MaxPathId(parse_frame(MaxPathIdPacket::new, packet)?)
+                }
+                FrameTypes::PathsBlocked => {
+                    Self::PathsBlocked(parse_frame(PathsBlockedPacket::new, packet)?)
+                }
+                FrameTypes::PathCidsBlocked => {
+                    Self::PathCidsBlocked(parse_frame(PathCidsBlockedPacket::new, packet)?)
+                }
+                _ => Self::Unknown {
+                    ty,
+                    rest: &packet[ty_len..],
+                },
             };
-            // lifetime of payload is 'a so this is safe to do.
-            packet = unsafe { std::mem::transmute(frame.remaining()) };
+            packet = &packet[packet.len() - frame.remaining().len()..];
             match (frames.last_mut(), frame) {
                 (Some(Frame::Padding(_, x)), Frame::Padding(_, y)) => *x += y,
                 (_, frame) => frames.push(frame),
@@ -535,28 +1020,41 @@ impl<'a> Frame<'a> {
         Some(frames)
     }
 
-    fn ty(&self) -> FrameType {
+    /// The frame type.
+    pub fn ty(&self) -> FrameType {
         match self {
-            Self::Padding(p, _) => p.get_ty(),
-            Self::Ping(p) => p.get_ty(),
-            Self::Ack(p) => p.get_ty(),
-            Self::ResetStream(p) => p.get_ty(),
-            Self::StopSending(p) => p.get_ty(),
-            Self::Crypto(p) => p.get_ty(),
-            Self::NewToken(p) => p.get_ty(),
-            Self::Stream(p) => p.get_ty(),
-            Self::MaxData(p) => p.get_ty(),
-            Self::MaxStreamData(p) => p.get_ty(),
-            Self::MaxStreams(p) => p.get_ty(),
-            Self::DataBlocked(p) => p.get_ty(),
-            Self::StreamDataBlocked(p) => p.get_ty(),
-            Self::StreamsBlocked(p) => p.get_ty(),
-            Self::NewConnectionId(p) => p.get_ty(),
-            Self::RetireConnectionId(p) => p.get_ty(),
-            Self::PathChallenge(p) => p.get_ty(),
-            Self::PathResponse(p) => p.get_ty(),
-            Self::ConnectionClose(p) => p.get_ty(),
-            Self::HandshakeDone(p) => p.get_ty(),
+            Self::Padding(p, _) => FrameType(p.get_ty().into()),
+            Self::Ping(p) => FrameType(p.get_ty().into()),
+            Self::Ack(p) => FrameType(p.get_ty().into()),
+            Self::ResetStream(p) => FrameType(p.get_ty().into()),
+            Self::StopSending(p) => FrameType(p.get_ty().into()),
+            Self::Crypto(p) => FrameType(p.get_ty().into()),
+            Self::NewToken(p) => FrameType(p.get_ty().into()),
+            Self::Stream(p) => FrameType(p.get_ty().into()),
+            Self::MaxData(p) => FrameType(p.get_ty().into()),
+            Self::MaxStreamData(p) => FrameType(p.get_ty().into()),
+            Self::MaxStreams(p) => FrameType(p.get_ty().into()),
+            Self::DataBlocked(p) => FrameType(p.get_ty().into()),
+            Self::StreamDataBlocked(p) => FrameType(p.get_ty().into()),
+            Self::StreamsBlocked(p) => FrameType(p.get_ty().into()),
+            Self::NewConnectionId(p) => FrameType(p.get_ty().into()),
+            Self::RetireConnectionId(p) => FrameType(p.get_ty().into()),
+            Self::PathChallenge(p) => FrameType(p.get_ty().into()),
+            Self::PathResponse(p) => FrameType(p.get_ty().into()),
+            Self::ConnectionClose(p) => FrameType(p.get_ty().into()),
+            Self::HandshakeDone(p) => FrameType(p.get_ty().into()),
+            Self::Datagram(p) => FrameType(p.get_ty().into()),
+            Self::ImmediateAck(p) => FrameType(p.get_ty().into()),
+            Self::AckFrequency(p) => FrameType(varint_u64(p.get_ty_raw())),
+            Self::PathAck(p) => FrameType(varint_u64(p.get_ty_raw())),
+            Self::PathAbandon(p) => FrameType(varint_u64(p.get_ty_raw())),
+            Self::PathStatus(p) => FrameType(varint_u64(p.get_ty_raw())),
+            Self::PathNewConnectionId(p) => FrameType(varint_u64(p.get_ty_raw())),
+            Self::PathRetireConnectionId(p) => FrameType(varint_u64(p.get_ty_raw())),
+            Self::MaxPathId(p) => FrameType(varint_u64(p.get_ty_raw())),
+            Self::PathsBlocked(p) => FrameType(varint_u64(p.get_ty_raw())),
+            Self::PathCidsBlocked(p) => FrameType(varint_u64(p.get_ty_raw())),
+            Self::Unknown { ty, .. } => *ty,
         }
     }
 
@@ -582,6 +1080,18 @@ impl<'a> Frame<'a> {
             Self::PathResponse(p) => p.payload(),
             Self::ConnectionClose(p) => p.payload(),
             Self::HandshakeDone(p) => p.payload(),
+            Self::Datagram(p) => p.payload(),
+            Self::ImmediateAck(p) => p.payload(),
+            Self::AckFrequency(p) => p.payload(),
+            Self::PathAck(p) => p.payload(),
+            Self::PathAbandon(p) => p.payload(),
+            Self::PathStatus(p) => p.payload(),
+            Self::PathNewConnectionId(p) => p.payload(),
+            Self::PathRetireConnectionId(p) => p.payload(),
+            Self::MaxPathId(p) => p.payload(),
+            Self::PathsBlocked(p) => p.payload(),
+            Self::PathCidsBlocked(p) => p.payload(),
+            Self::Unknown { rest, .. } => rest,
         }
     }
 
@@ -607,6 +1117,18 @@ impl<'a> Frame<'a> {
             Self::PathResponse(p) => p.get_remaining_raw(),
             Self::ConnectionClose(p) => p.get_remaining_raw(),
             Self::HandshakeDone(p) => p.get_remaining_raw(),
+            Self::Datagram(p) => p.get_remaining_raw(),
+            Self::ImmediateAck(p) => p.get_remaining_raw(),
+            Self::AckFrequency(p) => p.get_remaining_raw(),
+            Self::PathAck(p) => p.get_remaining_raw(),
+            Self::PathAbandon(p) => p.get_remaining_raw(),
+            Self::PathStatus(p) => p.get_remaining_raw(),
+            Self::PathNewConnectionId(p) => p.get_remaining_raw(),
+            Self::PathRetireConnectionId(p) => p.get_remaining_raw(),
+            Self::MaxPathId(p) => p.get_remaining_raw(),
+            Self::PathsBlocked(p) => p.get_remaining_raw(),
+            Self::PathCidsBlocked(p) => p.get_remaining_raw(),
+            Self::Unknown { .. } => &[],
         }
     }
 }
@@ -617,24 +1139,21 @@ impl<'a> std::fmt::Display for Frame<'a> {
     }
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Padding {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Ping {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Ack {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
     #[length = "varint_length(...)"]
     largest_acknowledged: Vec<u8>,
     #[length = "varint_length(...)"]
@@ -643,17 +1162,16 @@ pub struct Ack {
     ack_range_count: Vec<u8>,
     #[length = "varint_length(...)"]
     first_ack_range: Vec<u8>,
-    #[length = "n_varints(varint(&ack_range_count), ...)"]
+    #[length = "n_varints(2 * varint(&ack_range_count), ...)"]
     ack_range: Vec<u8>,
-    #[length = "n_varints(if ty.0 == 0x03 { 3 } else { 0 }, ...)"]
+    #[length = "n_varints(if ty == 0x03 { 3 } else { 0 }, ...)"]
     ecn_counts: Vec<u8>,
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct ResetStream {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
     #[length = "varint_length(...)"]
     stream_id: Vec<u8>,
     #[length = "varint_length(...)"]
@@ -663,10 +1181,9 @@ pub struct ResetStream {
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct StopSending {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
     #[length = "varint_length(...)"]
     stream_id: Vec<u8>,
     #[length = "varint_length(...)"]
@@ -674,10 +1191,9 @@ pub struct StopSending {
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Crypto {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
     #[length = "varint_length(...)"]
     offset: Vec<u8>,
     #[length = "varint_length(...)"]
@@ -687,10 +1203,9 @@ pub struct Crypto {
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct NewToken {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
     #[length = "varint_length(...)"]
     token_length: Vec<u8>,
     #[length = "varint(&token_length)"]
@@ -698,15 +1213,14 @@ pub struct NewToken {
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Stream {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
     #[length = "varint_length(...)"]
     stream_id: Vec<u8>,
-    #[length = "n_varints(if ty.0 & 0x04 > 0 { 1 } else { 0 }, ...)"]
+    #[length = "n_varints(if ty & 0x04 > 0 { 1 } else { 0 }, ...)"]
     offset: Vec<u8>,
-    #[length = "n_varints(if ty.0 & 0x02 > 0 { 1 } else { 0 }, ...)"]
+    #[length = "n_varints(if ty & 0x02 > 0 { 1 } else { 0 }, ...)"]
     length: Vec<u8>,
     #[payload]
     #[length = "if length.is_empty() { (...).len() } else { varint(&length) }"]
@@ -714,19 +1228,17 @@ pub struct Stream {
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct MaxData {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
     #[length = "varint_length(...)"]
     max_data: Vec<u8>,
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct MaxStreamData {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
     #[length = "varint_length(...)"]
     stream_id: Vec<u8>,
     #[length = "varint_length(...)"]
@@ -734,28 +1246,25 @@ pub struct MaxStreamData {
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct MaxStreams {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
     #[length = "varint_length(...)"]
     max_streams: Vec<u8>,
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct DataBlocked {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
     #[length = "varint_length(...)"]
     max_data: Vec<u8>,
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct StreamDataBlocked {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
     #[length = "varint_length(...)"]
     stream_id: Vec<u8>,
     #[length = "varint_length(...)"]
@@ -763,19 +1272,17 @@ pub struct StreamDataBlocked {
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct StreamsBlocked {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
     #[length = "varint_length(...)"]
     max_streams: Vec<u8>,
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct NewConnectionId {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
     #[length = "varint_length(...)"]
     sequence_number: Vec<u8>,
     #[length = "varint_length(...)"]
@@ -788,40 +1295,36 @@ pub struct NewConnectionId {
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct RetireConnectionId {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
     #[length = "varint_length(...)"]
     sequence_number: Vec<u8>,
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct PathChallenge {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
     #[length = "8"]
     data: Vec<u8>,
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct PathResponse {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
     #[length = "8"]
     data: Vec<u8>,
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct ConnectionClose {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
     #[length = "varint_length(...)"]
     error_code: Vec<u8>,
-    #[length = "n_varints(if ty.0 == 0x1d { 0 } else { 1 }, ...)"]
+    #[length = "n_varints(if ty == 0x1d { 0 } else { 1 }, ...)"]
     frame_type: Vec<u8>,
     #[length = "varint_length(...)"]
     reason_phrase_length: Vec<u8>,
@@ -830,10 +1333,153 @@ pub struct ConnectionClose {
     remaining: Vec<u8>,
 }
 
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct HandshakeDone {
-    #[construct_with(u8)]
-    ty: FrameType,
+    ty: u8,
+    remaining: Vec<u8>,
+}
+
+/// A DATAGRAM frame (RFC 9221).
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct Datagram {
+    ty: u8,
+    #[length = "n_varints(if ty & 0x01 > 0 { 1 } else { 0 }, ...)"]
+    length: Vec<u8>,
+    #[payload]
+    #[length = "if length.is_empty() { (...).len() } else { varint(&length) }"]
+    datagram_data: Vec<u8>,
+    remaining: Vec<u8>,
+}
+
+/// An ACK_FREQUENCY frame (draft-ietf-quic-ack-frequency).
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct AckFrequency {
+    #[length = "varint_length(...)"]
+    ty: Vec<u8>,
+    #[length = "varint_length(...)"]
+    sequence_number: Vec<u8>,
+    #[length = "varint_length(...)"]
+    ack_eliciting_threshold: Vec<u8>,
+    #[length = "varint_length(...)"]
+    requested_max_ack_delay: Vec<u8>,
+    #[length = "varint_length(...)"]
+    reordering_threshold: Vec<u8>,
+    remaining: Vec<u8>,
+}
+
+/// An IMMEDIATE_ACK frame (draft-ietf-quic-ack-frequency).
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct ImmediateAck {
+    ty: u8,
+    remaining: Vec<u8>,
+}
+
+/// A PATH_ACK frame (draft-ietf-quic-multipath), an ACK frame for a single path.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct PathAck {
+    #[length = "varint_length(...)"]
+    ty: Vec<u8>,
+    #[length = "varint_length(...)"]
+    path_id: Vec<u8>,
+    #[length = "varint_length(...)"]
+    largest_acknowledged: Vec<u8>,
+    #[length = "varint_length(...)"]
+    ack_delay: Vec<u8>,
+    #[length = "varint_length(...)"]
+    ack_range_count: Vec<u8>,
+    #[length = "varint_length(...)"]
+    first_ack_range: Vec<u8>,
+    #[length = "n_varints(2 * varint(&ack_range_count), ...)"]
+    ack_range: Vec<u8>,
+    #[length = "n_varints(if varint_u64(&ty) & 0x01 > 0 { 3 } else { 0 }, ...)"]
+    ecn_counts: Vec<u8>,
+    remaining: Vec<u8>,
+}
+
+/// A PATH_ABANDON frame (draft-ietf-quic-multipath).
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct PathAbandon {
+    #[length = "varint_length(...)"]
+    ty: Vec<u8>,
+    #[length = "varint_length(...)"]
+    path_id: Vec<u8>,
+    #[length = "varint_length(...)"]
+    error_code: Vec<u8>,
+    remaining: Vec<u8>,
+}
+
+/// A PATH_STATUS_BACKUP or PATH_STATUS_AVAILABLE frame (draft-ietf-quic-multipath).
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct PathStatus {
+    #[length = "varint_length(...)"]
+    ty: Vec<u8>,
+    #[length = "varint_length(...)"]
+    path_id: Vec<u8>,
+    #[length = "varint_length(...)"]
+    sequence_number: Vec<u8>,
+    remaining: Vec<u8>,
+}
+
+/// A PATH_NEW_CONNECTION_ID frame (draft-ietf-quic-multipath).
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct PathNewConnectionId {
+    #[length = "varint_length(...)"]
+    ty: Vec<u8>,
+    #[length = "varint_length(...)"]
+    path_id: Vec<u8>,
+    #[length = "varint_length(...)"]
+    sequence_number: Vec<u8>,
+    #[length = "varint_length(...)"]
+    retire_prior_to: Vec<u8>,
+    length: u8,
+    #[length = "length"]
+    connection_id: Vec<u8>,
+    #[length = "16"]
+    stateless_reset_token: Vec<u8>,
+    remaining: Vec<u8>,
+}
+
+/// A PATH_RETIRE_CONNECTION_ID frame (draft-ietf-quic-multipath).
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct PathRetireConnectionId {
+    #[length = "varint_length(...)"]
+    ty: Vec<u8>,
+    #[length = "varint_length(...)"]
+    path_id: Vec<u8>,
+    #[length = "varint_length(...)"]
+    sequence_number: Vec<u8>,
+    remaining: Vec<u8>,
+}
+
+/// A MAX_PATH_ID frame (draft-ietf-quic-multipath).
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct MaxPathId {
+    #[length = "varint_length(...)"]
+    ty: Vec<u8>,
+    #[length = "varint_length(...)"]
+    max_path_id: Vec<u8>,
+    remaining: Vec<u8>,
+}
+
+/// A PATHS_BLOCKED frame (draft-ietf-quic-multipath).
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct PathsBlocked {
+    #[length = "varint_length(...)"]
+    ty: Vec<u8>,
+    #[length = "varint_length(...)"]
+    max_path_id: Vec<u8>,
+    remaining: Vec<u8>,
+}
+
+/// A PATH_CIDS_BLOCKED frame (draft-ietf-quic-multipath).
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct PathCidsBlocked {
+    #[length = "varint_length(...)"]
+    ty: Vec<u8>,
+    #[length = "varint_length(...)"]
+    path_id: Vec<u8>,
+    #[length = "varint_length(...)"]
+    next_sequence_number: Vec<u8>,
     remaining: Vec<u8>,
 }
 
@@ -873,20 +1519,35 @@ pub mod FrameTypes {
     pub const ConnectionClose0: FrameType = FrameType(0x1c);
     pub const ConnectionClose1: FrameType = FrameType(0x1d);
     pub const HandshakeDone: FrameType = FrameType(0x1e);
+    pub const ImmediateAck: FrameType = FrameType(0x1f);
+    pub const Datagram0: FrameType = FrameType(0x30);
+    pub const Datagram1: FrameType = FrameType(0x31);
+    pub const AckFrequency: FrameType = FrameType(0xaf);
+    pub const PathAck0: FrameType = FrameType(0x15228c00);
+    pub const PathAck1: FrameType = FrameType(0x15228c01);
+    pub const PathAbandon: FrameType = FrameType(0x15228c05);
+    pub const PathStatusBackup: FrameType = FrameType(0x15228c07);
+    pub const PathStatusAvailable: FrameType = FrameType(0x15228c08);
+    pub const PathNewConnectionId: FrameType = FrameType(0x15228c09);
+    pub const PathRetireConnectionId: FrameType = FrameType(0x15228c0a);
+    pub const MaxPathId: FrameType = FrameType(0x15228c0c);
+    pub const PathsBlocked: FrameType = FrameType(0x15228c0d);
+    pub const PathCidsBlocked: FrameType = FrameType(0x15228c0e);
 }
 
+/// A frame type, encoded as a variable-length integer.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct FrameType(pub u8);
+pub struct FrameType(pub u64);
 
 impl FrameType {
     /// Construct a new `FrameType`.
-    pub fn new(val: u8) -> Self {
+    pub fn new(val: u64) -> Self {
         Self(val)
     }
 }
 
 impl PrimitiveValues for FrameType {
-    type T = (u8,);
+    type T = (u64,);
     fn to_primitive_values(&self) -> Self::T {
         (self.0,)
     }
@@ -894,29 +1555,340 @@ impl PrimitiveValues for FrameType {
 
 impl std::fmt::Display for FrameType {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let s = match self {
-            &FrameTypes::Padding => "padding",
-            &FrameTypes::Ping => "ping",
-            &FrameTypes::Ack0 | &FrameTypes::Ack1 => "ack",
-            &FrameTypes::ResetStream => "reset-stream",
-            &FrameTypes::StopSending => "stop-sending",
-            &FrameTypes::Crypto => "crypto",
-            &FrameTypes::NewToken => "new-token",
+        let s = match *self {
+            FrameTypes::Padding => "padding",
+            FrameTypes::Ping => "ping",
+            FrameTypes::Ack0 | FrameTypes::Ack1 => "ack",
+            FrameTypes::ResetStream => "reset-stream",
+            FrameTypes::StopSending => "stop-sending",
+            FrameTypes::Crypto => "crypto",
+            FrameTypes::NewToken => "new-token",
             x if (0x8..=0xf).contains(&x.0) => "stream",
-            &FrameTypes::MaxData => "max-data",
-            &FrameTypes::MaxStreamData => "max-stream-data",
-            &FrameTypes::MaxStreams0 | &FrameTypes::MaxStreams1 => "max-streams",
-            &FrameTypes::DataBlocked => "data-blocked",
-            &FrameTypes::StreamDataBlocked => "stream-data-blocked",
-            &FrameTypes::StreamsBlocked0 | &FrameTypes::StreamsBlocked1 => "streams-blocked",
-            &FrameTypes::NewConnectionId => "new-connection-id",
-            &FrameTypes::RetireConnectionId => "retire-connection-id",
-            &FrameTypes::PathChallenge => "path-challenge",
-            &FrameTypes::PathResponse => "path-response",
-            &FrameTypes::ConnectionClose0 | &FrameTypes::ConnectionClose1 => "connection-close",
-            &FrameTypes::HandshakeDone => "handshake-done",
+            FrameTypes::MaxData => "max-data",
+            FrameTypes::MaxStreamData => "max-stream-data",
+            FrameTypes::MaxStreams0 | FrameTypes::MaxStreams1 => "max-streams",
+            FrameTypes::DataBlocked => "data-blocked",
+            FrameTypes::StreamDataBlocked => "stream-data-blocked",
+            FrameTypes::StreamsBlocked0 | FrameTypes::StreamsBlocked1 => "streams-blocked",
+            FrameTypes::NewConnectionId => "new-connection-id",
+            FrameTypes::RetireConnectionId => "retire-connection-id",
+            FrameTypes::PathChallenge => "path-challenge",
+            FrameTypes::PathResponse => "path-response",
+            FrameTypes::ConnectionClose0 | FrameTypes::ConnectionClose1 => "connection-close",
+            FrameTypes::HandshakeDone => "handshake-done",
+            FrameTypes::ImmediateAck => "immediate-ack",
+            FrameTypes::Datagram0 | FrameTypes::Datagram1 => "datagram",
+            FrameTypes::AckFrequency => "ack-frequency",
+            FrameTypes::PathAck0 | FrameTypes::PathAck1 => "path-ack",
+            FrameTypes::PathAbandon => "path-abandon",
+            FrameTypes::PathStatusBackup => "path-status-backup",
+            FrameTypes::PathStatusAvailable => "path-status-available",
+            FrameTypes::PathNewConnectionId => "path-new-connection-id",
+            FrameTypes::PathRetireConnectionId => "path-retire-connection-id",
+            FrameTypes::MaxPathId => "max-path-id",
+            FrameTypes::PathsBlocked => "paths-blocked",
+            FrameTypes::PathCidsBlocked => "path-cids-blocked",
             _ => "unknown",
         };
         write!(f, "{}", s)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COALESCED: [u8; 25] = [
+        0xc0, /* long header, initial, 1 byte packet number */
+        0x00, 0x00, 0x00, 0x01, /* version */
+        0x01, 0xaa, /* dest id */
+        0x00, /* src id */
+        0x00, /* token length */
+        0x03, /* length */
+        0x07, /* packet number */
+        0x01, 0x00, /* frames */
+        0xe0, /* long header, handshake, 1 byte packet number */
+        0x00, 0x00, 0x00, 0x01, /* version */
+        0x01, 0xbb, /* dest id */
+        0x01, 0xcc, /* src id */
+        0x02, /* length */
+        0x08, /* packet number */
+        0x01, /* frames */
+    ];
+
+    fn first_dest_id(buf: &[u8]) -> &[u8] {
+        QuicPacket::new(buf).unwrap()[0].dest_id_borrowed().unwrap()
+    }
+
+    #[test]
+    fn coalesced_packets() {
+        let packets = QuicPacket::new(&COALESCED[..]).unwrap();
+        assert_eq!(packets.len(), 2);
+        assert!(matches!(packets[0], QuicPacket::Initial(_)));
+        assert!(matches!(packets[1], QuicPacket::Handshake(_)));
+        assert_eq!(packets[0].remaining(), &COALESCED[13..]);
+        assert_eq!(packets[1].packet(), &COALESCED[13..]);
+        assert_eq!(packets[0].frames(), Some(&[0x01, 0x00][..]));
+        assert_eq!(packets[1].frames(), Some(&[0x01][..]));
+        assert_eq!(packets[1].dest_id(), &[0xbb]);
+        assert_eq!(packets[1].src_id(), Some(&[0xcc][..]));
+        assert_eq!(first_dest_id(&COALESCED[..]), &[0xaa]);
+    }
+
+    #[test]
+    fn owned_packet_accessors() {
+        let initial = InitialPacket::owned(COALESCED[..13].to_vec()).unwrap();
+        let quic = QuicPacket::Initial(initial);
+        assert_eq!(quic.dest_id(), &[0xaa]);
+        assert_eq!(quic.src_id(), Some(&[][..]));
+        assert_eq!(quic.token(), Some(&[][..]));
+        assert_eq!(quic.dest_id_borrowed(), None);
+        assert_eq!(quic.src_id_borrowed(), None);
+        assert_eq!(quic.token_borrowed(), None);
+    }
+
+    #[test]
+    fn merged_padding() {
+        let buf = [0x01, 0x00, 0x00, 0x00, 0x01];
+        let frames = Frame::new(&buf[..]).unwrap();
+        assert_eq!(frames.len(), 3);
+        assert!(matches!(frames[0], Frame::Ping(_)));
+        assert_eq!(frames[0].remaining(), &buf[1..]);
+        match &frames[1] {
+            Frame::Padding(p, n) => {
+                assert_eq!(*n, 3);
+                assert_eq!(p.packet(), &buf[1..]);
+            }
+            f => panic!("expected padding, got {}", f),
+        }
+        assert!(matches!(frames[2], Frame::Ping(_)));
+        assert!(frames[2].remaining().is_empty());
+    }
+
+    #[test]
+    fn short_header_dest_id_len() {
+        let handshake = [
+            0xe0, /* long header, handshake, 1 byte packet number */
+            0x00, 0x00, 0x00, 0x01, /* version */
+            0x01, 0xbb, /* dest id */
+            0x03, 0xcc, 0xdd, 0xee, /* src id */
+            0x02, /* length */
+            0x08, /* packet number */
+            0x01, /* frames */
+        ];
+        let one_rtt = [
+            0x40, /* short header, 1 byte packet number */
+            0xcc, 0xdd, 0xee, /* dest id */
+            0x09, /* packet number */
+            0x01, 0x00, /* frames */
+        ];
+        let mut datagram = handshake.to_vec();
+        datagram.extend_from_slice(&one_rtt);
+        let packets = QuicPacket::new(&datagram).unwrap();
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[1].dest_id(), &[0xcc, 0xdd, 0xee]);
+        assert_eq!(packets[1].packet_number(), Some(9));
+        assert_eq!(packets[1].frames(), Some(&[0x01, 0x00][..]));
+
+        let mut parser = QuicParser::with_dest_id_len(0);
+        let packets = parser.parse(&one_rtt).unwrap();
+        assert!(packets[0].dest_id().is_empty());
+        assert_eq!(packets[0].packet_number(), Some(0xcc));
+        parser.parse(&handshake).unwrap();
+        assert_eq!(parser.dest_id_len(&one_rtt[1..]), 3);
+        assert_eq!(
+            parser.parse(&one_rtt).unwrap()[0].dest_id(),
+            &[0xcc, 0xdd, 0xee]
+        );
+        assert!(QuicPacket::new(&one_rtt).is_none());
+    }
+
+    #[test]
+    fn per_flow_connection_ids() {
+        let handshake = [
+            0xe0, /* long header, handshake, 1 byte packet number */
+            0x00, 0x00, 0x00, 0x01, /* version */
+            0x00, /* dest id */
+            0x03, 0xcc, 0xdd, 0xee, /* src id */
+            0x02, /* length */
+            0x08, /* packet number */
+            0x01, /* frames */
+        ];
+        let one_rtt = [
+            0x40, /* short header, 1 byte packet number */
+            0xcc, 0xdd, 0xee, /* dest id */
+            0x09, /* packet number */
+            0x01, 0x00, /* frames */
+        ];
+        let client: SocketAddr = "10.0.0.1:4433".parse().unwrap();
+        let server: SocketAddr = "10.0.0.2:443".parse().unwrap();
+        let other: SocketAddr = "10.0.0.3:443".parse().unwrap();
+
+        let mut parser = QuicParser::with_dest_id_len(2);
+        parser.parse_flow(server, client, &handshake).unwrap();
+        let packets = parser.parse_flow(client, server, &one_rtt).unwrap();
+        assert_eq!(packets[0].dest_id(), &[0xcc, 0xdd, 0xee]);
+        let packets = parser.parse_flow(client, other, &one_rtt).unwrap();
+        assert_eq!(packets[0].dest_id(), &[0xcc, 0xdd]);
+        assert_eq!(parser.dest_id_len(&one_rtt[1..]), 2);
+
+        // the learned zero length id doesn't override the default
+        let unknown = [0x40, 0x11, 0x22, 0x33, 0x01];
+        let packets = parser.parse_flow(server, client, &unknown).unwrap();
+        assert_eq!(packets[0].dest_id(), &[0x11, 0x22]);
+        let packets = parser.parse_flow(server, client, &unknown[..3]).unwrap();
+        assert!(packets[0].dest_id().is_empty());
+
+        parser.forget_flow(server, client);
+        let packets = parser.parse_flow(client, server, &one_rtt).unwrap();
+        assert_eq!(packets[0].dest_id(), &[0xcc, 0xdd]);
+
+        parser.parse(&handshake).unwrap();
+        assert_eq!(parser.dest_id_len(&one_rtt[1..]), 3);
+        parser.forget();
+        assert_eq!(parser.dest_id_len(&one_rtt[1..]), 2);
+    }
+
+    #[test]
+    fn short_header_bad_dest_id_len() {
+        let buf = [0x40, 0xcc, 0x09];
+        let quic = QuicPacket::OneRtt(OneRttPacket::new(&buf).unwrap(), 4);
+        assert!(quic.dest_id().is_empty());
+        assert_eq!(quic.dest_id_borrowed(), None);
+        assert_eq!(quic.packet_number(), None);
+        assert_eq!(quic.frames(), None);
+    }
+
+    #[test]
+    fn version_2_packet_types() {
+        let mut packet = [
+            0xd0, /* long header, type bits 01, 1 byte packet number */
+            0x6b, 0x33, 0x43, 0xcf, /* version */
+            0x00, /* dest id */
+            0x00, /* src id */
+            0x00, /* token length */
+            0x02, /* length */
+            0x00, /* packet number */
+            0x01, /* frames */
+        ];
+        let packets = QuicPacket::new(&packet).unwrap();
+        assert!(matches!(packets[0], QuicPacket::Initial(_)));
+        assert_eq!(packets[0].version(), Some(Versions::V2));
+        packet[1..5].copy_from_slice(&[0, 0, 0, 1]);
+        packet[0] = 0xc0;
+        assert!(matches!(
+            QuicPacket::new(&packet).unwrap()[0],
+            QuicPacket::Initial(_)
+        ));
+        let zero_rtt = [0xd0, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x02, 0x00, 0x01];
+        assert!(matches!(
+            QuicPacket::new(&zero_rtt).unwrap()[0],
+            QuicPacket::ZeroRtt(_)
+        ));
+
+        for ty in 0..4 {
+            let ty = LongPacketType(ty);
+            for version in [Versions::V1, Versions::V2, Versions::Draft29] {
+                assert_eq!(LongPacketType::from_bits(ty.to_bits(version), version), ty);
+            }
+        }
+        assert_eq!(LongPacketTypes::Retry.to_bits(Versions::V2), 0b00);
+        assert_eq!(LongPacketTypes::Handshake.to_bits(Versions::V2), 0b11);
+    }
+
+    #[test]
+    fn supported_versions() {
+        let packet = [
+            0x80, /* long header */
+            0x00, 0x00, 0x00, 0x00, /* version */
+            0x01, 0xaa, /* dest id */
+            0x00, /* src id */
+            0x6b, 0x33, 0x43, 0xcf, 0x00, 0x00, 0x00, 0x01, 0xff, 0x00, 0x00, 0x1d, 0x3a, 0x4a,
+            0x5a, 0x6a, /* supported versions */
+        ];
+        let packets = QuicPacket::new(&packet).unwrap();
+        let versions: Vec<_> = match &packets[0] {
+            QuicPacket::VersionNegotiation(vn) => vn.supported_versions().collect(),
+            p => panic!("expected version negotiation, got {}", p),
+        };
+        assert_eq!(
+            versions,
+            vec![
+                Versions::V2,
+                Versions::V1,
+                Versions::Draft29,
+                Version(0x3a4a_5a6a)
+            ]
+        );
+        assert!(versions[3].is_greased());
+        assert!(Versions::Greased.is_greased());
+        assert!(!Versions::V2.is_greased());
+        assert!(versions[2].is_draft());
+        assert!(!versions[1].is_draft());
+    }
+
+    #[test]
+    fn packet_number_decoding() {
+        assert_eq!(
+            decode_packet_number(Some(0xa82f30ea), 0x9b32, 2),
+            Some(0xa82f9b32)
+        );
+        assert_eq!(decode_packet_number(None, 0, 1), Some(0));
+        assert_eq!(decode_packet_number(Some(0xff), 0x01, 1), Some(0x101));
+        assert_eq!(decode_packet_number(Some(0x101), 0xff, 1), Some(0xff));
+        assert_eq!(
+            decode_packet_number(Some(654360563), 0xbff4, 3),
+            Some(654360564)
+        );
+        assert_eq!(
+            decode_packet_number(Some(0xffff_fffe), 0xffff_ffff, 4),
+            Some(0xffff_ffff)
+        );
+        for pn_len in &[0, 5, 7, 8, 9] {
+            assert_eq!(decode_packet_number(Some(0x1234), 0x34, *pn_len), None);
+        }
+    }
+
+    #[test]
+    fn stateless_reset() {
+        let mut frames = vec![];
+        frame::encode_new_connection_id(
+            &mut frames,
+            &frame::NewConnectionIdFrame {
+                sequence_number: 1,
+                retire_prior_to: 0,
+                connection_id: &[5; 8],
+                stateless_reset_token: &[0xee; 16],
+            },
+        );
+        let mut tokens = StatelessResetTokens::new();
+        tokens.insert_frames(&Frame::new(&frames).unwrap());
+        assert!(!tokens.insert(&[0; 15]));
+
+        let mut datagram = vec![0x5a; 8];
+        datagram.extend_from_slice(&[0xee; 16]);
+        assert!(tokens.is_stateless_reset(&datagram));
+        assert!(tokens.is_stateless_reset(&datagram[3..]));
+        assert!(!tokens.is_stateless_reset(&datagram[4..]));
+        datagram[0] = 0xca;
+        assert!(!tokens.is_stateless_reset(&datagram));
+        datagram[0] = 0x5a;
+        *datagram.last_mut().unwrap() = 0;
+        assert!(!tokens.is_stateless_reset(&datagram));
+    }
+
+    #[test]
+    fn short_retry() {
+        let mut packet = vec![0xf0, 0, 0, 0, 1, 0, 0];
+        packet.extend_from_slice(&[0; 15]);
+        assert!(QuicPacket::new(&packet).is_none());
+        packet.push(0);
+        match &QuicPacket::new(&packet).unwrap()[0] {
+            QuicPacket::Retry(retry) => {
+                assert!(retry.get_retry_token_raw().is_empty());
+                assert_eq!(retry.get_integrity_tag_raw(), &[0; 16][..]);
+            }
+            p => panic!("expected retry, got {}", p),
+        }
+    }
+}
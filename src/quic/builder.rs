@@ -0,0 +1,347 @@
+//! Assembling QUIC packets from encoded frames (see `quic::frame`).
+
+#[cfg(feature = "crypto")]
+use super::crypto::{apply_header_protection, retry_integrity_tag, Keys, SAMPLE_LEN, TAG_LEN};
+use super::{
+    encode_varint, encoded_varint_length, LongPacketType, LongPacketTypes, Version, Versions,
+};
+use std::convert::TryFrom;
+
+/// The minimum size of a datagram carrying an Initial packet (RFC 9000 section 14.1).
+pub const MIN_INITIAL_SIZE: usize = 1200;
+
+/// The packet types assembled by `PacketBuilder`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PacketKind {
+    Initial,
+    ZeroRtt,
+    Handshake,
+    OneRtt,
+}
+
+/// Builds Initial, 0-RTT, Handshake and 1-RTT packets, computing the `length` field.
+///
+/// Initial packets are padded to `MIN_INITIAL_SIZE` bytes unless `min_size` says otherwise.
+#[derive(Clone, Debug)]
+pub struct PacketBuilder<'a> {
+    kind: PacketKind,
+    version: Version,
+    dest_id: &'a [u8],
+    src_id: &'a [u8],
+    token: &'a [u8],
+    packet_number: u64,
+    packet_number_len: usize,
+    spin: bool,
+    key_phase: bool,
+    min_size: usize,
+}
+
+impl<'a> PacketBuilder<'a> {
+    fn new(kind: PacketKind, version: Version, dest_id: &'a [u8], src_id: &'a [u8]) -> Self {
+        Self {
+            kind,
+            version,
+            dest_id,
+            src_id,
+            token: &[],
+            packet_number: 0,
+            packet_number_len: 1,
+            spin: false,
+            key_phase: false,
+            min_size: if kind == PacketKind::Initial {
+                MIN_INITIAL_SIZE
+            } else {
+                0
+            },
+        }
+    }
+
+    /// An Initial packet carrying `token`, which is empty unless responding to a Retry or
+    /// using a NEW_TOKEN token.
+    pub fn initial(version: Version, dest_id: &'a [u8], src_id: &'a [u8], token: &'a [u8]) -> Self {
+        Self {
+            token,
+            ..Self::new(PacketKind::Initial, version, dest_id, src_id)
+        }
+    }
+
+    /// A 0-RTT packet.
+    pub fn zero_rtt(version: Version, dest_id: &'a [u8], src_id: &'a [u8]) -> Self {
+        Self::new(PacketKind::ZeroRtt, version, dest_id, src_id)
+    }
+
+    /// A Handshake packet.
+    pub fn handshake(version: Version, dest_id: &'a [u8], src_id: &'a [u8]) -> Self {
+        Self::new(PacketKind::Handshake, version, dest_id, src_id)
+    }
+
+    /// A 1-RTT packet with a short header.
+    pub fn one_rtt(dest_id: &'a [u8]) -> Self {
+        Self::new(PacketKind::OneRtt, Versions::Negotiation, dest_id, &[])
+    }
+
+    /// Sets the packet number and the number of bytes (1 to 4) used to encode it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is not between 1 and 4.
+    pub fn packet_number(mut self, packet_number: u64, len: usize) -> Self {
+        assert!(
+            (1..=4).contains(&len),
+            "invalid packet number length {}",
+            len
+        );
+        self.packet_number = packet_number;
+        self.packet_number_len = len;
+        self
+    }
+
+    /// Sets the spin bit of a 1-RTT packet.
+    pub fn spin(mut self, spin: bool) -> Self {
+        self.spin = spin;
+        self
+    }
+
+    /// Sets the key phase bit of a 1-RTT packet.
+    pub fn key_phase(mut self, key_phase: bool) -> Self {
+        self.key_phase = key_phase;
+        self
+    }
+
+    /// Pads the payload with PADDING frames until the packet is at least `size` bytes long.
+    pub fn min_size(mut self, size: usize) -> Self {
+        self.min_size = size;
+        self
+    }
+
+    fn long_packet_type(&self) -> Option<LongPacketType> {
+        match self.kind {
+            PacketKind::Initial => Some(LongPacketTypes::Initial),
+            PacketKind::ZeroRtt => Some(LongPacketTypes::ZeroRtt),
+            PacketKind::Handshake => Some(LongPacketTypes::Handshake),
+            PacketKind::OneRtt => None,
+        }
+    }
+
+    /// Returns the header, including the packet number, and the padded payload of a packet
+    /// whose protected payload grows by `tag_len` bytes and must be at least `min_payload`
+    /// bytes long.
+    fn assemble(&self, frames: &[u8], tag_len: usize, min_payload: usize) -> (Vec<u8>, Vec<u8>) {
+        let pn_len = self.packet_number_len;
+        let mut payload = frames.to_vec();
+        if payload.len() < min_payload {
+            payload.resize(min_payload, 0);
+        }
+        let mut header = vec![];
+        match self.long_packet_type() {
+            Some(ty) => {
+                header.push(0xc0 | ty.to_bits(self.version) << 4 | (pn_len - 1) as u8);
+                header.extend_from_slice(&self.version.0.to_be_bytes());
+                push_connection_id(&mut header, self.dest_id);
+                push_connection_id(&mut header, self.src_id);
+                if self.kind == PacketKind::Initial {
+                    encode_varint(self.token.len() as u64, &mut header);
+                    header.extend_from_slice(self.token);
+                }
+                let fixed = header.len() + pn_len + tag_len;
+                let size = |payload_len: usize| {
+                    let length = (pn_len + payload_len + tag_len) as u64;
+                    fixed + encoded_varint_length(length) + payload_len
+                };
+                if size(payload.len()) < self.min_size {
+                    // the length field may grow with the padding, so search downwards
+                    let mut len = self.min_size - fixed - 1;
+                    while size(len) > self.min_size {
+                        len -= 1;
+                    }
+                    if size(len) < self.min_size {
+                        len += 1;
+                    }
+                    payload.resize(len, 0);
+                }
+                encode_varint((pn_len + payload.len() + tag_len) as u64, &mut header);
+            }
+            None => {
+                let spin = if self.spin { 0x20 } else { 0 };
+                let key_phase = if self.key_phase { 0x04 } else { 0 };
+                header.push(0x40 | spin | key_phase | (pn_len - 1) as u8);
+                header.extend_from_slice(self.dest_id);
+                let size = header.len() + pn_len + payload.len() + tag_len;
+                if size < self.min_size {
+                    payload.resize(payload.len() + self.min_size - size, 0);
+                }
+            }
+        }
+        header.extend_from_slice(&self.packet_number.to_be_bytes()[8 - pn_len..]);
+        (header, payload)
+    }
+
+    /// Assembles an unprotected packet carrying the encoded `frames`.
+    pub fn build(&self, frames: &[u8]) -> Vec<u8> {
+        let (mut packet, payload) = self.assemble(frames, 0, 0);
+        packet.extend_from_slice(&payload);
+        packet
+    }
+
+    /// Assembles a packet carrying the encoded `frames`, with payload and header protection
+    /// applied using `keys`. The payload is padded so that it can be sampled for header
+    /// protection.
+    #[cfg(feature = "crypto")]
+    pub fn build_protected(&self, frames: &[u8], keys: &Keys) -> Vec<u8> {
+        let min_payload = (4 + SAMPLE_LEN).saturating_sub(self.packet_number_len + TAG_LEN);
+        let (mut packet, payload) = self.assemble(frames, TAG_LEN, min_payload);
+        let pn_offset = packet.len() - self.packet_number_len;
+        let ciphertext = keys.encrypt_payload(self.packet_number, &packet, &payload);
+        packet.extend_from_slice(&ciphertext);
+        apply_header_protection(&keys.header_protection(), &mut packet, pn_offset)
+            .expect("payload padded for sampling");
+        packet
+    }
+}
+
+/// Assembles a Retry packet. `retry_token` is followed by the 16 byte `integrity_tag`; see
+/// `retry_protected` to compute it.
+pub fn retry(
+    version: Version,
+    dest_id: &[u8],
+    src_id: &[u8],
+    retry_token: &[u8],
+    integrity_tag: &[u8; 16],
+) -> Vec<u8> {
+    let mut packet = vec![0xc0 | LongPacketTypes::Retry.to_bits(version) << 4];
+    packet.extend_from_slice(&version.0.to_be_bytes());
+    push_connection_id(&mut packet, dest_id);
+    push_connection_id(&mut packet, src_id);
+    packet.extend_from_slice(retry_token);
+    packet.extend_from_slice(integrity_tag);
+    packet
+}
+
+/// Assembles a Retry packet sent in response to an Initial packet with destination connection id
+/// `original_dest_id`, computing its integrity tag.
+///
+/// Returns `None` if the version has no known Retry key.
+#[cfg(feature = "crypto")]
+pub fn retry_protected(
+    version: Version,
+    dest_id: &[u8],
+    src_id: &[u8],
+    retry_token: &[u8],
+    original_dest_id: &[u8],
+) -> Option<Vec<u8>> {
+    let mut packet = retry(version, dest_id, src_id, retry_token, &[0; 16]);
+    let len = packet.len() - 16;
+    let tag = retry_integrity_tag(version, original_dest_id, &packet[..len])?;
+    packet[len..].copy_from_slice(&tag);
+    Some(packet)
+}
+
+/// Assembles a Version Negotiation packet listing `versions`.
+pub fn version_negotiation(dest_id: &[u8], src_id: &[u8], versions: &[Version]) -> Vec<u8> {
+    let mut packet = vec![0x80];
+    packet.extend_from_slice(&[0; 4]);
+    push_connection_id(&mut packet, dest_id);
+    push_connection_id(&mut packet, src_id);
+    for version in versions {
+        packet.extend_from_slice(&version.0.to_be_bytes());
+    }
+    packet
+}
+
+/// Appends a length-prefixed connection id.
+///
+/// # Panics
+///
+/// Panics if the connection id is longer than 255 bytes.
+fn push_connection_id(buf: &mut Vec<u8>, id: &[u8]) {
+    buf.push(u8::try_from(id.len()).expect("connection id too long"));
+    buf.extend_from_slice(id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quic::frame::{encode_crypto, encode_ping, encode_stream};
+    use crate::quic::{Frame, QuicPacket, QuicParser};
+
+    #[test]
+    fn initial_is_padded() {
+        let mut frames = vec![];
+        encode_crypto(&mut frames, 0, &[0x16; 40]);
+        let packet = PacketBuilder::initial(Versions::V1, &[1; 8], &[2; 4], &[0xaa; 3])
+            .packet_number(7, 2)
+            .build(&frames);
+        assert_eq!(packet.len(), MIN_INITIAL_SIZE);
+        let packets = QuicPacket::new(&packet).unwrap();
+        assert_eq!(packets.len(), 1);
+        assert_eq!(packets[0].token(), Some(&[0xaa; 3][..]));
+        assert_eq!(packets[0].packet_number(), Some(7));
+        let parsed = Frame::new(packets[0].frames().unwrap()).unwrap();
+        assert!(matches!(parsed[0], Frame::Crypto(_)));
+        assert!(matches!(parsed[1], Frame::Padding(..)));
+    }
+
+    #[test]
+    fn coalesced_handshake_and_one_rtt() {
+        let mut frames = vec![];
+        encode_ping(&mut frames);
+        let mut datagram = PacketBuilder::handshake(Versions::V1, &[3; 5], &[4; 2])
+            .packet_number(300, 2)
+            .build(&frames);
+        frames.clear();
+        encode_stream(&mut frames, 0, None, b"hi", true);
+        datagram.extend(
+            PacketBuilder::one_rtt(&[4; 2])
+                .packet_number(301, 1)
+                .key_phase(true)
+                .build(&frames),
+        );
+        let packets = QuicParser::new().parse(&datagram).unwrap();
+        assert_eq!(packets.len(), 2);
+        assert!(matches!(packets[0], QuicPacket::Handshake(_)));
+        assert_eq!(packets[0].packet_number(), Some(300));
+        match &packets[1] {
+            QuicPacket::OneRtt(packet, 2) => assert_eq!(packet.get_key_phase(), 1),
+            p => panic!("expected 1-rtt, got {}", p),
+        }
+        assert_eq!(packets[1].packet_number(), Some(301 & 0xff));
+        assert_eq!(packets[1].frames(), Some(&frames[..]));
+    }
+
+    #[test]
+    fn retry_and_version_negotiation() {
+        let packet = retry(Versions::V1, &[1; 4], &[2; 8], b"token", &[9; 16]);
+        let packets = QuicPacket::new(&packet).unwrap();
+        match &packets[0] {
+            QuicPacket::Retry(retry) => {
+                assert_eq!(retry.get_retry_token_raw(), b"token");
+                assert_eq!(retry.get_integrity_tag_raw(), &[9; 16][..]);
+            }
+            p => panic!("expected retry, got {}", p),
+        }
+        let packet = version_negotiation(&[1; 4], &[2; 8], &[Versions::V1, Versions::Draft29]);
+        let packets = QuicPacket::new(&packet).unwrap();
+        match &packets[0] {
+            QuicPacket::VersionNegotiation(vn) => assert_eq!(
+                vn.supported_versions().collect::<Vec<_>>(),
+                vec![Versions::V1, Versions::Draft29]
+            ),
+            p => panic!("expected version negotiation, got {}", p),
+        }
+        assert_eq!(packets[0].src_id(), Some(&[2; 8][..]));
+    }
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn protected_retry() {
+        let packet = retry_protected(Versions::V2, &[1; 4], &[2; 8], b"token", &[3; 8]).unwrap();
+        match &QuicPacket::new(&packet).unwrap()[0] {
+            QuicPacket::Retry(retry) => {
+                assert!(retry.verify_integrity_tag(&[3; 8]));
+                assert!(!retry.verify_integrity_tag(&[4; 8]));
+            }
+            p => panic!("expected retry, got {}", p),
+        }
+        assert!(retry_protected(Versions::Draft27, &[], &[], b"", &[]).is_none());
+    }
+}
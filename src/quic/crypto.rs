@@ -0,0 +1,763 @@
+//! QUIC packet protection (RFC 9001 section 5).
+//!
+//! Initial packets are protected with keys derived from the Destination Connection ID the client
+//! chose for its first Initial packet, so any observer can remove their protection. Header
+//! protection of other packets can be removed given the header protection key.
+
+use super::{
+    decode_packet_number, varint, InitialPacket, MutableHandshakePacket, MutableInitialPacket,
+    MutableOneRttPacket, RetryPacket, Version, Versions,
+};
+use crate::{MutablePacket, Packet};
+use aes::cipher::{BlockEncrypt, KeyInit, KeyIvInit, StreamCipher, StreamCipherSeek};
+use aes::{Aes128, Aes256};
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::Aes128Gcm;
+use chacha20::ChaCha20;
+use hkdf::Hkdf;
+use sha2::Sha256;
+use std::convert::TryFrom;
+
+const INITIAL_SALT_V1: [u8; 20] = [
+    0x38, 0x76, 0x2c, 0xf7, 0xf5, 0x59, 0x34, 0xb3, 0x4d, 0x17, 0x9a, 0xe6, 0xa4, 0xc8, 0x0c, 0xad,
+    0xcc, 0xbb, 0x7f, 0x0a,
+];
+
+const INITIAL_SALT_V2: [u8; 20] = [
+    0x0d, 0xed, 0xe3, 0xde, 0xf7, 0x00, 0xa6, 0xdb, 0x81, 0x93, 0x81, 0xbe, 0x6e, 0x26, 0x9d, 0xcb,
+    0xf9, 0xbd, 0x2e, 0xd9,
+];
+
+const INITIAL_SALT_DRAFT29: [u8; 20] = [
+    0xaf, 0xbf, 0xec, 0x28, 0x99, 0x93, 0xd2, 0x4c, 0x9e, 0x97, 0x86, 0xf1, 0x9c, 0x61, 0x11, 0xe0,
+    0x43, 0x90, 0xa8, 0x99,
+];
+
+const RETRY_KEY_V1: [u8; 16] = [
+    0xbe, 0x0c, 0x69, 0x0b, 0x9f, 0x66, 0x57, 0x5a, 0x1d, 0x76, 0x6b, 0x54, 0xe3, 0x68, 0xc8, 0x4e,
+];
+
+const RETRY_NONCE_V1: [u8; 12] = [
+    0x46, 0x15, 0x99, 0xd3, 0x5d, 0x63, 0x2b, 0xf2, 0x23, 0x98, 0x25, 0xbb,
+];
+
+const RETRY_KEY_V2: [u8; 16] = [
+    0x8f, 0xb4, 0xb0, 0x1b, 0x56, 0xac, 0x48, 0xe2, 0x60, 0xfb, 0xcb, 0xce, 0xad, 0x7c, 0xcc, 0x92,
+];
+
+const RETRY_NONCE_V2: [u8; 12] = [
+    0xd8, 0x69, 0x69, 0xbc, 0x2d, 0x7c, 0x6d, 0x99, 0x90, 0xef, 0xb0, 0x4a,
+];
+
+const RETRY_KEY_DRAFT29: [u8; 16] = [
+    0xcc, 0xce, 0x18, 0x7e, 0xd0, 0x9a, 0x09, 0xd0, 0x57, 0x28, 0x15, 0x5a, 0x6c, 0xb9, 0x6b, 0xe1,
+];
+
+const RETRY_NONCE_DRAFT29: [u8; 12] = [
+    0xe5, 0x49, 0x30, 0xf9, 0x7f, 0x21, 0x36, 0xf0, 0x53, 0x0a, 0x8c, 0x1c,
+];
+
+/// Length of the AEAD authentication tag appended to the payload.
+pub const TAG_LEN: usize = 16;
+
+/// Length of the ciphertext sample used for header protection.
+pub const SAMPLE_LEN: usize = 16;
+
+/// The Initial salt and the labels used by `version`, or `None` if the version is unknown.
+fn version_params(version: Version) -> Option<(&'static [u8; 20], [&'static str; 3])> {
+    match version {
+        Versions::V1 => Some((&INITIAL_SALT_V1, ["quic key", "quic iv", "quic hp"])),
+        Versions::V2 => Some((&INITIAL_SALT_V2, ["quicv2 key", "quicv2 iv", "quicv2 hp"])),
+        Versions::Draft29 => Some((&INITIAL_SALT_DRAFT29, ["quic key", "quic iv", "quic hp"])),
+        _ => None,
+    }
+}
+
+/// The fixed key and nonce protecting Retry packets of `version`.
+fn retry_params(version: Version) -> Option<(&'static [u8; 16], &'static [u8; 12])> {
+    match version {
+        Versions::V1 => Some((&RETRY_KEY_V1, &RETRY_NONCE_V1)),
+        Versions::V2 => Some((&RETRY_KEY_V2, &RETRY_NONCE_V2)),
+        Versions::Draft29 => Some((&RETRY_KEY_DRAFT29, &RETRY_NONCE_DRAFT29)),
+        _ => None,
+    }
+}
+
+/// HKDF-Expand-Label from TLS 1.3 (RFC 8446 section 7.1) with an empty context.
+fn expand_label(secret: &[u8], label: &str, out: &mut [u8]) {
+    let hk = Hkdf::<Sha256>::from_prk(secret).expect("secret is a SHA-256 PRK");
+    let label_len = 6 + label.len();
+    let mut info = Vec::with_capacity(4 + label_len);
+    info.extend_from_slice(&(out.len() as u16).to_be_bytes());
+    info.push(label_len as u8);
+    info.extend_from_slice(b"tls13 ");
+    info.extend_from_slice(label.as_bytes());
+    info.push(0);
+    hk.expand(&info, out)
+        .expect("output fits a single HKDF expansion");
+}
+
+/// The client and server keys protecting Initial packets of a connection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InitialKeys {
+    /// Keys protecting packets sent by the client.
+    pub client: Keys,
+    /// Keys protecting packets sent by the server.
+    pub server: Keys,
+}
+
+impl InitialKeys {
+    /// Derives the Initial keys of a connection from the Destination Connection ID of the first
+    /// Initial packet sent by the client. Returns `None` for versions without a known salt.
+    pub fn new(version: Version, dest_id: &[u8]) -> Option<Self> {
+        let (salt, _) = version_params(version)?;
+        let (initial_secret, _) = Hkdf::<Sha256>::extract(Some(&salt[..]), dest_id);
+        let mut client = [0; 32];
+        let mut server = [0; 32];
+        expand_label(&initial_secret, "client in", &mut client);
+        expand_label(&initial_secret, "server in", &mut server);
+        Some(Self {
+            client: Keys::new(version, &client)?,
+            server: Keys::new(version, &server)?,
+        })
+    }
+}
+
+/// AES-128-GCM packet protection keys derived from a traffic secret.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keys {
+    /// The AEAD key.
+    pub key: [u8; 16],
+    /// The AEAD IV, combined with the packet number to form the nonce.
+    pub iv: [u8; 12],
+    /// The header protection key.
+    pub hp: [u8; 16],
+}
+
+impl Keys {
+    /// Derives packet protection keys from a 32 byte traffic secret using the labels of `version`.
+    pub fn new(version: Version, secret: &[u8]) -> Option<Self> {
+        let (_, [key_label, iv_label, hp_label]) = version_params(version)?;
+        let mut keys = Self {
+            key: [0; 16],
+            iv: [0; 12],
+            hp: [0; 16],
+        };
+        expand_label(secret, key_label, &mut keys.key);
+        expand_label(secret, iv_label, &mut keys.iv);
+        expand_label(secret, hp_label, &mut keys.hp);
+        Some(keys)
+    }
+
+    /// The header protection key.
+    pub fn header_protection(&self) -> HeaderProtectionKey {
+        HeaderProtectionKey::Aes128(self.hp)
+    }
+
+    fn nonce(&self, pn: u64) -> [u8; 12] {
+        let mut nonce = self.iv;
+        for (n, p) in nonce[4..].iter_mut().zip(&pn.to_be_bytes()) {
+            *n ^= p;
+        }
+        nonce
+    }
+
+    /// Authenticates and decrypts `ciphertext` protected with packet number `pn` and header `aad`.
+    pub fn decrypt_payload(&self, pn: u64, aad: &[u8], ciphertext: &[u8]) -> Option<Vec<u8>> {
+        let cipher = Aes128Gcm::new(&self.key.into());
+        cipher
+            .decrypt(
+                &self.nonce(pn).into(),
+                Payload {
+                    msg: ciphertext,
+                    aad,
+                },
+            )
+            .ok()
+    }
+
+    /// Encrypts `plaintext` with packet number `pn` and header `aad`, appending the tag.
+    pub fn encrypt_payload(&self, pn: u64, aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let cipher = Aes128Gcm::new(&self.key.into());
+        cipher
+            .encrypt(
+                &self.nonce(pn).into(),
+                Payload {
+                    msg: plaintext,
+                    aad,
+                },
+            )
+            .expect("payload fits AES-GCM limits")
+    }
+
+    /// Removes the protection of an Initial packet sent by the peer these keys belong to, given
+    /// the largest packet number received so far in the Initial packet number space, which is
+    /// needed to reconstruct the full packet number (see `decode_packet_number`).
+    ///
+    /// Returns `None` if the packet is too short to be sampled or fails authentication.
+    pub fn decrypt_initial(
+        &self,
+        packet: &InitialPacket,
+        largest_pn: Option<u64>,
+    ) -> Option<Decrypted> {
+        let data = packet.packet();
+        let end = data.len() - packet.get_remaining_raw().len();
+        let pn_offset = end.checked_sub(varint(packet.get_length_raw()))?;
+        let mut buf = data[..end].to_vec();
+        let pn_len = remove_header_protection(&self.header_protection(), &mut buf, pn_offset)?;
+        let header = &buf[..pn_offset + pn_len];
+        let truncated_pn = super::packet_number(&header[pn_offset..]);
+        let packet_number = decode_packet_number(largest_pn, truncated_pn, pn_len)?;
+        let frames = self.decrypt_payload(packet_number, header, &data[header.len()..end])?;
+        Some(Decrypted {
+            header: header.to_vec(),
+            packet_number_len: pn_len as u8,
+            packet_number,
+            frames,
+        })
+    }
+}
+
+/// A header protection key (RFC 9001 section 5.4).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HeaderProtectionKey {
+    /// Header protection for AEAD_AES_128_GCM and AEAD_AES_128_CCM.
+    Aes128([u8; 16]),
+    /// Header protection for AEAD_AES_256_GCM.
+    Aes256([u8; 32]),
+    /// Header protection for AEAD_CHACHA20_POLY1305.
+    ChaCha20([u8; 32]),
+}
+
+impl HeaderProtectionKey {
+    /// Computes the header protection mask for a ciphertext sample.
+    pub fn mask(&self, sample: &[u8; SAMPLE_LEN]) -> [u8; 5] {
+        let mut mask = [0; 5];
+        match self {
+            Self::Aes128(key) => {
+                let mut block = (*sample).into();
+                Aes128::new(&(*key).into()).encrypt_block(&mut block);
+                mask.copy_from_slice(&block[..5]);
+            }
+            Self::Aes256(key) => {
+                let mut block = (*sample).into();
+                Aes256::new(&(*key).into()).encrypt_block(&mut block);
+                mask.copy_from_slice(&block[..5]);
+            }
+            Self::ChaCha20(key) => {
+                let counter = u32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]]);
+                let mut nonce = [0; 12];
+                nonce.copy_from_slice(&sample[4..]);
+                let mut cipher = ChaCha20::new(&(*key).into(), &nonce.into());
+                cipher.seek(counter as u64 * 64);
+                cipher.apply_keystream(&mut mask);
+            }
+        }
+        mask
+    }
+}
+
+/// Masks the first byte and packet number of `packet` in place, given the offset of its packet
+/// number. Masking is its own inverse, but the packet number length must be read from the
+/// unprotected first byte, so `protected` tells whether the packet currently is. Returns the
+/// length of the packet number, or `None` if the packet is too short to be sampled, in which case
+/// it is left untouched.
+fn mask_header(
+    key: &HeaderProtectionKey,
+    packet: &mut [u8],
+    pn_offset: usize,
+    protected: bool,
+) -> Option<usize> {
+    let mut sample = [0; SAMPLE_LEN];
+    sample.copy_from_slice(packet.get(pn_offset + 4..pn_offset + 4 + SAMPLE_LEN)?);
+    let mask = key.mask(&sample);
+    let long_header = packet[0] & 0x80 != 0;
+    let first_mask = mask[0] & if long_header { 0x0f } else { 0x1f };
+    let first = if protected {
+        packet[0] ^ first_mask
+    } else {
+        packet[0]
+    };
+    let pn_len = (first & 0x03) as usize + 1;
+    packet[0] ^= first_mask;
+    for (b, m) in packet[pn_offset..pn_offset + pn_len]
+        .iter_mut()
+        .zip(&mask[1..])
+    {
+        *b ^= m;
+    }
+    Some(pn_len)
+}
+
+/// Removes header protection from `packet` in place, given the offset of its packet number.
+/// Returns the length of the unprotected packet number, or `None` if the packet is too short to
+/// be sampled.
+fn remove_header_protection(
+    key: &HeaderProtectionKey,
+    packet: &mut [u8],
+    pn_offset: usize,
+) -> Option<usize> {
+    mask_header(key, packet, pn_offset, true)
+}
+
+/// Applies header protection to `packet` in place, given the offset of its packet number.
+/// Returns `None` if the packet is too short to be sampled, in which case it is left untouched.
+pub fn apply_header_protection(
+    key: &HeaderProtectionKey,
+    packet: &mut [u8],
+    pn_offset: usize,
+) -> Option<()> {
+    mask_header(key, packet, pn_offset, false)?;
+    Some(())
+}
+
+macro_rules! header_protection {
+    ($($packet:ident => $($rest:ident),+;)*) => {
+        $(
+            impl<'p> $packet<'p> {
+                /// Removes header protection in place, exposing the real packet number length
+                /// and the truncated packet number. Use `decode_packet_number` to recover the
+                /// full packet number.
+                ///
+                /// Returns `None` if the packet is too short to be sampled, in which case it is
+                /// left untouched.
+                pub fn remove_header_protection(&mut self, key: &HeaderProtectionKey) -> Option<()> {
+                    let len = self.packet().len();
+                    let pn_offset = len
+                        - self.get_packet_number_raw().len()
+                        $(- self.$rest().len())+;
+                    remove_header_protection(key, self.packet_mut(), pn_offset)?;
+                    Some(())
+                }
+            }
+        )*
+    };
+}
+
+header_protection! {
+    MutableInitialPacket => get_frames_raw, get_remaining_raw;
+    MutableHandshakePacket => get_frames_raw, get_remaining_raw;
+}
+
+impl<'p> MutableOneRttPacket<'p> {
+    /// Removes header protection in place, given the length of the destination connection id
+    /// (see `QuicParser`). Behaves like `MutableInitialPacket::remove_header_protection`.
+    pub fn remove_header_protection(
+        &mut self,
+        key: &HeaderProtectionKey,
+        dest_id_len: usize,
+    ) -> Option<()> {
+        remove_header_protection(key, self.packet_mut(), 1 + dest_id_len)?;
+        Some(())
+    }
+}
+
+/// Computes the Retry Integrity Tag (RFC 9001 section 5.8) of `retry`, a Retry packet without
+/// its tag, sent in response to an Initial packet with destination connection id
+/// `original_dest_id`.
+///
+/// Returns `None` if the version is unknown or the connection id is longer than 255 bytes.
+pub fn retry_integrity_tag(
+    version: Version,
+    original_dest_id: &[u8],
+    retry: &[u8],
+) -> Option<[u8; TAG_LEN]> {
+    let (key, nonce) = retry_params(version)?;
+    let mut pseudo_packet = Vec::with_capacity(1 + original_dest_id.len() + retry.len());
+    pseudo_packet.push(u8::try_from(original_dest_id.len()).ok()?);
+    pseudo_packet.extend_from_slice(original_dest_id);
+    pseudo_packet.extend_from_slice(retry);
+    let cipher = Aes128Gcm::new(key.into());
+    let tag = cipher
+        .encrypt(
+            nonce.into(),
+            Payload {
+                msg: &[],
+                aad: &pseudo_packet,
+            },
+        )
+        .ok()?;
+    let mut out = [0; TAG_LEN];
+    out.copy_from_slice(&tag);
+    Some(out)
+}
+
+impl<'p> RetryPacket<'p> {
+    /// Whether the integrity tag is valid for a Retry sent in response to an Initial packet
+    /// with destination connection id `original_dest_id`. Always `false` for unknown versions.
+    pub fn verify_integrity_tag(&self, original_dest_id: &[u8]) -> bool {
+        let data = self.packet();
+        let tag = self.get_integrity_tag_raw();
+        retry_integrity_tag(
+            self.get_version(),
+            original_dest_id,
+            &data[..data.len() - tag.len()],
+        )
+        .is_some_and(|expected| expected[..] == *tag)
+    }
+}
+
+/// An Initial packet with its protection removed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Decrypted {
+    /// The unprotected header, ending with the packet number.
+    pub header: Vec<u8>,
+    /// Length of the encoded packet number in bytes.
+    pub packet_number_len: u8,
+    /// The full packet number, reconstructed from the truncated one encoded in the header.
+    pub packet_number: u64,
+    /// The decrypted frames, which can be parsed with `Frame::new`.
+    pub frames: Vec<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quic::builder::{PacketBuilder, MIN_INITIAL_SIZE};
+    use crate::quic::frame::encode_ping;
+    use crate::quic::{Frame, QuicPacket};
+
+    fn hex(s: &str) -> Vec<u8> {
+        let s: String = s.split_whitespace().collect();
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    const DEST_ID: [u8; 8] = [0x83, 0x94, 0xc8, 0xf0, 0x3e, 0x51, 0x57, 0x08];
+
+    const CLIENT_CRYPTO: &str = "
+        060040f1010000ed0303ebf8fa56f12939b9584a3896472ec40bb863cfd3e868
+        04fe3a47f06a2b69484c00000413011302010000c000000010000e00000b6578
+        616d706c652e636f6dff01000100000a00080006001d00170018001000070005
+        04616c706e000500050100000000003300260024001d00209370b2c9caa47fba
+        baf4559fedba753de171fa71f50f1ce15d43e994ec74d748002b000302030400
+        0d0010000e0403050306030203080408050806002d00020101001c0002400100
+        3900320408ffffffffffffffff05048000ffff07048000ffff08011001048000
+        75300901100f088394c8f03e51570806048000ffff";
+
+    const CLIENT_INITIAL: &str = "
+        c000000001088394c8f03e5157080000449e7b9aec34d1b1c98dd7689fb8ec11
+        d242b123dc9bd8bab936b47d92ec356c0bab7df5976d27cd449f63300099f399
+        1c260ec4c60d17b31f8429157bb35a1282a643a8d2262cad67500cadb8e7378c
+        8eb7539ec4d4905fed1bee1fc8aafba17c750e2c7ace01e6005f80fcb7df6212
+        30c83711b39343fa028cea7f7fb5ff89eac2308249a02252155e2347b63d58c5
+        457afd84d05dfffdb20392844ae812154682e9cf012f9021a6f0be17ddd0c208
+        4dce25ff9b06cde535d0f920a2db1bf362c23e596d11a4f5a6cf3948838a3aec
+        4e15daf8500a6ef69ec4e3feb6b1d98e610ac8b7ec3faf6ad760b7bad1db4ba3
+        485e8a94dc250ae3fdb41ed15fb6a8e5eba0fc3dd60bc8e30c5c4287e53805db
+        059ae0648db2f64264ed5e39be2e20d82df566da8dd5998ccabdae053060ae6c
+        7b4378e846d29f37ed7b4ea9ec5d82e7961b7f25a9323851f681d582363aa5f8
+        9937f5a67258bf63ad6f1a0b1d96dbd4faddfcefc5266ba6611722395c906556
+        be52afe3f565636ad1b17d508b73d8743eeb524be22b3dcbc2c7468d54119c74
+        68449a13d8e3b95811a198f3491de3e7fe942b330407abf82a4ed7c1b311663a
+        c69890f4157015853d91e923037c227a33cdd5ec281ca3f79c44546b9d90ca00
+        f064c99e3dd97911d39fe9c5d0b23a229a234cb36186c4819e8b9c5927726632
+        291d6a418211cc2962e20fe47feb3edf330f2c603a9d48c0fcb5699dbfe58964
+        25c5bac4aee82e57a85aaf4e2513e4f05796b07ba2ee47d80506f8d2c25e50fd
+        14de71e6c418559302f939b0e1abd576f279c4b2e0feb85c1f28ff18f58891ff
+        ef132eef2fa09346aee33c28eb130ff28f5b766953334113211996d20011a198
+        e3fc433f9f2541010ae17c1bf202580f6047472fb36857fe843b19f5984009dd
+        c324044e847a4f4a0ab34f719595de37252d6235365e9b84392b061085349d73
+        203a4a13e96f5432ec0fd4a1ee65accdd5e3904df54c1da510b0ff20dcc0c77f
+        cb2c0e0eb605cb0504db87632cf3d8b4dae6e705769d1de354270123cb11450e
+        fc60ac47683d7b8d0f811365565fd98c4c8eb936bcab8d069fc33bd801b03ade
+        a2e1fbc5aa463d08ca19896d2bf59a071b851e6c239052172f296bfb5e724047
+        90a2181014f3b94a4e97d117b438130368cc39dbb2d198065ae3986547926cd2
+        162f40a29f0c3c8745c0f50fba3852e566d44575c29d39a03f0cda721984b6f4
+        40591f355e12d439ff150aab7613499dbd49adabc8676eef023b15b65bfc5ca0
+        6948109f23f350db82123535eb8a7433bdabcb909271a6ecbcb58b936a88cd4e
+        8f2e6ff5800175f113253d8fa9ca8885c2f552e657dc603f252e1a8e308f76f0
+        be79e2fb8f5d5fbbe2e30ecadd220723c8c0aea8078cdfcb3868263ff8f09400
+        54da48781893a7e49ad5aff4af300cd804a6b6279ab3ff3afb64491c85194aab
+        760d58a606654f9f4400e8b38591356fbf6425aca26dc85244259ff2b19c41b9
+        f96f3ca9ec1dde434da7d2d392b905ddf3d1f9af93d1af5950bd493f5aa731b4
+        056df31bd267b6b90a079831aaf579be0a39013137aac6d404f518cfd4684064
+        7e78bfe706ca4cf5e9c5453e9f7cfd2b8b4c8d169a44e55c88d4a9a7f9474241
+        e221af44860018ab0856972e194cd934";
+
+    const SERVER_INITIAL: &str = "
+        cf000000010008f067a5502a4262b5004075c0d95a482cd0991cd25b0aac406a
+        5816b6394100f37a1c69797554780bb38cc5a99f5ede4cf73c3ec2493a1839b3
+        dbcba3f6ea46c5b7684df3548e7ddeb9c3bf9c73cc3f3bded74b562bfb19fb84
+        022f8ef4cdd93795d77d06edbb7aaf2f58891850abbdca3d20398c276456cbc4
+        2158407dd074ee";
+
+    #[test]
+    fn rfc9001_initial_keys() {
+        let keys = InitialKeys::new(Versions::V1, &DEST_ID).unwrap();
+        assert_eq!(
+            &keys.client.key[..],
+            &hex("1f369613dd76d5467730efcbe3b1a22d")[..]
+        );
+        assert_eq!(&keys.client.iv[..], &hex("fa044b2f42a3fd3b46fb255c")[..]);
+        assert_eq!(
+            &keys.client.hp[..],
+            &hex("9f50449e04a0e810283a1e9933adedd2")[..]
+        );
+        assert_eq!(
+            &keys.server.key[..],
+            &hex("cf3a5331653c364c88f0f379b6067e37")[..]
+        );
+        assert_eq!(&keys.server.iv[..], &hex("0ac1493ca1905853b0bba03e")[..]);
+        assert_eq!(
+            &keys.server.hp[..],
+            &hex("c206b8d9b9f0f37644430b490eeaa314")[..]
+        );
+        assert!(InitialKeys::new(Version(0xdead_beef), &DEST_ID).is_none());
+    }
+
+    #[test]
+    fn rfc9369_initial_keys() {
+        let keys = InitialKeys::new(Versions::V2, &DEST_ID).unwrap();
+        assert_eq!(
+            &keys.client.key[..],
+            &hex("8b1a0bc121284290a29e0971b5cd045d")[..]
+        );
+        assert_eq!(&keys.client.iv[..], &hex("91f73e2351d8fa91660e909f")[..]);
+        assert_eq!(
+            &keys.client.hp[..],
+            &hex("45b95e15235d6f45a6b19cbcb0294ba9")[..]
+        );
+        assert_eq!(
+            &keys.server.key[..],
+            &hex("82db637861d55e1d011f19ea71d5d2a7")[..]
+        );
+        assert_eq!(&keys.server.iv[..], &hex("dd13c276499c0249d3310652")[..]);
+        assert_eq!(
+            &keys.server.hp[..],
+            &hex("edf6d05c83121201b436e16877593c3a")[..]
+        );
+    }
+
+    #[test]
+    fn rfc9001_retry() {
+        let data = hex("ff000000010008f067a5502a4262b5746f6b656e04a265ba2eff4d829058fb3f0f2496ba");
+        let packets = QuicPacket::new(&data).unwrap();
+        let retry = match &packets[0] {
+            QuicPacket::Retry(retry) => retry,
+            p => panic!("expected retry, got {}", p),
+        };
+        assert_eq!(retry.get_retry_token_raw(), b"token");
+        assert!(retry.verify_integrity_tag(&DEST_ID));
+        assert!(!retry.verify_integrity_tag(&DEST_ID[1..]));
+        let tag = retry_integrity_tag(Versions::V1, &DEST_ID, &data[..data.len() - 16]).unwrap();
+        assert_eq!(&tag[..], retry.get_integrity_tag_raw());
+    }
+
+    #[test]
+    fn rfc9369_retry() {
+        let data = hex("cf6b3343cf0008f067a5502a4262b5746f6b656ec8646ce8bfe33952d955543665dcc7b6");
+        let packets = QuicPacket::new(&data).unwrap();
+        match &packets[0] {
+            QuicPacket::Retry(retry) => assert!(retry.verify_integrity_tag(&DEST_ID)),
+            p => panic!("expected retry, got {}", p),
+        }
+    }
+
+    #[test]
+    fn rfc9001_client_header_mask() {
+        let keys = InitialKeys::new(Versions::V1, &DEST_ID).unwrap();
+        let mut sample = [0; SAMPLE_LEN];
+        sample.copy_from_slice(&hex("d1b1c98dd7689fb8ec11d242b123dc9b"));
+        let mask = keys.client.header_protection().mask(&sample);
+        assert_eq!(&mask[..], &hex("437b9aec36")[..]);
+    }
+
+    #[test]
+    fn rfc9001_chacha20_header_mask() {
+        let mut key = [0; 32];
+        key.copy_from_slice(&hex(
+            "25a282b9e82f06f21f488917a4fc8f1b73573685608597d0efcb076b0ab7a7a4",
+        ));
+        let mut sample = [0; SAMPLE_LEN];
+        sample.copy_from_slice(&hex("5e5cd55c41f69080575d7999c25a5bfb"));
+        let mask = HeaderProtectionKey::ChaCha20(key).mask(&sample);
+        assert_eq!(&mask[..], &hex("aefefe7d03")[..]);
+    }
+
+    #[test]
+    fn rfc9001_client_initial() {
+        let data = hex(CLIENT_INITIAL);
+        assert_eq!(data.len(), 1200);
+        let packets = QuicPacket::new(&data).unwrap();
+        let initial = match &packets[0] {
+            QuicPacket::Initial(initial) => initial,
+            p => panic!("expected initial, got {}", p),
+        };
+        let keys = InitialKeys::new(initial.get_version(), initial.get_dest_id_raw()).unwrap();
+        let decrypted = keys.client.decrypt_initial(initial, None).unwrap();
+        assert_eq!(
+            decrypted.header,
+            hex("c300000001088394c8f03e5157080000449e00000002")
+        );
+        assert_eq!(decrypted.packet_number_len, 4);
+        assert_eq!(decrypted.packet_number, 2);
+        let crypto = hex(CLIENT_CRYPTO);
+        assert_eq!(decrypted.frames.len(), 1162);
+        assert_eq!(&decrypted.frames[..crypto.len()], &crypto[..]);
+        assert!(decrypted.frames[crypto.len()..].iter().all(|&b| b == 0));
+
+        let packet = PacketBuilder::initial(Versions::V1, &DEST_ID, &[], &[])
+            .packet_number(2, 4)
+            .build_protected(&crypto, &keys.client);
+        assert_eq!(packet, data);
+    }
+
+    #[test]
+    fn decrypt_initial_full_packet_number() {
+        let keys = InitialKeys::new(Versions::V1, &DEST_ID).unwrap();
+        let mut frames = vec![];
+        encode_ping(&mut frames);
+        let packet = PacketBuilder::initial(Versions::V1, &DEST_ID, &[], &[])
+            .packet_number(0x1_0005, 2)
+            .build_protected(&frames, &keys.client);
+        let packets = QuicPacket::new(&packet).unwrap();
+        let initial = match &packets[0] {
+            QuicPacket::Initial(initial) => initial,
+            p => panic!("expected initial, got {}", p),
+        };
+        let decrypted = keys
+            .client
+            .decrypt_initial(initial, Some(0x1_0003))
+            .unwrap();
+        assert_eq!(decrypted.packet_number, 0x1_0005);
+        assert_eq!(decrypted.packet_number_len, 2);
+        // The truncated packet number alone gives the wrong nonce.
+        assert!(keys.client.decrypt_initial(initial, None).is_none());
+    }
+
+    #[test]
+    fn rfc9001_server_initial() {
+        let data = hex(SERVER_INITIAL);
+        let packets = QuicPacket::new(&data).unwrap();
+        let initial = match &packets[0] {
+            QuicPacket::Initial(initial) => initial,
+            p => panic!("expected initial, got {}", p),
+        };
+        let keys = InitialKeys::new(initial.get_version(), &DEST_ID).unwrap();
+        let decrypted = keys.server.decrypt_initial(initial, None).unwrap();
+        assert_eq!(
+            decrypted.header,
+            hex("c1000000010008f067a5502a4262b50040750001")
+        );
+        assert_eq!(decrypted.packet_number_len, 2);
+        assert_eq!(decrypted.packet_number, 1);
+        assert_eq!(
+            decrypted.frames,
+            hex(
+                "02000000000600405a020000560303eefce7f7b37ba1d1632e96677825ddf739
+                 88cfc79825df566dc5430b9a045a1200130100002e00330024001d00209d3c94
+                 0d89690b84d08a60993c144eca684d1081287c834d5311bcf32bb9da1a002b00
+                 020304"
+            )
+        );
+        let frames = Frame::new(&decrypted.frames).unwrap();
+        assert!(matches!(frames[0], Frame::Ack(_)));
+        assert!(matches!(frames[1], Frame::Crypto(_)));
+        assert!(keys.client.decrypt_initial(initial, None).is_none());
+    }
+
+    #[test]
+    fn initial_header_protection_in_place() {
+        let mut data = hex(SERVER_INITIAL);
+        let keys = InitialKeys::new(Versions::V1, &DEST_ID).unwrap();
+        let mut packet = MutableInitialPacket::new(&mut data).unwrap();
+        assert_eq!(packet.get_packet_number_len(), 3);
+        packet
+            .remove_header_protection(&keys.server.header_protection())
+            .unwrap();
+        assert_eq!(packet.get_packet_number_len(), 1);
+        assert_eq!(packet.get_packet_number_raw(), &[0x00, 0x01]);
+        assert_eq!(packet.get_frames_raw().len(), 0x75 - 2);
+        assert_eq!(
+            &data[..20],
+            &hex("c1000000010008f067a5502a4262b50040750001")[..]
+        );
+    }
+
+    #[test]
+    fn one_rtt_header_protection_in_place() {
+        let mut plain = vec![0x41, 1, 2, 3, 4, 5, 6, 7, 8, 0x12, 0x34];
+        plain.extend((0..20).map(|i| i * 7));
+        let key = HeaderProtectionKey::Aes128([0x42; 16]);
+        let mut sample = [0; SAMPLE_LEN];
+        sample.copy_from_slice(&plain[13..29]);
+        let mask = key.mask(&sample);
+        let mut data = plain.clone();
+        data[0] ^= mask[0] & 0x1f;
+        data[9] ^= mask[1];
+        data[10] ^= mask[2];
+
+        let mut packet = MutableOneRttPacket::new(&mut data).unwrap();
+        packet.remove_header_protection(&key, 8).unwrap();
+        assert_eq!(packet.get_packet_number_len(), 1);
+        assert_eq!(data, plain);
+        let packets = QuicPacket::new(&data).unwrap();
+        assert_eq!(packets[0].packet_number(), Some(0x1234));
+
+        let mut short = vec![0x41; 20];
+        let mut packet = MutableOneRttPacket::new(&mut short).unwrap();
+        assert!(packet.remove_header_protection(&key, 8).is_none());
+        assert_eq!(short, vec![0x41; 20]);
+    }
+
+    #[test]
+    fn build_rfc9001_server_initial() {
+        let keys = InitialKeys::new(Versions::V1, &DEST_ID).unwrap();
+        let frames = hex(
+            "02000000000600405a020000560303eefce7f7b37ba1d1632e96677825ddf739
+             88cfc79825df566dc5430b9a045a1200130100002e00330024001d00209d3c94
+             0d89690b84d08a60993c144eca684d1081287c834d5311bcf32bb9da1a002b00
+             020304",
+        );
+        let packet = PacketBuilder::initial(Versions::V1, &[], &hex("f067a5502a4262b5"), &[])
+            .packet_number(1, 2)
+            .min_size(0)
+            .build_protected(&frames, &keys.server);
+        assert_eq!(packet, hex(SERVER_INITIAL));
+    }
+
+    #[test]
+    fn build_protected_initial() {
+        let keys = InitialKeys::new(Versions::V1, &DEST_ID).unwrap();
+        let mut frames = vec![];
+        encode_ping(&mut frames);
+        let packet = PacketBuilder::initial(Versions::V1, &DEST_ID, &[], &[])
+            .packet_number(2, 4)
+            .build_protected(&frames, &keys.client);
+        assert_eq!(packet.len(), MIN_INITIAL_SIZE);
+        let packets = QuicPacket::new(&packet).unwrap();
+        let initial = match &packets[0] {
+            QuicPacket::Initial(initial) => initial,
+            p => panic!("expected initial, got {}", p),
+        };
+        let decrypted = keys.client.decrypt_initial(initial, None).unwrap();
+        assert_eq!(decrypted.packet_number, 2);
+        assert_eq!(decrypted.packet_number_len, 4);
+        let frames = Frame::new(&decrypted.frames).unwrap();
+        assert!(matches!(frames[0], Frame::Ping(_)));
+        assert!(matches!(frames[1], Frame::Padding(..)));
+    }
+
+    #[test]
+    fn build_protected_v2_initial() {
+        let keys = InitialKeys::new(Versions::V2, &DEST_ID).unwrap();
+        let mut frames = vec![];
+        encode_ping(&mut frames);
+        let packet = PacketBuilder::initial(Versions::V2, &DEST_ID, &[], &[])
+            .build_protected(&frames, &keys.client);
+        assert_eq!(packet[0] & 0x30, 0x10);
+        let packets = QuicPacket::new(&packet).unwrap();
+        let initial = match &packets[0] {
+            QuicPacket::Initial(initial) => initial,
+            p => panic!("expected initial, got {}", p),
+        };
+        let decrypted = keys.client.decrypt_initial(initial, None).unwrap();
+        assert!(matches!(
+            Frame::new(&decrypted.frames).unwrap()[0],
+            Frame::Ping(_)
+        ));
+    }
+}
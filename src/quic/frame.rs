@@ -0,0 +1,884 @@
+//! Typed views over the QUIC frame packets, decoding their variable-length integer fields. The
+//! views are `None` if one of those fields is truncated.
+
+use super::{
+    checked_varint_u64, encode_varint, varint_length, varint_u64, AckFrequencyPacket, AckPacket,
+    ConnectionClosePacket, CryptoPacket, DatagramPacket, FrameTypes, MaxStreamDataPacket,
+    MaxStreamsPacket, NewConnectionIdPacket, NewTokenPacket, PathAckPacket, ResetStreamPacket,
+    StopSendingPacket, StreamDataBlockedPacket, StreamPacket, StreamsBlockedPacket,
+};
+use crate::Packet;
+use std::convert::TryFrom;
+use std::ops::RangeInclusive;
+
+/// A STREAM frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StreamFrame<'a> {
+    pub id: u64,
+    /// The offset of `data` in the stream, absent when zero.
+    pub offset: Option<u64>,
+    pub len: usize,
+    /// Whether the frame marks the end of the stream.
+    pub fin: bool,
+    pub data: &'a [u8],
+}
+
+impl<'a> StreamFrame<'a> {
+    pub fn new(packet: &'a StreamPacket) -> Option<Self> {
+        let offset = packet.get_offset_raw();
+        let data = packet.payload();
+        Some(Self {
+            id: checked_varint_u64(packet.get_stream_id_raw())?,
+            offset: if offset.is_empty() {
+                None
+            } else {
+                Some(checked_varint_u64(offset)?)
+            },
+            len: data.len(),
+            fin: packet.get_ty() & 0x01 > 0,
+            data,
+        })
+    }
+}
+
+/// The ECN counts of an ACK frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EcnCounts {
+    pub ect0: u64,
+    pub ect1: u64,
+    pub ce: u64,
+}
+
+impl EcnCounts {
+    fn decode(ecn: &[u8]) -> Option<Self> {
+        (!ecn.is_empty()).then(|| {
+            let mut counts = Varints(ecn);
+            Self {
+                ect0: counts.next().unwrap_or_default(),
+                ect1: counts.next().unwrap_or_default(),
+                ce: counts.next().unwrap_or_default(),
+            }
+        })
+    }
+}
+
+/// An ACK frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AckFrame<'a> {
+    pub largest_acknowledged: u64,
+    /// The encoded acknowledgement delay, to be scaled by the peer's `ack_delay_exponent`.
+    pub ack_delay: u64,
+    pub first_ack_range: u64,
+    ack_ranges: &'a [u8],
+    pub ecn_counts: Option<EcnCounts>,
+}
+
+impl<'a> AckFrame<'a> {
+    pub fn new(packet: &'a AckPacket) -> Option<Self> {
+        Some(Self {
+            largest_acknowledged: checked_varint_u64(packet.get_largest_acknowledged_raw())?,
+            ack_delay: checked_varint_u64(packet.get_ack_delay_raw())?,
+            first_ack_range: checked_varint_u64(packet.get_first_ack_range_raw())?,
+            ack_ranges: packet.get_ack_range_raw(),
+            ecn_counts: EcnCounts::decode(packet.get_ecn_counts_raw()),
+        })
+    }
+
+    /// The acknowledgements of a multipath PATH_ACK frame, whose path id is
+    /// `packet.get_path_id_raw()`.
+    pub fn path_ack(packet: &'a PathAckPacket) -> Option<Self> {
+        Some(Self {
+            largest_acknowledged: checked_varint_u64(packet.get_largest_acknowledged_raw())?,
+            ack_delay: checked_varint_u64(packet.get_ack_delay_raw())?,
+            first_ack_range: checked_varint_u64(packet.get_first_ack_range_raw())?,
+            ack_ranges: packet.get_ack_range_raw(),
+            ecn_counts: EcnCounts::decode(packet.get_ecn_counts_raw()),
+        })
+    }
+
+    /// The acknowledged packet numbers, in descending order.
+    pub fn ranges(&self) -> AckRanges<'a> {
+        AckRanges {
+            next: self
+                .largest_acknowledged
+                .checked_sub(self.first_ack_range)
+                .map(|start| start..=self.largest_acknowledged),
+            ranges: Varints(self.ack_ranges),
+        }
+    }
+}
+
+/// Iterator over the ranges of packet numbers acknowledged by an ACK frame.
+#[derive(Clone, Debug)]
+pub struct AckRanges<'a> {
+    next: Option<RangeInclusive<u64>>,
+    ranges: Varints<'a>,
+}
+
+impl<'a> Iterator for AckRanges<'a> {
+    type Item = RangeInclusive<u64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.take()?;
+        self.next = match (self.ranges.next(), self.ranges.next()) {
+            (Some(gap), Some(len)) => current
+                .start()
+                .checked_sub(gap + 2)
+                .and_then(|end| Some(end.checked_sub(len)?..=end)),
+            _ => None,
+        };
+        Some(current)
+    }
+}
+
+/// A CONNECTION_CLOSE frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConnectionCloseFrame<'a> {
+    pub error_code: u64,
+    /// The type of the frame that triggered the error, absent for application errors.
+    pub frame_type: Option<u64>,
+    pub reason_phrase: &'a [u8],
+}
+
+impl<'a> ConnectionCloseFrame<'a> {
+    pub fn new(packet: &'a ConnectionClosePacket) -> Option<Self> {
+        let frame_type = packet.get_frame_type_raw();
+        Some(Self {
+            error_code: checked_varint_u64(packet.get_error_code_raw())?,
+            frame_type: if frame_type.is_empty() {
+                None
+            } else {
+                Some(checked_varint_u64(frame_type)?)
+            },
+            reason_phrase: packet.get_reason_phrase_raw(),
+        })
+    }
+
+    /// Whether the error was signaled by the application rather than the transport.
+    pub fn is_application_error(&self) -> bool {
+        self.frame_type.is_none()
+    }
+
+    /// The reason phrase, which should be UTF-8.
+    pub fn reason(&self) -> Result<&'a str, std::str::Utf8Error> {
+        std::str::from_utf8(self.reason_phrase)
+    }
+}
+
+/// A RESET_STREAM frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResetStreamFrame {
+    pub id: u64,
+    pub error_code: u64,
+    pub final_size: u64,
+}
+
+impl ResetStreamFrame {
+    pub fn new(packet: &ResetStreamPacket) -> Option<Self> {
+        Some(Self {
+            id: checked_varint_u64(packet.get_stream_id_raw())?,
+            error_code: checked_varint_u64(packet.get_application_protocol_error_code_raw())?,
+            final_size: checked_varint_u64(packet.get_final_size_raw())?,
+        })
+    }
+}
+
+/// A STOP_SENDING frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StopSendingFrame {
+    pub id: u64,
+    pub error_code: u64,
+}
+
+impl StopSendingFrame {
+    pub fn new(packet: &StopSendingPacket) -> Option<Self> {
+        Some(Self {
+            id: checked_varint_u64(packet.get_stream_id_raw())?,
+            error_code: checked_varint_u64(packet.get_application_protocol_error_code_raw())?,
+        })
+    }
+}
+
+/// A CRYPTO frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CryptoFrame<'a> {
+    pub offset: u64,
+    pub data: &'a [u8],
+}
+
+impl<'a> CryptoFrame<'a> {
+    pub fn new(packet: &'a CryptoPacket) -> Option<Self> {
+        Some(Self {
+            offset: checked_varint_u64(packet.get_offset_raw())?,
+            data: packet.get_crypto_payload_raw(),
+        })
+    }
+}
+
+/// A NEW_TOKEN frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NewTokenFrame<'a> {
+    pub token: &'a [u8],
+}
+
+impl<'a> NewTokenFrame<'a> {
+    pub fn new(packet: &'a NewTokenPacket) -> Self {
+        Self {
+            token: packet.get_token_raw(),
+        }
+    }
+}
+
+/// A MAX_STREAM_DATA or STREAM_DATA_BLOCKED frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StreamLimitFrame {
+    pub id: u64,
+    pub limit: u64,
+}
+
+impl StreamLimitFrame {
+    pub fn max_stream_data(packet: &MaxStreamDataPacket) -> Option<Self> {
+        Some(Self {
+            id: checked_varint_u64(packet.get_stream_id_raw())?,
+            limit: checked_varint_u64(packet.get_max_stream_data_raw())?,
+        })
+    }
+
+    pub fn stream_data_blocked(packet: &StreamDataBlockedPacket) -> Option<Self> {
+        Some(Self {
+            id: checked_varint_u64(packet.get_stream_id_raw())?,
+            limit: checked_varint_u64(packet.get_max_stream_data_raw())?,
+        })
+    }
+}
+
+/// A MAX_STREAMS or STREAMS_BLOCKED frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StreamsFrame {
+    /// Whether the limit applies to bidirectional rather than unidirectional streams.
+    pub bidirectional: bool,
+    pub limit: u64,
+}
+
+impl StreamsFrame {
+    pub fn max_streams(packet: &MaxStreamsPacket) -> Option<Self> {
+        Some(Self {
+            bidirectional: u64::from(packet.get_ty()) == FrameTypes::MaxStreams0.0,
+            limit: checked_varint_u64(packet.get_max_streams_raw())?,
+        })
+    }
+
+    pub fn streams_blocked(packet: &StreamsBlockedPacket) -> Option<Self> {
+        Some(Self {
+            bidirectional: u64::from(packet.get_ty()) == FrameTypes::StreamsBlocked0.0,
+            limit: checked_varint_u64(packet.get_max_streams_raw())?,
+        })
+    }
+}
+
+/// A NEW_CONNECTION_ID frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NewConnectionIdFrame<'a> {
+    pub sequence_number: u64,
+    pub retire_prior_to: u64,
+    pub connection_id: &'a [u8],
+    pub stateless_reset_token: &'a [u8],
+}
+
+impl<'a> NewConnectionIdFrame<'a> {
+    pub fn new(packet: &'a NewConnectionIdPacket) -> Option<Self> {
+        Some(Self {
+            sequence_number: checked_varint_u64(packet.get_sequence_number_raw())?,
+            retire_prior_to: checked_varint_u64(packet.get_retire_prior_to_raw())?,
+            connection_id: packet.get_connection_id_raw(),
+            stateless_reset_token: packet.get_stateless_reset_token_raw(),
+        })
+    }
+}
+
+/// A DATAGRAM frame (RFC 9221).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DatagramFrame<'a> {
+    pub data: &'a [u8],
+}
+
+impl<'a> DatagramFrame<'a> {
+    pub fn new(packet: &'a DatagramPacket) -> Self {
+        Self {
+            data: packet.payload(),
+        }
+    }
+}
+
+/// An ACK_FREQUENCY frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AckFrequencyFrame {
+    pub sequence_number: u64,
+    pub ack_eliciting_threshold: u64,
+    /// The requested maximum acknowledgement delay in microseconds.
+    pub requested_max_ack_delay: u64,
+    pub reordering_threshold: u64,
+}
+
+impl AckFrequencyFrame {
+    pub fn new(packet: &AckFrequencyPacket) -> Option<Self> {
+        Some(Self {
+            sequence_number: checked_varint_u64(packet.get_sequence_number_raw())?,
+            ack_eliciting_threshold: checked_varint_u64(packet.get_ack_eliciting_threshold_raw())?,
+            requested_max_ack_delay: checked_varint_u64(packet.get_requested_max_ack_delay_raw())?,
+            reordering_threshold: checked_varint_u64(packet.get_reordering_threshold_raw())?,
+        })
+    }
+}
+
+/// Appends `len` PADDING frames.
+pub fn encode_padding(buf: &mut Vec<u8>, len: usize) {
+    buf.resize(buf.len() + len, FrameTypes::Padding.0 as u8);
+}
+
+/// Appends a PING frame.
+pub fn encode_ping(buf: &mut Vec<u8>) {
+    encode_varint(FrameTypes::Ping.0, buf);
+}
+
+/// Appends an ACK frame acknowledging `ranges`, which must be non-empty, in descending order and
+/// separated by at least one unacknowledged packet number.
+pub fn encode_ack(
+    buf: &mut Vec<u8>,
+    ranges: &[RangeInclusive<u64>],
+    ack_delay: u64,
+    ecn_counts: Option<&EcnCounts>,
+) {
+    let ty = if ecn_counts.is_some() {
+        FrameTypes::Ack1
+    } else {
+        FrameTypes::Ack0
+    };
+    encode_varint(ty.0, buf);
+    let (first, rest) = ranges.split_first().expect("at least one ack range");
+    encode_varint(*first.end(), buf);
+    encode_varint(ack_delay, buf);
+    encode_varint(rest.len() as u64, buf);
+    encode_varint(first.end() - first.start(), buf);
+    let mut smallest = *first.start();
+    for range in rest {
+        encode_varint(smallest - range.end() - 2, buf);
+        encode_varint(range.end() - range.start(), buf);
+        smallest = *range.start();
+    }
+    if let Some(ecn) = ecn_counts {
+        encode_varint(ecn.ect0, buf);
+        encode_varint(ecn.ect1, buf);
+        encode_varint(ecn.ce, buf);
+    }
+}
+
+/// Appends a RESET_STREAM frame.
+pub fn encode_reset_stream(buf: &mut Vec<u8>, frame: &ResetStreamFrame) {
+    encode_varint(FrameTypes::ResetStream.0, buf);
+    encode_varint(frame.id, buf);
+    encode_varint(frame.error_code, buf);
+    encode_varint(frame.final_size, buf);
+}
+
+/// Appends a STOP_SENDING frame.
+pub fn encode_stop_sending(buf: &mut Vec<u8>, frame: &StopSendingFrame) {
+    encode_varint(FrameTypes::StopSending.0, buf);
+    encode_varint(frame.id, buf);
+    encode_varint(frame.error_code, buf);
+}
+
+/// Appends a CRYPTO frame.
+pub fn encode_crypto(buf: &mut Vec<u8>, offset: u64, data: &[u8]) {
+    encode_varint(FrameTypes::Crypto.0, buf);
+    encode_varint(offset, buf);
+    encode_varint(data.len() as u64, buf);
+    buf.extend_from_slice(data);
+}
+
+/// Appends a NEW_TOKEN frame.
+pub fn encode_new_token(buf: &mut Vec<u8>, token: &[u8]) {
+    encode_varint(FrameTypes::NewToken.0, buf);
+    encode_varint(token.len() as u64, buf);
+    buf.extend_from_slice(token);
+}
+
+/// Appends a STREAM frame. The length is always encoded, so further frames may follow.
+pub fn encode_stream(buf: &mut Vec<u8>, id: u64, offset: Option<u64>, data: &[u8], fin: bool) {
+    let mut ty = FrameTypes::Stream2.0;
+    if offset.is_some() {
+        ty |= 0x04;
+    }
+    if fin {
+        ty |= 0x01;
+    }
+    encode_varint(ty, buf);
+    encode_varint(id, buf);
+    if let Some(offset) = offset {
+        encode_varint(offset, buf);
+    }
+    encode_varint(data.len() as u64, buf);
+    buf.extend_from_slice(data);
+}
+
+/// Appends a MAX_DATA frame.
+pub fn encode_max_data(buf: &mut Vec<u8>, max_data: u64) {
+    encode_varint(FrameTypes::MaxData.0, buf);
+    encode_varint(max_data, buf);
+}
+
+/// Appends a MAX_STREAM_DATA frame.
+pub fn encode_max_stream_data(buf: &mut Vec<u8>, frame: &StreamLimitFrame) {
+    encode_varint(FrameTypes::MaxStreamData.0, buf);
+    encode_varint(frame.id, buf);
+    encode_varint(frame.limit, buf);
+}
+
+/// Appends a MAX_STREAMS frame.
+pub fn encode_max_streams(buf: &mut Vec<u8>, frame: &StreamsFrame) {
+    let ty = if frame.bidirectional {
+        FrameTypes::MaxStreams0
+    } else {
+        FrameTypes::MaxStreams1
+    };
+    encode_varint(ty.0, buf);
+    encode_varint(frame.limit, buf);
+}
+
+/// Appends a DATA_BLOCKED frame.
+pub fn encode_data_blocked(buf: &mut Vec<u8>, max_data: u64) {
+    encode_varint(FrameTypes::DataBlocked.0, buf);
+    encode_varint(max_data, buf);
+}
+
+/// Appends a STREAM_DATA_BLOCKED frame.
+pub fn encode_stream_data_blocked(buf: &mut Vec<u8>, frame: &StreamLimitFrame) {
+    encode_varint(FrameTypes::StreamDataBlocked.0, buf);
+    encode_varint(frame.id, buf);
+    encode_varint(frame.limit, buf);
+}
+
+/// Appends a STREAMS_BLOCKED frame.
+pub fn encode_streams_blocked(buf: &mut Vec<u8>, frame: &StreamsFrame) {
+    let ty = if frame.bidirectional {
+        FrameTypes::StreamsBlocked0
+    } else {
+        FrameTypes::StreamsBlocked1
+    };
+    encode_varint(ty.0, buf);
+    encode_varint(frame.limit, buf);
+}
+
+/// Appends a NEW_CONNECTION_ID frame.
+///
+/// # Panics
+///
+/// Panics if the connection id is longer than 255 bytes.
+pub fn encode_new_connection_id(buf: &mut Vec<u8>, frame: &NewConnectionIdFrame) {
+    encode_varint(FrameTypes::NewConnectionId.0, buf);
+    encode_varint(frame.sequence_number, buf);
+    encode_varint(frame.retire_prior_to, buf);
+    buf.push(u8::try_from(frame.connection_id.len()).expect("connection id too long"));
+    buf.extend_from_slice(frame.connection_id);
+    buf.extend_from_slice(frame.stateless_reset_token);
+}
+
+/// Appends a RETIRE_CONNECTION_ID frame.
+pub fn encode_retire_connection_id(buf: &mut Vec<u8>, sequence_number: u64) {
+    encode_varint(FrameTypes::RetireConnectionId.0, buf);
+    encode_varint(sequence_number, buf);
+}
+
+/// Appends a PATH_CHALLENGE frame.
+pub fn encode_path_challenge(buf: &mut Vec<u8>, data: &[u8; 8]) {
+    encode_varint(FrameTypes::PathChallenge.0, buf);
+    buf.extend_from_slice(data);
+}
+
+/// Appends a PATH_RESPONSE frame.
+pub fn encode_path_response(buf: &mut Vec<u8>, data: &[u8; 8]) {
+    encode_varint(FrameTypes::PathResponse.0, buf);
+    buf.extend_from_slice(data);
+}
+
+/// Appends a CONNECTION_CLOSE frame. Frames without a `frame_type` signal application errors.
+pub fn encode_connection_close(buf: &mut Vec<u8>, frame: &ConnectionCloseFrame) {
+    match frame.frame_type {
+        Some(frame_type) => {
+            encode_varint(FrameTypes::ConnectionClose0.0, buf);
+            encode_varint(frame.error_code, buf);
+            encode_varint(frame_type, buf);
+        }
+        None => {
+            encode_varint(FrameTypes::ConnectionClose1.0, buf);
+            encode_varint(frame.error_code, buf);
+        }
+    }
+    encode_varint(frame.reason_phrase.len() as u64, buf);
+    buf.extend_from_slice(frame.reason_phrase);
+}
+
+/// Appends a HANDSHAKE_DONE frame.
+pub fn encode_handshake_done(buf: &mut Vec<u8>) {
+    encode_varint(FrameTypes::HandshakeDone.0, buf);
+}
+
+/// Appends a DATAGRAM frame. The length is always encoded, so further frames may follow.
+pub fn encode_datagram(buf: &mut Vec<u8>, data: &[u8]) {
+    encode_varint(FrameTypes::Datagram1.0, buf);
+    encode_varint(data.len() as u64, buf);
+    buf.extend_from_slice(data);
+}
+
+/// Appends an ACK_FREQUENCY frame.
+pub fn encode_ack_frequency(buf: &mut Vec<u8>, frame: &AckFrequencyFrame) {
+    encode_varint(FrameTypes::AckFrequency.0, buf);
+    encode_varint(frame.sequence_number, buf);
+    encode_varint(frame.ack_eliciting_threshold, buf);
+    encode_varint(frame.requested_max_ack_delay, buf);
+    encode_varint(frame.reordering_threshold, buf);
+}
+
+/// Appends an IMMEDIATE_ACK frame.
+pub fn encode_immediate_ack(buf: &mut Vec<u8>) {
+    encode_varint(FrameTypes::ImmediateAck.0, buf);
+}
+
+/// Iterator over a sequence of variable-length integers.
+#[derive(Clone, Debug)]
+struct Varints<'a>(&'a [u8]);
+
+impl<'a> Iterator for Varints<'a> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.0.is_empty() {
+            return None;
+        }
+        let len = varint_length(self.0);
+        let bytes = self.0.get(..len)?;
+        self.0 = &self.0[len..];
+        Some(varint_u64(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quic::{n_varints, Frame, FrameType};
+
+    #[test]
+    fn stream_frame() {
+        let buf = [0x0f, 0x04, 0x40, 0x10, 0x03, b'a', b'b', b'c', 0x01];
+        let frames = Frame::new(&buf[..]).unwrap();
+        let stream = match &frames[0] {
+            Frame::Stream(p) => StreamFrame::new(p).unwrap(),
+            f => panic!("expected stream, got {}", f),
+        };
+        assert_eq!(
+            stream,
+            StreamFrame {
+                id: 4,
+                offset: Some(16),
+                len: 3,
+                fin: true,
+                data: b"abc",
+            }
+        );
+        assert!(matches!(frames[1], Frame::Ping(_)));
+
+        let buf = [0x08, 0x00, b'x', b'y'];
+        let frames = Frame::new(&buf[..]).unwrap();
+        let stream = match &frames[0] {
+            Frame::Stream(p) => StreamFrame::new(p).unwrap(),
+            f => panic!("expected stream, got {}", f),
+        };
+        assert_eq!(stream.offset, None);
+        assert!(!stream.fin);
+        assert_eq!(stream.data, b"xy");
+    }
+
+    #[test]
+    fn ack_frame() {
+        // largest 100, first range 2, then gap 1 len 3, gap 0 len 0, ecn 1 2 3
+        let buf = [
+            0x03, 0x40, 0x64, 0x05, 0x02, 0x02, 0x01, 0x03, 0x00, 0x00, 0x01, 0x02, 0x03,
+        ];
+        let frames = Frame::new(&buf[..]).unwrap();
+        let ack = match &frames[0] {
+            Frame::Ack(p) => AckFrame::new(p).unwrap(),
+            f => panic!("expected ack, got {}", f),
+        };
+        assert_eq!(ack.largest_acknowledged, 100);
+        assert_eq!(ack.ack_delay, 5);
+        assert_eq!(
+            ack.ranges().collect::<Vec<_>>(),
+            vec![98..=100, 92..=95, 90..=90]
+        );
+        assert_eq!(
+            ack.ecn_counts,
+            Some(EcnCounts {
+                ect0: 1,
+                ect1: 2,
+                ce: 3
+            })
+        );
+
+        let buf = [0x02, 0x01, 0x00, 0x01, 0x00, 0x05, 0x00];
+        let frames = Frame::new(&buf[..]).unwrap();
+        let ack = match &frames[0] {
+            Frame::Ack(p) => AckFrame::new(p).unwrap(),
+            f => panic!("expected ack, got {}", f),
+        };
+        assert_eq!(ack.ecn_counts, None);
+        assert_eq!(ack.ranges().collect::<Vec<_>>(), vec![1..=1]);
+    }
+
+    #[test]
+    fn connection_close_frame() {
+        let buf = [0x1c, 0x0a, 0x06, 0x03, b'b', b'a', b'd'];
+        let frames = Frame::new(&buf[..]).unwrap();
+        let close = match &frames[0] {
+            Frame::ConnectionClose(p) => ConnectionCloseFrame::new(p).unwrap(),
+            f => panic!("expected connection close, got {}", f),
+        };
+        assert_eq!(close.error_code, 10);
+        assert_eq!(close.frame_type, Some(6));
+        assert!(!close.is_application_error());
+        assert_eq!(close.reason(), Ok("bad"));
+
+        let buf = [0x1d, 0x01, 0x00];
+        let frames = Frame::new(&buf[..]).unwrap();
+        let close = match &frames[0] {
+            Frame::ConnectionClose(p) => ConnectionCloseFrame::new(p).unwrap(),
+            f => panic!("expected connection close, got {}", f),
+        };
+        assert!(close.is_application_error());
+        assert_eq!(close.reason(), Ok(""));
+    }
+
+    #[test]
+    fn new_connection_id_frame() {
+        let mut buf = vec![0x18, 0x02, 0x01, 0x04, 0xaa, 0xbb, 0xcc, 0xdd];
+        buf.extend_from_slice(&[0x11; 16]);
+        let frames = Frame::new(&buf[..]).unwrap();
+        let ncid = match &frames[0] {
+            Frame::NewConnectionId(p) => NewConnectionIdFrame::new(p).unwrap(),
+            f => panic!("expected new connection id, got {}", f),
+        };
+        assert_eq!(ncid.sequence_number, 2);
+        assert_eq!(ncid.retire_prior_to, 1);
+        assert_eq!(ncid.connection_id, &[0xaa, 0xbb, 0xcc, 0xdd]);
+        assert_eq!(ncid.stateless_reset_token, &[0x11; 16]);
+    }
+
+    #[test]
+    fn encode_frames() {
+        let mut buf = vec![];
+        encode_ping(&mut buf);
+        encode_stream(&mut buf, 4, Some(16), b"abc", true);
+        encode_ack(&mut buf, &[98..=100, 92..=95, 90..=90], 5, None);
+        encode_crypto(&mut buf, 0x4000, b"hello");
+        encode_connection_close(
+            &mut buf,
+            &ConnectionCloseFrame {
+                error_code: 10,
+                frame_type: None,
+                reason_phrase: b"bye",
+            },
+        );
+        encode_max_streams(
+            &mut buf,
+            &StreamsFrame {
+                bidirectional: false,
+                limit: 100,
+            },
+        );
+        encode_new_connection_id(
+            &mut buf,
+            &NewConnectionIdFrame {
+                sequence_number: 1,
+                retire_prior_to: 0,
+                connection_id: &[1, 2, 3],
+                stateless_reset_token: &[7; 16],
+            },
+        );
+        encode_path_challenge(&mut buf, &[9; 8]);
+        encode_handshake_done(&mut buf);
+        encode_padding(&mut buf, 3);
+
+        let frames = Frame::new(&buf).unwrap();
+        assert_eq!(frames.len(), 10);
+        assert!(matches!(frames[0], Frame::Ping(_)));
+        match &frames[1] {
+            Frame::Stream(p) => {
+                let stream = StreamFrame::new(p).unwrap();
+                assert_eq!((stream.id, stream.offset, stream.fin), (4, Some(16), true));
+                assert_eq!(stream.data, b"abc");
+            }
+            f => panic!("expected stream, got {}", f),
+        }
+        match &frames[2] {
+            Frame::Ack(p) => {
+                let ack = AckFrame::new(p).unwrap();
+                assert_eq!(ack.ack_delay, 5);
+                assert_eq!(ack.ecn_counts, None);
+                let ranges: Vec<_> = ack.ranges().collect();
+                assert_eq!(ranges, vec![98..=100, 92..=95, 90..=90]);
+            }
+            f => panic!("expected ack, got {}", f),
+        }
+        match &frames[3] {
+            Frame::Crypto(p) => {
+                assert_eq!(CryptoFrame::new(p).unwrap().offset, 0x4000);
+                assert_eq!(CryptoFrame::new(p).unwrap().data, b"hello");
+            }
+            f => panic!("expected crypto, got {}", f),
+        }
+        match &frames[4] {
+            Frame::ConnectionClose(p) => {
+                let close = ConnectionCloseFrame::new(p).unwrap();
+                assert!(close.is_application_error());
+                assert_eq!(close.reason(), Ok("bye"));
+            }
+            f => panic!("expected connection close, got {}", f),
+        }
+        match &frames[5] {
+            Frame::MaxStreams(p) => {
+                assert_eq!(StreamsFrame::max_streams(p).unwrap().limit, 100);
+                assert!(!StreamsFrame::max_streams(p).unwrap().bidirectional);
+            }
+            f => panic!("expected max streams, got {}", f),
+        }
+        match &frames[6] {
+            Frame::NewConnectionId(p) => {
+                assert_eq!(
+                    NewConnectionIdFrame::new(p).unwrap().connection_id,
+                    &[1, 2, 3]
+                );
+            }
+            f => panic!("expected new connection id, got {}", f),
+        }
+        assert!(matches!(frames[7], Frame::PathChallenge(_)));
+        assert!(matches!(frames[8], Frame::HandshakeDone(_)));
+        assert!(matches!(frames[9], Frame::Padding(_, 3)));
+    }
+
+    #[test]
+    fn extension_frames() {
+        let mut buf = vec![];
+        encode_datagram(&mut buf, b"hello");
+        encode_immediate_ack(&mut buf);
+        let ack_frequency = AckFrequencyFrame {
+            sequence_number: 3,
+            ack_eliciting_threshold: 10,
+            requested_max_ack_delay: 25_000,
+            reordering_threshold: 1,
+        };
+        encode_ack_frequency(&mut buf, &ack_frequency);
+        // PATH_ACK on path 1: largest 10, delay 2, no further ranges, first range 1, ecn 4 5 6
+        buf.extend_from_slice(&[
+            0x95, 0x22, 0x8c, 0x01, 0x01, 0x0a, 0x02, 0x00, 0x01, 4, 5, 6,
+        ]);
+        // unreliable datagram without a length, extending to the end of the packet
+        buf.extend_from_slice(&[0x30, b'b', b'y', b'e']);
+
+        let frames = Frame::new(&buf).unwrap();
+        assert_eq!(frames.len(), 5);
+        match &frames[0] {
+            Frame::Datagram(p) => assert_eq!(DatagramFrame::new(p).data, b"hello"),
+            f => panic!("expected datagram, got {}", f),
+        }
+        assert_eq!(frames[1].ty(), FrameTypes::ImmediateAck);
+        match &frames[2] {
+            Frame::AckFrequency(p) => assert_eq!(AckFrequencyFrame::new(p).unwrap(), ack_frequency),
+            f => panic!("expected ack frequency, got {}", f),
+        }
+        match &frames[3] {
+            Frame::PathAck(p) => {
+                assert_eq!(varint_u64(p.get_path_id_raw()), 1);
+                let ack = AckFrame::path_ack(p).unwrap();
+                assert_eq!(ack.ranges().collect::<Vec<_>>(), vec![9..=10]);
+                assert_eq!(ack.ecn_counts.map(|ecn| ecn.ce), Some(6));
+            }
+            f => panic!("expected path ack, got {}", f),
+        }
+        assert_eq!(frames[3].ty(), FrameTypes::PathAck1);
+        assert_eq!(frames[3].to_string(), "path-ack");
+        match &frames[4] {
+            Frame::Datagram(p) => assert_eq!(DatagramFrame::new(p).data, b"bye"),
+            f => panic!("expected datagram, got {}", f),
+        }
+    }
+
+    #[test]
+    fn unknown_frames() {
+        // PING, then a reserved frame type, then bytes that can't be parsed
+        let buf = [0x01, 0x80, 0x00, 0xca, 0xfe, 0xff, 0xff];
+        let frames = Frame::new(&buf[..]).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert!(matches!(frames[0], Frame::Ping(_)));
+        match frames[1] {
+            Frame::Unknown { ty, rest } => {
+                assert_eq!(ty, FrameType(0xcafe));
+                assert_eq!(rest, &[0xff, 0xff]);
+            }
+            ref f => panic!("expected unknown, got {}", f),
+        }
+        // a PING with an overlong type
+        match Frame::new(&[0x40, 0x01]).unwrap()[..] {
+            [Frame::Unknown { ty, rest }] => assert_eq!((ty, rest), (FrameTypes::Ping, &[][..])),
+            ref f => panic!("expected unknown, got {:?}", f),
+        }
+        // truncated frame type
+        assert!(Frame::new(&[0x01, 0x80, 0x00]).is_none());
+    }
+
+    #[test]
+    fn truncated_frames() {
+        // ACK, RESET_STREAM, CRYPTO, NEW_TOKEN and MAX_STREAMS without their fields
+        for ty in &[0x02, 0x04, 0x06, 0x07, 0x12] {
+            assert!(Frame::new(&[*ty]).is_none(), "{:#x}", ty);
+        }
+        // ACK with a truncated ack range and ECN counts
+        assert!(Frame::new(&[0x02, 0x01, 0x00, 0x01, 0x00, 0x05]).is_none());
+        assert!(Frame::new(&[0x03, 0x01, 0x00, 0x00, 0x00, 0x01, 0x02]).is_none());
+        // CRYPTO with fewer bytes than its length
+        assert!(Frame::new(&[0x06, 0x00, 0x05, 0x01]).is_none());
+        // a PING followed by a truncated STREAM with an offset
+        assert!(Frame::new(&[0x01, 0x0c, 0x04]).is_none());
+        assert_eq!(n_varints(2, &[0x40]), 2);
+        assert_eq!(varint_length(&[]), 1);
+    }
+
+    #[test]
+    fn truncated_varints() {
+        use crate::quic::{CryptoPacket, MaxStreamsPacket};
+
+        let max_streams = MaxStreamsPacket::new(&[0x12, 0x40]).unwrap();
+        assert_eq!(StreamsFrame::max_streams(&max_streams), None);
+        let max_streams = MaxStreamsPacket::new(&[0x12, 0x40, 0x64]).unwrap();
+        assert_eq!(StreamsFrame::max_streams(&max_streams).unwrap().limit, 100);
+        let crypto = CryptoPacket::new(&[0x06, 0x80, 0x00]).unwrap();
+        assert_eq!(CryptoFrame::new(&crypto), None);
+        assert_eq!(checked_varint_u64(&[]), None);
+    }
+
+    #[test]
+    fn encode_varints() {
+        for &(value, len) in &[
+            (0, 1),
+            (63, 1),
+            (64, 2),
+            (16383, 2),
+            (16384, 4),
+            (1 << 30, 8),
+        ] {
+            let mut buf = vec![];
+            encode_varint(value, &mut buf);
+            assert_eq!(buf.len(), len);
+            assert_eq!(varint_length(&buf), len);
+            assert_eq!(varint_u64(&buf), value);
+        }
+    }
+}
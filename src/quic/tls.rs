@@ -0,0 +1,605 @@
+//! TLS 1.3 handshake messages carried in QUIC CRYPTO frames (RFC 9001 section 4).
+//!
+//! `CryptoStream` reassembles CRYPTO frame data by offset, from which the ClientHello and
+//! ServerHello can be parsed to extract the server name, the ALPN protocols and the QUIC
+//! transport parameters.
+
+use super::frame::CryptoFrame;
+use super::{split_varint, Frame};
+use crate::{types::*, Packet, PrimitiveValues};
+use std::collections::BTreeMap;
+
+/// Reassembles the data of CRYPTO frames of one packet number space.
+#[derive(Clone, Debug, Default)]
+pub struct CryptoStream {
+    data: Vec<u8>,
+    pending: BTreeMap<u64, Vec<u8>>,
+}
+
+impl CryptoStream {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts `data` received at `offset`, which may arrive out of order or overlap.
+    pub fn insert(&mut self, offset: u64, data: &[u8]) {
+        let end = offset + data.len() as u64;
+        if end <= self.data.len() as u64 {
+            return;
+        }
+        if offset > self.data.len() as u64 {
+            let pending = self.pending.entry(offset).or_default();
+            if pending.len() < data.len() {
+                *pending = data.to_vec();
+            }
+            return;
+        }
+        let start = self.data.len() - offset as usize;
+        self.data.extend_from_slice(&data[start..]);
+        while let Some((&offset, _)) = self.pending.iter().next() {
+            if offset > self.data.len() as u64 {
+                break;
+            }
+            let data = self.pending.remove(&offset).unwrap_or_default();
+            self.insert(offset, &data);
+        }
+    }
+
+    /// Inserts the data of every CRYPTO frame in `frames`.
+    pub fn insert_frames(&mut self, frames: &[Frame]) {
+        for frame in frames {
+            if let Some(crypto) = match frame {
+                Frame::Crypto(packet) => CryptoFrame::new(packet),
+                _ => None,
+            } {
+                self.insert(crypto.offset, crypto.data);
+            }
+        }
+    }
+
+    /// The data received so far without gaps.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// The complete handshake messages received so far.
+    pub fn messages(&self) -> HandshakeMessages<'_> {
+        HandshakeMessages(&self.data)
+    }
+
+    /// The ClientHello, once it has been received completely. Returns `None` if the message is
+    /// too short for its fields.
+    pub fn client_hello(&self) -> Option<ClientHelloPacket<'_>> {
+        self.messages()
+            .find(|m| m.get_msg_type() == HandshakeTypes::ClientHello)
+            .and_then(|m| client_hello(m.get_body_borrowed()?))
+    }
+
+    /// The ServerHello, once it has been received completely. Returns `None` if the message is
+    /// too short for its fields.
+    pub fn server_hello(&self) -> Option<ServerHelloPacket<'_>> {
+        self.messages()
+            .find(|m| m.get_msg_type() == HandshakeTypes::ServerHello)
+            .and_then(|m| server_hello(m.get_body_borrowed()?))
+    }
+}
+
+/// Iterator over the complete handshake messages in a buffer.
+#[derive(Clone, Debug)]
+pub struct HandshakeMessages<'a>(&'a [u8]);
+
+impl<'a> HandshakeMessages<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self(buf)
+    }
+}
+
+impl<'a> Iterator for HandshakeMessages<'a> {
+    type Item = HandshakeMessagePacket<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let message = HandshakeMessagePacket::new(self.0)?;
+        let len = 4 + message.get_length() as usize;
+        if self.0.len() < len {
+            return None;
+        }
+        self.0 = &self.0[len..];
+        Some(message)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct HandshakeMessage {
+    #[construct_with(u8)]
+    msg_type: HandshakeType,
+    length: u24be,
+    #[length = "length"]
+    body: Vec<u8>,
+    remaining: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct ClientHello {
+    legacy_version: u16be,
+    #[length = "32"]
+    random: Vec<u8>,
+    session_id_len: u8,
+    #[length = "session_id_len"]
+    session_id: Vec<u8>,
+    cipher_suites_len: u16be,
+    #[length = "cipher_suites_len"]
+    cipher_suites: Vec<u8>,
+    compression_methods_len: u8,
+    #[length = "compression_methods_len"]
+    compression_methods: Vec<u8>,
+    extensions_len: u16be,
+    #[length = "extensions_len"]
+    extensions: Vec<u8>,
+    remaining: Vec<u8>,
+}
+
+/// Parses a ClientHello after checking that `body` holds every field up to the extensions.
+fn client_hello(body: &[u8]) -> Option<ClientHelloPacket<'_>> {
+    let (_session_id, rest) = split_u8(body.get(34..)?)?;
+    let (_cipher_suites, rest) = split_u16(rest)?;
+    let (_compression_methods, rest) = split_u8(rest)?;
+    split_u16(rest)?;
+    ClientHelloPacket::new(body)
+}
+
+impl<'a> ClientHelloPacket<'a> {
+    /// The extensions, or `None` if the packet owns its data.
+    pub fn extensions(&self) -> Option<Extensions<'a>> {
+        Some(Extensions(self.get_extensions_borrowed()?))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct ServerHello {
+    legacy_version: u16be,
+    #[length = "32"]
+    random: Vec<u8>,
+    session_id_len: u8,
+    #[length = "session_id_len"]
+    session_id: Vec<u8>,
+    cipher_suite: u16be,
+    compression_method: u8,
+    extensions_len: u16be,
+    #[length = "extensions_len"]
+    extensions: Vec<u8>,
+    remaining: Vec<u8>,
+}
+
+/// Parses a ServerHello after checking that `body` holds every field up to the extensions.
+fn server_hello(body: &[u8]) -> Option<ServerHelloPacket<'_>> {
+    let (_session_id, rest) = split_u8(body.get(34..)?)?;
+    split_u16(rest.get(3..)?)?;
+    ServerHelloPacket::new(body)
+}
+
+impl<'a> ServerHelloPacket<'a> {
+    /// The extensions, or `None` if the packet owns its data.
+    pub fn extensions(&self) -> Option<Extensions<'a>> {
+        Some(Extensions(self.get_extensions_borrowed()?))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct Extension {
+    #[construct_with(u16be)]
+    ty: ExtensionType,
+    length: u16be,
+    #[length = "length"]
+    data: Vec<u8>,
+    remaining: Vec<u8>,
+}
+
+/// Iterator over a list of TLS extensions.
+#[derive(Clone, Debug)]
+pub struct Extensions<'a>(&'a [u8]);
+
+impl<'a> Extensions<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self(buf)
+    }
+
+    /// The data of the first extension of type `ty`.
+    pub fn get(&self, ty: ExtensionType) -> Option<&'a [u8]> {
+        self.clone()
+            .find(|e| e.get_ty() == ty)
+            .and_then(|e| e.get_data_borrowed())
+    }
+
+    /// The host name of the server_name extension.
+    pub fn server_name(&self) -> Option<&'a str> {
+        let mut list = Vectors::u16(self.get(ExtensionTypes::ServerName)?)?;
+        let mut names = list.next()?;
+        while let Some((&name_type, rest)) = names.split_first() {
+            let (name, rest) = split_u16(rest)?;
+            if name_type == 0 {
+                return std::str::from_utf8(name).ok();
+            }
+            names = rest;
+        }
+        None
+    }
+
+    /// The protocols of the application_layer_protocol_negotiation extension.
+    pub fn alpn(&self) -> Option<Vec<&'a [u8]>> {
+        let list =
+            Vectors::u16(self.get(ExtensionTypes::ApplicationLayerProtocolNegotiation)?)?.next()?;
+        Some(Vectors::u8(list).collect())
+    }
+
+    /// The decoded quic_transport_parameters extension, or its draft codepoint.
+    pub fn transport_parameters(&self) -> Option<TransportParameters<'a>> {
+        let data = self
+            .get(ExtensionTypes::QuicTransportParameters)
+            .or_else(|| self.get(ExtensionTypes::QuicTransportParametersDraft))?;
+        TransportParameters::new(data)
+    }
+}
+
+impl<'a> Iterator for Extensions<'a> {
+    type Item = ExtensionPacket<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let extension = ExtensionPacket::new(self.0)?;
+        let len = 4 + extension.get_length() as usize;
+        if self.0.len() < len {
+            return None;
+        }
+        self.0 = &self.0[len..];
+        Some(extension)
+    }
+}
+
+/// Splits a vector with an 8 bit length prefix off the front of `buf`.
+fn split_u8(buf: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (&len, rest) = buf.split_first()?;
+    (rest.len() >= len as usize).then(|| rest.split_at(len as usize))
+}
+
+/// Splits a vector with a 16 bit length prefix off the front of `buf`.
+fn split_u16(buf: &[u8]) -> Option<(&[u8], &[u8])> {
+    let len = u16::from_be_bytes([*buf.first()?, *buf.get(1)?]) as usize;
+    let rest = &buf[2..];
+    (rest.len() >= len).then(|| rest.split_at(len))
+}
+
+/// Iterator over length-prefixed vectors.
+struct Vectors<'a> {
+    buf: &'a [u8],
+    wide: bool,
+}
+
+impl<'a> Vectors<'a> {
+    fn u8(buf: &'a [u8]) -> Self {
+        Self { buf, wide: false }
+    }
+
+    fn u16(buf: &'a [u8]) -> Option<Self> {
+        (buf.len() >= 2).then_some(Self { buf, wide: true })
+    }
+}
+
+impl<'a> Iterator for Vectors<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        let (vector, rest) = if self.wide {
+            split_u16(self.buf)?
+        } else {
+            split_u8(self.buf)?
+        };
+        self.buf = rest;
+        Some(vector)
+    }
+}
+
+/// QUIC transport parameters (RFC 9000 section 18.2). Absent parameters take the default
+/// values defined by the RFC.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransportParameters<'a> {
+    pub original_destination_connection_id: Option<&'a [u8]>,
+    pub max_idle_timeout: Option<u64>,
+    pub stateless_reset_token: Option<&'a [u8]>,
+    pub max_udp_payload_size: Option<u64>,
+    pub initial_max_data: Option<u64>,
+    pub initial_max_stream_data_bidi_local: Option<u64>,
+    pub initial_max_stream_data_bidi_remote: Option<u64>,
+    pub initial_max_stream_data_uni: Option<u64>,
+    pub initial_max_streams_bidi: Option<u64>,
+    pub initial_max_streams_uni: Option<u64>,
+    pub ack_delay_exponent: Option<u64>,
+    pub max_ack_delay: Option<u64>,
+    pub disable_active_migration: bool,
+    pub preferred_address: Option<&'a [u8]>,
+    pub active_connection_id_limit: Option<u64>,
+    pub initial_source_connection_id: Option<&'a [u8]>,
+    pub retry_source_connection_id: Option<&'a [u8]>,
+    /// Parameters not defined by RFC 9000, such as `max_datagram_frame_size`.
+    pub unknown: Vec<(u64, &'a [u8])>,
+}
+
+impl<'a> TransportParameters<'a> {
+    /// Decodes the contents of a quic_transport_parameters extension.
+    pub fn new(mut buf: &'a [u8]) -> Option<Self> {
+        let mut params = Self::default();
+        while !buf.is_empty() {
+            let (id, rest) = split_varint(buf)?;
+            let (len, rest) = split_varint(rest)?;
+            if rest.len() < len as usize {
+                return None;
+            }
+            let (value, rest) = rest.split_at(len as usize);
+            buf = rest;
+            let int = || -> Option<u64> {
+                let (int, rest) = split_varint(value)?;
+                rest.is_empty().then_some(int)
+            };
+            match id {
+                0x00 => params.original_destination_connection_id = Some(value),
+                0x01 => params.max_idle_timeout = Some(int()?),
+                0x02 => params.stateless_reset_token = Some(value),
+                0x03 => params.max_udp_payload_size = Some(int()?),
+                0x04 => params.initial_max_data = Some(int()?),
+                0x05 => params.initial_max_stream_data_bidi_local = Some(int()?),
+                0x06 => params.initial_max_stream_data_bidi_remote = Some(int()?),
+                0x07 => params.initial_max_stream_data_uni = Some(int()?),
+                0x08 => params.initial_max_streams_bidi = Some(int()?),
+                0x09 => params.initial_max_streams_uni = Some(int()?),
+                0x0a => params.ack_delay_exponent = Some(int()?),
+                0x0b => params.max_ack_delay = Some(int()?),
+                0x0c => params.disable_active_migration = true,
+                0x0d => params.preferred_address = Some(value),
+                0x0e => params.active_connection_id_limit = Some(int()?),
+                0x0f => params.initial_source_connection_id = Some(value),
+                0x10 => params.retry_source_connection_id = Some(value),
+                _ => params.unknown.push((id, value)),
+            }
+        }
+        Some(params)
+    }
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod HandshakeTypes {
+    use super::HandshakeType;
+
+    pub const ClientHello: HandshakeType = HandshakeType(1);
+    pub const ServerHello: HandshakeType = HandshakeType(2);
+    pub const NewSessionTicket: HandshakeType = HandshakeType(4);
+    pub const EndOfEarlyData: HandshakeType = HandshakeType(5);
+    pub const EncryptedExtensions: HandshakeType = HandshakeType(8);
+    pub const Certificate: HandshakeType = HandshakeType(11);
+    pub const CertificateRequest: HandshakeType = HandshakeType(13);
+    pub const CertificateVerify: HandshakeType = HandshakeType(15);
+    pub const Finished: HandshakeType = HandshakeType(20);
+    pub const KeyUpdate: HandshakeType = HandshakeType(24);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandshakeType(pub u8);
+
+impl HandshakeType {
+    /// Construct a new `HandshakeType`.
+    pub fn new(val: u8) -> Self {
+        Self(val)
+    }
+}
+
+impl PrimitiveValues for HandshakeType {
+    type T = (u8,);
+    fn to_primitive_values(&self) -> Self::T {
+        (self.0,)
+    }
+}
+
+impl std::fmt::Display for HandshakeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let s = match *self {
+            HandshakeTypes::ClientHello => "client-hello",
+            HandshakeTypes::ServerHello => "server-hello",
+            HandshakeTypes::NewSessionTicket => "new-session-ticket",
+            HandshakeTypes::EndOfEarlyData => "end-of-early-data",
+            HandshakeTypes::EncryptedExtensions => "encrypted-extensions",
+            HandshakeTypes::Certificate => "certificate",
+            HandshakeTypes::CertificateRequest => "certificate-request",
+            HandshakeTypes::CertificateVerify => "certificate-verify",
+            HandshakeTypes::Finished => "finished",
+            HandshakeTypes::KeyUpdate => "key-update",
+            _ => "unknown",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod ExtensionTypes {
+    use super::ExtensionType;
+
+    pub const ServerName: ExtensionType = ExtensionType(0);
+    pub const SupportedGroups: ExtensionType = ExtensionType(10);
+    pub const SignatureAlgorithms: ExtensionType = ExtensionType(13);
+    pub const ApplicationLayerProtocolNegotiation: ExtensionType = ExtensionType(16);
+    pub const PreSharedKey: ExtensionType = ExtensionType(41);
+    pub const EarlyData: ExtensionType = ExtensionType(42);
+    pub const SupportedVersions: ExtensionType = ExtensionType(43);
+    pub const PskKeyExchangeModes: ExtensionType = ExtensionType(45);
+    pub const KeyShare: ExtensionType = ExtensionType(51);
+    pub const QuicTransportParameters: ExtensionType = ExtensionType(57);
+    pub const QuicTransportParametersDraft: ExtensionType = ExtensionType(0xffa5);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExtensionType(pub u16);
+
+impl ExtensionType {
+    /// Construct a new `ExtensionType`.
+    pub fn new(val: u16) -> Self {
+        Self(val)
+    }
+}
+
+impl PrimitiveValues for ExtensionType {
+    type T = (u16,);
+    fn to_primitive_values(&self) -> Self::T {
+        (self.0,)
+    }
+}
+
+impl std::fmt::Display for ExtensionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let s = match *self {
+            ExtensionTypes::ServerName => "server-name",
+            ExtensionTypes::SupportedGroups => "supported-groups",
+            ExtensionTypes::SignatureAlgorithms => "signature-algorithms",
+            ExtensionTypes::ApplicationLayerProtocolNegotiation => "alpn",
+            ExtensionTypes::PreSharedKey => "pre-shared-key",
+            ExtensionTypes::EarlyData => "early-data",
+            ExtensionTypes::SupportedVersions => "supported-versions",
+            ExtensionTypes::PskKeyExchangeModes => "psk-key-exchange-modes",
+            ExtensionTypes::KeyShare => "key-share",
+            ExtensionTypes::QuicTransportParameters
+            | ExtensionTypes::QuicTransportParametersDraft => "quic-transport-parameters",
+            _ => "unknown",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quic::encode_varint;
+    use crate::quic::frame::encode_crypto;
+
+    fn extension(ty: u16, data: &[u8], buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&ty.to_be_bytes());
+        buf.extend_from_slice(&(data.len() as u16).to_be_bytes());
+        buf.extend_from_slice(data);
+    }
+
+    fn parameter(id: u64, value: &[u8], buf: &mut Vec<u8>) {
+        encode_varint(id, buf);
+        encode_varint(value.len() as u64, buf);
+        buf.extend_from_slice(value);
+    }
+
+    fn client_hello() -> Vec<u8> {
+        let mut extensions = vec![];
+        let name = b"example.com";
+        let mut sni = vec![];
+        sni.extend_from_slice(&(name.len() as u16 + 3).to_be_bytes());
+        sni.push(0);
+        sni.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        sni.extend_from_slice(name);
+        extension(0, &sni, &mut extensions);
+        let alpn = b"\x06libp2p\x02h3";
+        let mut data = (alpn.len() as u16).to_be_bytes().to_vec();
+        data.extend_from_slice(alpn);
+        extension(16, &data, &mut extensions);
+        let mut params = vec![];
+        parameter(0x01, &[0x80, 0x00, 0x75, 0x30], &mut params);
+        parameter(0x04, &[0x80, 0x10, 0x00, 0x00], &mut params);
+        parameter(0x0c, &[], &mut params);
+        parameter(0x0e, &[0x08], &mut params);
+        parameter(0x0f, &[1, 2, 3, 4], &mut params);
+        parameter(0x20, &[0x44, 0xb0], &mut params);
+        extension(57, &params, &mut extensions);
+
+        let mut body = vec![0x03, 0x03];
+        body.extend_from_slice(&[0x11; 32]);
+        body.push(0);
+        body.extend_from_slice(&[0x00, 0x02, 0x13, 0x01]);
+        body.extend_from_slice(&[0x01, 0x00]);
+        body.extend_from_slice(&(extensions.len() as u16).to_be_bytes());
+        body.extend_from_slice(&extensions);
+        let mut message = vec![1];
+        message.extend_from_slice(&(body.len() as u32).to_be_bytes()[1..]);
+        message.extend_from_slice(&body);
+        message
+    }
+
+    #[test]
+    fn reassembled_client_hello() {
+        let hello = client_hello();
+        let (head, tail) = hello.split_at(40);
+        let mut frames = vec![];
+        encode_crypto(&mut frames, 40, tail);
+        encode_crypto(&mut frames, 0, &head[..20]);
+        let mut stream = CryptoStream::new();
+        stream.insert_frames(&Frame::new(&frames).unwrap());
+        assert!(stream.client_hello().is_none());
+        stream.insert(10, &head[10..]);
+        assert_eq!(stream.data(), &hello[..]);
+
+        let client_hello = stream.client_hello().unwrap();
+        assert_eq!(client_hello.get_cipher_suites_raw(), &[0x13, 0x01]);
+        let extensions = client_hello.extensions().unwrap();
+        assert_eq!(extensions.server_name(), Some("example.com"));
+        assert_eq!(extensions.alpn(), Some(vec![&b"libp2p"[..], &b"h3"[..]]));
+        let params = extensions.transport_parameters().unwrap();
+        assert_eq!(params.max_idle_timeout, Some(30000));
+        assert_eq!(params.initial_max_data, Some(0x100000));
+        assert!(params.disable_active_migration);
+        assert_eq!(params.active_connection_id_limit, Some(8));
+        assert_eq!(params.initial_source_connection_id, Some(&[1, 2, 3, 4][..]));
+        assert_eq!(params.max_udp_payload_size, None);
+        assert_eq!(params.unknown, vec![(0x20, &[0x44, 0xb0][..])]);
+        assert!(stream.server_hello().is_none());
+    }
+
+    #[test]
+    fn rfc9001_server_hello() {
+        let crypto = [
+            0x02, 0x00, 0x00, 0x56, 0x03, 0x03, 0xee, 0xfc, 0xe7, 0xf7, 0xb3, 0x7b, 0xa1, 0xd1,
+            0x63, 0x2e, 0x96, 0x67, 0x78, 0x25, 0xdd, 0xf7, 0x39, 0x88, 0xcf, 0xc7, 0x98, 0x25,
+            0xdf, 0x56, 0x6d, 0xc5, 0x43, 0x0b, 0x9a, 0x04, 0x5a, 0x12, 0x00, 0x13, 0x01, 0x00,
+            0x00, 0x2e, 0x00, 0x33, 0x00, 0x24, 0x00, 0x1d, 0x00, 0x20, 0x9d, 0x3c, 0x94, 0x0d,
+            0x89, 0x69, 0x0b, 0x84, 0xd0, 0x8a, 0x60, 0x99, 0x3c, 0x14, 0x4e, 0xca, 0x68, 0x4d,
+            0x10, 0x81, 0x28, 0x7c, 0x83, 0x4d, 0x53, 0x11, 0xbc, 0xf3, 0x2b, 0xb9, 0xda, 0x1a,
+            0x00, 0x2b, 0x00, 0x02, 0x03, 0x04,
+        ];
+        let mut stream = CryptoStream::new();
+        stream.insert(0, &crypto);
+        let server_hello = stream.server_hello().unwrap();
+        assert_eq!(server_hello.get_cipher_suite(), 0x1301);
+        let extensions: Vec<_> = server_hello
+            .extensions()
+            .unwrap()
+            .map(|e| e.get_ty())
+            .collect();
+        assert_eq!(
+            extensions,
+            vec![ExtensionTypes::KeyShare, ExtensionTypes::SupportedVersions]
+        );
+        let extensions = server_hello.extensions().unwrap();
+        assert_eq!(
+            extensions.get(ExtensionTypes::SupportedVersions),
+            Some(&[0x03, 0x04][..])
+        );
+        assert!(extensions.transport_parameters().is_none());
+    }
+
+    #[test]
+    fn truncated_hello() {
+        let hello = client_hello();
+        let body_len = hello.len() - 4;
+        for len in [0, 12, 35, 38, 41, body_len - 1] {
+            let mut message = vec![1];
+            message.extend_from_slice(&(len as u32).to_be_bytes()[1..]);
+            message.extend_from_slice(&hello[4..4 + len]);
+            let mut stream = CryptoStream::new();
+            stream.insert(0, &message);
+            assert!(stream.client_hello().is_none(), "{}", len);
+            message[0] = 2;
+            let mut stream = CryptoStream::new();
+            stream.insert(0, &message);
+            assert!(stream.server_hello().is_none(), "{}", len);
+        }
+    }
+}
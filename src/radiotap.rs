@@ -0,0 +1,378 @@
+//! A radiotap header abstraction, carrying the radio information of captured 802.11 frames.
+
+use crate::{types::*, Packet};
+use std::convert::TryInto;
+
+/// The bit of the presence bitmap indicating that another bitmap follows.
+const EXT: u32 = 31;
+/// The bit switching the next bitmap to the radiotap namespace.
+const RADIOTAP_NAMESPACE: u32 = 29;
+/// The bit switching the next bitmap to a vendor namespace.
+const VENDOR_NAMESPACE: u32 = 30;
+
+/// The flags field indicates that the frame includes the FCS.
+pub const FLAG_FCS: u8 = 0x10;
+
+/// A radiotap header followed by an 802.11 frame. The fields, whose presence is given by the
+/// bitmaps starting with `present`, are little-endian and naturally aligned.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct Radiotap {
+    pub version: u8,
+    pub pad: u8,
+    pub length: u16le,
+    pub present: u32le,
+    #[length = "length.saturating_sub(8)"]
+    pub data: Vec<u8>,
+    #[payload]
+    pub payload: Vec<u8>,
+}
+
+/// A radiotap field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RadiotapField<'a> {
+    /// The MAC timestamp of the first bit of the frame, in microseconds.
+    Tsft(u64),
+    Flags(u8),
+    /// The legacy data rate, in units of 500 kbps.
+    Rate(u8),
+    /// The channel frequency in MHz, and the channel flags.
+    Channel {
+        frequency: u16,
+        flags: u16,
+    },
+    /// The antenna signal power, in dBm.
+    AntennaSignal(i8),
+    /// The antenna noise power, in dBm.
+    AntennaNoise(i8),
+    /// The antenna index, which applies to the signal and noise fields of the same namespace.
+    Antenna(u8),
+    /// The 802.11n MCS information.
+    Mcs {
+        known: u8,
+        flags: u8,
+        mcs: u8,
+    },
+    /// The 802.11ac VHT information, with the MCS and number of spatial streams of each user.
+    Vht {
+        known: u16,
+        flags: u8,
+        bandwidth: u8,
+        mcs_nss: [u8; 4],
+        coding: u8,
+        group_id: u8,
+        partial_aid: u16,
+    },
+    /// Any other field of the radiotap namespace, by its bit number.
+    Other {
+        bit: u8,
+        data: &'a [u8],
+    },
+}
+
+/// The alignment and size of the fields of the radiotap namespace. Fields without defined
+/// layout end the iteration.
+fn field_layout(bit: u32) -> Option<(usize, usize)> {
+    Some(match bit {
+        0 => (8, 8),
+        1 | 2 => (1, 1),
+        3 => (2, 4),
+        4 => (1, 2),
+        5 | 6 => (1, 1),
+        7..=9 => (2, 2),
+        10..=13 => (1, 1),
+        14 | 15 => (2, 2),
+        16 | 17 => (1, 1),
+        18 => (4, 8),
+        19 => (1, 3),
+        20 => (4, 8),
+        21 => (2, 12),
+        22 => (8, 12),
+        23 | 24 => (2, 12),
+        25 => (2, 6),
+        26 => (1, 1),
+        27 => (2, 4),
+        _ => return None,
+    })
+}
+
+impl<'a> RadiotapField<'a> {
+    fn decode(bit: u32, data: &'a [u8]) -> Self {
+        let u16_at = |i: usize| u16::from_le_bytes([data[i], data[i + 1]]);
+        match bit {
+            0 => Self::Tsft(u64::from_le_bytes(data.try_into().unwrap())),
+            1 => Self::Flags(data[0]),
+            2 => Self::Rate(data[0]),
+            3 => Self::Channel {
+                frequency: u16_at(0),
+                flags: u16_at(2),
+            },
+            5 => Self::AntennaSignal(data[0] as i8),
+            6 => Self::AntennaNoise(data[0] as i8),
+            11 => Self::Antenna(data[0]),
+            19 => Self::Mcs {
+                known: data[0],
+                flags: data[1],
+                mcs: data[2],
+            },
+            21 => Self::Vht {
+                known: u16_at(0),
+                flags: data[2],
+                bandwidth: data[3],
+                mcs_nss: data[4..8].try_into().unwrap(),
+                coding: data[8],
+                group_id: data[9],
+                partial_aid: u16_at(10),
+            },
+            bit => Self::Other {
+                bit: bit as u8,
+                data,
+            },
+        }
+    }
+}
+
+/// Iterator over the fields of a radiotap header, in the order of their presence bits. Fields of
+/// vendor namespaces are skipped. Ends at the first field that is truncated or whose layout is
+/// unknown.
+#[derive(Clone, Debug)]
+pub struct RadiotapFields<'a> {
+    /// The header, up to its length.
+    header: &'a [u8],
+    /// The offset of the current presence bitmap.
+    bitmap: usize,
+    /// The current presence bitmap.
+    present: u32,
+    /// The presence bits of the current bitmap left to visit.
+    bits: u32,
+    /// The bit number of bit 0 of the current bitmap, within its namespace.
+    base: u32,
+    vendor: bool,
+    /// The length of the data of the next vendor namespace.
+    skip_length: usize,
+    /// The offset of the next field.
+    offset: usize,
+}
+
+impl<'a> RadiotapFields<'a> {
+    fn new(header: &'a [u8]) -> Self {
+        let read = |i: usize| -> Option<u32> {
+            Some(u32::from_le_bytes(
+                header.get(i..i + 4)?.try_into().unwrap(),
+            ))
+        };
+        let mut offset = 4;
+        while read(offset).is_some_and(|present| present & 1 << EXT != 0) {
+            offset += 4;
+        }
+        let present = read(4).unwrap_or(0);
+        Self {
+            header,
+            bitmap: 4,
+            present,
+            bits: present,
+            base: 0,
+            vendor: false,
+            skip_length: 0,
+            offset: offset + 4,
+        }
+    }
+
+    /// Moves to the next presence bitmap, returning `false` if there is none.
+    fn next_bitmap(&mut self) -> bool {
+        if self.present & 1 << EXT == 0 {
+            return false;
+        }
+        let switched = self.present & (1 << RADIOTAP_NAMESPACE | 1 << VENDOR_NAMESPACE) != 0;
+        if switched {
+            self.vendor = self.present & 1 << VENDOR_NAMESPACE != 0;
+            self.base = 0;
+        } else {
+            self.base += 32;
+        }
+        self.bitmap += 4;
+        self.present = match self.header.get(self.bitmap..self.bitmap + 4) {
+            Some(bytes) => u32::from_le_bytes(bytes.try_into().unwrap()),
+            None => return false,
+        };
+        self.bits = self.present;
+        if self.vendor && switched {
+            self.offset += self.skip_length;
+        }
+        true
+    }
+}
+
+impl<'a> Iterator for RadiotapFields<'a> {
+    type Item = RadiotapField<'a>;
+
+    fn next(&mut self) -> Option<RadiotapField<'a>> {
+        loop {
+            if self.bits == 0 && !self.next_bitmap() {
+                return None;
+            }
+            if self.bits == 0 {
+                continue;
+            }
+            let bit = self.bits.trailing_zeros();
+            self.bits &= !(1 << bit);
+            let layout = match bit {
+                EXT | RADIOTAP_NAMESPACE => continue,
+                VENDOR_NAMESPACE => Some((2, 6)),
+                _ if self.vendor => continue,
+                _ => field_layout(self.base + bit),
+            };
+            let field = layout.and_then(|(align, size)| {
+                let start = self.offset.div_ceil(align) * align;
+                Some((start + size, self.header.get(start..start + size)?))
+            });
+            let data = match field {
+                Some((end, data)) => {
+                    self.offset = end;
+                    data
+                }
+                None => {
+                    self.bits = 0;
+                    self.present = 0;
+                    return None;
+                }
+            };
+            if bit == VENDOR_NAMESPACE {
+                self.skip_length = u16::from_le_bytes([data[4], data[5]]) as usize;
+                continue;
+            }
+            return Some(RadiotapField::decode(self.base + bit, data));
+        }
+    }
+}
+
+impl<'p> RadiotapPacket<'p> {
+    /// Iterates over the fields of the header.
+    pub fn fields(&self) -> RadiotapFields<'_> {
+        let length = (self.get_length() as usize).min(self.packet().len());
+        RadiotapFields::new(&self.packet()[..length])
+    }
+
+    /// The 802.11 frame, without the FCS if the flags field says it is included.
+    pub fn frame(&self) -> &[u8] {
+        let payload = self.payload();
+        let fcs = self
+            .fields()
+            .any(|field| matches!(field, RadiotapField::Flags(flags) if flags & FLAG_FCS != 0));
+        if fcs {
+            &payload[..payload.len().saturating_sub(4)]
+        } else {
+            payload
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields() {
+        let header = [
+            0x00, 0x00, 0x2c, 0x00, // version, pad, length
+            0x2f, 0x40, 0x00, 0xa0, // TSFT, flags, rate, channel, signal, RX flags, ext
+            0x20, 0x08, 0x00, 0xa0, // signal, antenna, radiotap namespace, ext
+            0x20, 0x08, 0x00, 0x00, // signal, antenna
+            0x10, 0x32, 0x54, 0x76, 0x00, 0x00, 0x00, 0x00, // TSFT
+            0x10, 0x0c, // flags, rate
+            0x6c, 0x09, 0xa0, 0x00, // channel
+            0xbd, // signal
+            0x00, // padding to 2 bytes
+            0x00, 0x00, // RX flags
+            0xbc, 0x00, // signal, antenna 0
+            0xbe, 0x01, // signal, antenna 1
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // trailing header bytes
+            0x80, 0x00, // frame
+            0x01, 0x02, 0x03, 0x04, // FCS
+        ];
+        let radiotap = RadiotapPacket::new(&header).unwrap();
+        let fields: Vec<_> = radiotap.fields().collect();
+        assert_eq!(
+            fields,
+            [
+                RadiotapField::Tsft(0x76543210),
+                RadiotapField::Flags(FLAG_FCS),
+                RadiotapField::Rate(12),
+                RadiotapField::Channel {
+                    frequency: 2412,
+                    flags: 0xa0
+                },
+                RadiotapField::AntennaSignal(-67),
+                RadiotapField::Other {
+                    bit: 14,
+                    data: &[0, 0]
+                },
+                RadiotapField::AntennaSignal(-68),
+                RadiotapField::Antenna(0),
+                RadiotapField::AntennaSignal(-66),
+                RadiotapField::Antenna(1),
+            ]
+        );
+        assert_eq!(radiotap.frame(), &[0x80, 0x00]);
+    }
+
+    #[test]
+    fn mcs_and_vht() {
+        let header = [
+            0x00, 0x00, 0x1c, 0x00, // version, pad, length
+            0x00, 0x00, 0x28, 0x00, // MCS, VHT
+            0x07, 0x00, 0x07, // MCS
+            0x00, // padding to 2 bytes
+            0x44, 0x00, 0x04, 0x04, 0x92, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // VHT
+            0x00, 0x00, 0x00, 0x00, // trailing header bytes
+        ];
+        let radiotap = RadiotapPacket::new(&header).unwrap();
+        let fields: Vec<_> = radiotap.fields().collect();
+        assert_eq!(
+            fields,
+            [
+                RadiotapField::Mcs {
+                    known: 0x07,
+                    flags: 0x00,
+                    mcs: 7
+                },
+                RadiotapField::Vht {
+                    known: 0x44,
+                    flags: 0x04,
+                    bandwidth: 4,
+                    mcs_nss: [0x92, 0, 0, 0],
+                    coding: 0,
+                    group_id: 0,
+                    partial_aid: 0,
+                },
+            ]
+        );
+        assert!(radiotap.frame().is_empty());
+    }
+
+    #[test]
+    fn vendor_namespace_and_truncation() {
+        let header = [
+            0x00, 0x00, 0x18, 0x00, // version, pad, length
+            0x02, 0x00, 0x00, 0xc0, // flags, vendor namespace, ext
+            0x04, 0x00, 0x00, 0x00, // vendor bitmap
+            0x00, // flags
+            0x00, // padding to 2 bytes
+            0x00, 0x11, 0x22, 0x01, 0x03, 0x00, // vendor namespace, 3 bytes
+            0xaa, 0xbb, 0xcc, // vendor data
+            0x00,
+        ];
+        let radiotap = RadiotapPacket::new(&header).unwrap();
+        assert_eq!(
+            radiotap.fields().collect::<Vec<_>>(),
+            [RadiotapField::Flags(0)]
+        );
+
+        let truncated = [0x00, 0x00, 0x09, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00];
+        let radiotap = RadiotapPacket::new(&truncated).unwrap();
+        assert_eq!(radiotap.fields().count(), 0);
+
+        let unknown = [0x00, 0x00, 0x0a, 0x00, 0x06, 0x00, 0x00, 0x10, 0x00, 0x00];
+        let radiotap = RadiotapPacket::new(&unknown).unwrap();
+        assert_eq!(radiotap.fields().count(), 2);
+    }
+}
@@ -0,0 +1,65 @@
+//! A raw IP (`LINKTYPE_RAW`, `LINKTYPE_IPV4` and `LINKTYPE_IPV6`) abstraction, for captures
+//! without link-layer header.
+
+use crate::ethernet::{EtherType, EtherTypes};
+use crate::ipv4::Ipv4Packet;
+use crate::ipv6::Ipv6Packet;
+use crate::Packet;
+
+/// An IP packet, whose version is given by its first nibble.
+#[derive(Debug)]
+pub enum RawIp<'a> {
+    Ipv4(Ipv4Packet<'a>),
+    Ipv6(Ipv6Packet<'a>),
+}
+
+impl<'a> RawIp<'a> {
+    /// Decodes `data` according to its version. Returns `None` for other versions or if it is
+    /// too short for its header.
+    pub fn new(data: &'a [u8]) -> Option<Self> {
+        match data.first()? >> 4 {
+            4 => Some(Self::Ipv4(Ipv4Packet::new(data)?)),
+            6 => Some(Self::Ipv6(Ipv6Packet::new(data)?)),
+            _ => None,
+        }
+    }
+
+    pub fn ethertype(&self) -> EtherType {
+        match self {
+            Self::Ipv4(_) => EtherTypes::Ipv4,
+            Self::Ipv6(_) => EtherTypes::Ipv6,
+        }
+    }
+
+    /// The whole packet.
+    pub fn packet(&self) -> &[u8] {
+        match self {
+            Self::Ipv4(packet) => packet.packet(),
+            Self::Ipv6(packet) => packet.packet(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version() {
+        let mut ipv6 = vec![0x60, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3b, 0x40];
+        ipv6.extend_from_slice(&[0; 32]);
+        let ip = RawIp::new(&ipv6).unwrap();
+        assert_eq!(ip.ethertype(), EtherTypes::Ipv6);
+        assert!(matches!(ip, RawIp::Ipv6(_)));
+        assert_eq!(ip.packet().len(), 40);
+
+        let ipv4 = [
+            0x45, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x00, 0x40, 0x01, 0x00, 0x00, 0x7f, 0x00,
+            0x00, 0x01, 0x7f, 0x00, 0x00, 0x01,
+        ];
+        assert!(matches!(RawIp::new(&ipv4), Some(RawIp::Ipv4(_))));
+        assert!(RawIp::new(&ipv6[..20]).is_none());
+        assert!(RawIp::new(&[0x50; 40]).is_none());
+        assert!(RawIp::new(&[]).is_none());
+    }
+}
@@ -0,0 +1,192 @@
+//! A Linux cooked capture (`LINKTYPE_LINUX_SLL`) header abstraction, as written by
+//! `tcpdump -i any`.
+
+use crate::arp::ArpHardwareType;
+use crate::ethernet::EtherType;
+use crate::{types::*, MacAddr, Packet, PrimitiveValues};
+
+/// How a captured packet was addressed relative to the capturing host.
+#[derive(Hash, Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone)]
+pub struct SllPacketType(pub u16);
+
+impl SllPacketType {
+    /// Construct a new `SllPacketType`.
+    pub fn new(value: u16) -> Self {
+        Self(value)
+    }
+}
+
+impl PrimitiveValues for SllPacketType {
+    type T = (u16,);
+    fn to_primitive_values(&self) -> (u16,) {
+        (self.0,)
+    }
+}
+
+impl std::fmt::Display for SllPacketType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match *self {
+                SllPacketTypes::Host => "Host",
+                SllPacketTypes::Broadcast => "Broadcast",
+                SllPacketTypes::Multicast => "Multicast",
+                SllPacketTypes::OtherHost => "OtherHost",
+                SllPacketTypes::Outgoing => "Outgoing",
+                _ => "unknown",
+            }
+        )
+    }
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod SllPacketTypes {
+    use super::SllPacketType;
+
+    /// Sent to the capturing host.
+    pub const Host: SllPacketType = SllPacketType(0);
+    /// Broadcast by another host.
+    pub const Broadcast: SllPacketType = SllPacketType(1);
+    /// Multicast by another host.
+    pub const Multicast: SllPacketType = SllPacketType(2);
+    /// Sent by another host to another host.
+    pub const OtherHost: SllPacketType = SllPacketType(3);
+    /// Sent by the capturing host.
+    pub const Outgoing: SllPacketType = SllPacketType(4);
+}
+
+/// Linux `ARPHRD_*` device types that differ from the ARP hardware types, for the
+/// `arphrd_type` field.
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod ArphrdTypes {
+    use crate::arp::ArpHardwareType;
+
+    pub const Can: ArpHardwareType = ArpHardwareType(280);
+    pub const Ppp: ArpHardwareType = ArpHardwareType(512);
+    /// IP in IP tunnel.
+    pub const Tunnel: ArpHardwareType = ArpHardwareType(768);
+    pub const Loopback: ArpHardwareType = ArpHardwareType(772);
+    /// IPv6 in IPv4 tunnel.
+    pub const Sit: ArpHardwareType = ArpHardwareType(776);
+    pub const IpGre: ArpHardwareType = ArpHardwareType(778);
+    pub const Ieee80211Radiotap: ArpHardwareType = ArpHardwareType(803);
+    pub const Netlink: ArpHardwareType = ArpHardwareType(824);
+    /// `ARPHRD_NONE`, a device without link-layer header such as a WireGuard tunnel.
+    pub const NoHeader: ArpHardwareType = ArpHardwareType(0xfffe);
+}
+
+/// Linux `ETH_P_*` values below 1536 that stand in for the protocol of frames without an
+/// ethertype.
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod SllProtocols {
+    use crate::ethernet::EtherType;
+
+    /// Novell raw IEEE 802.3 frame carrying IPX.
+    pub const Novell802_3: EtherType = EtherType(0x0001);
+    /// IEEE 802.2 LLC frame, see `llc::LlcPacket`.
+    pub const Llc: EtherType = EtherType(0x0004);
+    pub const Can: EtherType = EtherType(0x000c);
+    pub const CanFd: EtherType = EtherType(0x000d);
+}
+
+/// A Linux cooked capture header. The protocol is an ethertype, or one of `SllProtocols`.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct Sll {
+    #[construct_with(u16be)]
+    pub packet_type: SllPacketType,
+    #[construct_with(u16be)]
+    pub arphrd_type: ArpHardwareType,
+    pub address_length: u16be,
+    // the link-layer source address, padded to 8 bytes; see `source_address`
+    pub address: u64be,
+    #[construct_with(u16be)]
+    pub protocol: EtherType,
+    #[payload]
+    pub payload: Vec<u8>,
+}
+
+/// The first `len` bytes of the 8 byte address field, and the address as a MAC if `len` is 6.
+pub(crate) fn link_address(field: &[u8], len: usize) -> (&[u8], Option<MacAddr>) {
+    let address = &field[..len.min(field.len())];
+    let mac = match *address {
+        [a, b, c, d, e, f] => Some(MacAddr(a, b, c, d, e, f)),
+        _ => None,
+    };
+    (address, mac)
+}
+
+impl<'p> SllPacket<'p> {
+    /// The used part of the link-layer source address.
+    pub fn source_address(&self) -> &[u8] {
+        let field = self.packet().get(6..14).unwrap_or(&[]);
+        link_address(field, self.get_address_length() as usize).0
+    }
+
+    /// The link-layer source address if it is a MAC address.
+    pub fn source_mac(&self) -> Option<MacAddr> {
+        let field = self.packet().get(6..14).unwrap_or(&[]);
+        link_address(field, self.get_address_length() as usize).1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arp::{ArpHardwareTypes, ArpOperations, ArpPacket};
+    use crate::ethernet::EtherTypes;
+    use crate::ipv4::Ipv4Packet;
+
+    #[test]
+    fn ipv4() {
+        let mut packet = vec![
+            0x00, 0x04, 0x00, 0x01, 0x00, 0x06, 0x02, 0x42, 0xac, 0x11, 0x00, 0x02, 0x00, 0x00,
+            0x08, 0x00,
+        ];
+        packet.extend_from_slice(&[
+            0x45, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x00, 0x40, 0x11, 0x00, 0x00, 0x0a, 0x00,
+            0x00, 0x01, 0x0a, 0x00, 0x00, 0x02,
+        ]);
+        let sll = SllPacket::new(&packet).unwrap();
+        assert_eq!(sll.get_packet_type(), SllPacketTypes::Outgoing);
+        assert_eq!(sll.get_arphrd_type(), ArpHardwareTypes::Ethernet);
+        assert_eq!(
+            sll.source_mac(),
+            Some(MacAddr(0x02, 0x42, 0xac, 0x11, 0x00, 0x02))
+        );
+        assert_eq!(sll.get_protocol(), EtherTypes::Ipv4);
+        let ip = Ipv4Packet::new(sll.payload()).unwrap();
+        assert_eq!(ip.get_ttl(), 0x40);
+    }
+
+    #[test]
+    fn loopback_arp() {
+        let mut packet = vec![
+            0x00, 0x00, 0x03, 0x04, 0x00, 0x00, 0, 0, 0, 0, 0, 0, 0, 0, 0x08, 0x06,
+        ];
+        packet.extend_from_slice(&[
+            0x00, 0x01, 0x08, 0x00, 0x06, 0x04, 0x00, 0x01, 0, 0, 0, 0, 0, 0, 127, 0, 0, 1, 0, 0,
+            0, 0, 0, 0, 127, 0, 0, 2,
+        ]);
+        let sll = SllPacket::new(&packet).unwrap();
+        assert_eq!(sll.get_arphrd_type(), ArphrdTypes::Loopback);
+        assert!(sll.source_address().is_empty());
+        assert_eq!(sll.source_mac(), None);
+        assert_eq!(sll.get_protocol(), EtherTypes::Arp);
+        let arp = ArpPacket::new(sll.payload()).unwrap();
+        assert_eq!(arp.get_operation(), ArpOperations::Request);
+    }
+
+    #[test]
+    fn truncated_header() {
+        assert_eq!(SllPacket::minimum_packet_size(), 16);
+        assert!(SllPacket::new(&[0; 12]).is_none());
+        assert!(SllPacket::new(&[0; 15]).is_none());
+        let sll = SllPacket::new(&[0; 16]).unwrap();
+        assert!(sll.source_address().is_empty());
+        assert!(sll.payload().is_empty());
+    }
+}
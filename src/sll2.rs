@@ -0,0 +1,84 @@
+//! A Linux cooked capture v2 (`LINKTYPE_LINUX_SLL2`) header abstraction, which adds the
+//! interface index to `sll`.
+
+use crate::arp::ArpHardwareType;
+use crate::ethernet::EtherType;
+use crate::sll::{link_address, SllPacketType};
+use crate::{types::*, MacAddr, Packet};
+
+/// A Linux cooked capture v2 header. The protocol is an ethertype, or one of
+/// `sll::SllProtocols`.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct Sll2 {
+    #[construct_with(u16be)]
+    pub protocol: EtherType,
+    pub reserved: u16be,
+    pub interface_index: u32be,
+    #[construct_with(u16be)]
+    pub arphrd_type: ArpHardwareType,
+    pub packet_type: u8,
+    pub address_length: u8,
+    // the link-layer source address, padded to 8 bytes; see `source_address`
+    pub address: u64be,
+    #[payload]
+    pub payload: Vec<u8>,
+}
+
+impl<'p> Sll2Packet<'p> {
+    /// The packet type, which is a single byte in v2 headers.
+    pub fn sll_packet_type(&self) -> SllPacketType {
+        SllPacketType(self.get_packet_type() as u16)
+    }
+
+    /// The used part of the link-layer source address.
+    pub fn source_address(&self) -> &[u8] {
+        let field = self.packet().get(12..20).unwrap_or(&[]);
+        link_address(field, self.get_address_length() as usize).0
+    }
+
+    /// The link-layer source address if it is a MAC address.
+    pub fn source_mac(&self) -> Option<MacAddr> {
+        let field = self.packet().get(12..20).unwrap_or(&[]);
+        link_address(field, self.get_address_length() as usize).1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arp::ArpHardwareTypes;
+    use crate::ethernet::EtherTypes;
+    use crate::ipv6::Ipv6Packet;
+    use crate::sll::SllPacketTypes;
+
+    #[test]
+    fn ipv6() {
+        let mut packet = vec![
+            0x86, 0xdd, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x01, 0x02, 0x06, 0x02, 0x42,
+            0xac, 0x11, 0x00, 0x02, 0x00, 0x00,
+        ];
+        packet.extend_from_slice(&[0x60, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3b, 0x40]);
+        packet.extend_from_slice(&[0; 32]);
+        let sll = Sll2Packet::new(&packet).unwrap();
+        assert_eq!(sll.get_protocol(), EtherTypes::Ipv6);
+        assert_eq!(sll.get_interface_index(), 3);
+        assert_eq!(sll.get_arphrd_type(), ArpHardwareTypes::Ethernet);
+        assert_eq!(sll.sll_packet_type(), SllPacketTypes::Multicast);
+        assert_eq!(sll.source_address(), &[0x02, 0x42, 0xac, 0x11, 0x00, 0x02]);
+        assert!(sll.source_mac().is_some());
+        let ip = Ipv6Packet::new(sll.payload()).unwrap();
+        assert_eq!(ip.get_hop_limit(), 0x40);
+    }
+
+    #[test]
+    fn truncated_header() {
+        assert_eq!(Sll2Packet::minimum_packet_size(), 20);
+        assert!(Sll2Packet::new(&[0; 12]).is_none());
+        assert!(Sll2Packet::new(&[0; 19]).is_none());
+        let mut packet = [0; 20];
+        packet[11] = 6;
+        let sll = Sll2Packet::new(&packet).unwrap();
+        assert_eq!(sll.source_mac(), Some(MacAddr(0, 0, 0, 0, 0, 0)));
+        assert!(sll.payload().is_empty());
+    }
+}
@@ -0,0 +1,534 @@
+//! A Spanning Tree Protocol BPDU abstraction (IEEE 802.1D and 802.1Q), covering STP, RSTP and
+//! MSTP. BPDUs are carried in IEEE 802.3 frames with `LlcSaps::Stp` addresses.
+
+use crate::llc::{LlcPacket, LlcSaps};
+use crate::{types::*, MacAddr, Packet, PrimitiveValues};
+
+/// The destination address of BPDUs.
+pub const STP_MULTICAST: MacAddr = MacAddr(0x01, 0x80, 0xc2, 0x00, 0x00, 0x00);
+
+/// The protocol version of STP BPDUs.
+pub const STP_VERSION: u8 = 0;
+/// The protocol version of RSTP BPDUs.
+pub const RSTP_VERSION: u8 = 2;
+/// The protocol version of MSTP BPDUs.
+pub const MSTP_VERSION: u8 = 3;
+
+/// The type of a BPDU.
+#[derive(Hash, Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone)]
+pub struct BpduType(pub u8);
+
+impl BpduType {
+    /// Construct a new `BpduType`.
+    pub fn new(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+impl PrimitiveValues for BpduType {
+    type T = (u8,);
+    fn to_primitive_values(&self) -> (u8,) {
+        (self.0,)
+    }
+}
+
+impl std::fmt::Display for BpduType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match *self {
+                BpduTypes::Config => "Config",
+                BpduTypes::Rst => "Rst",
+                BpduTypes::Tcn => "Tcn",
+                _ => "unknown",
+            }
+        )
+    }
+}
+
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+pub mod BpduTypes {
+    use super::BpduType;
+
+    /// Configuration BPDU.
+    pub const Config: BpduType = BpduType(0x00);
+    /// Rapid Spanning Tree BPDU, also used by MSTP.
+    pub const Rst: BpduType = BpduType(0x02);
+    /// Topology Change Notification BPDU.
+    pub const Tcn: BpduType = BpduType(0x80);
+}
+
+/// The role of the port sending a BPDU, from bits 2 and 3 of the flags.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PortRole {
+    /// Unknown in RST BPDUs, master in MSTI configuration messages.
+    Master,
+    AlternateOrBackup,
+    Root,
+    Designated,
+}
+
+/// The flags of a BPDU or an MSTI configuration message. Configuration BPDUs only use the
+/// topology change and topology change acknowledgment flags.
+#[derive(Hash, Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone)]
+pub struct BpduFlags(pub u8);
+
+impl BpduFlags {
+    /// Construct a new `BpduFlags`.
+    pub fn new(value: u8) -> Self {
+        Self(value)
+    }
+
+    pub fn topology_change(&self) -> bool {
+        self.0 & 0x01 != 0
+    }
+
+    pub fn proposal(&self) -> bool {
+        self.0 & 0x02 != 0
+    }
+
+    pub fn port_role(&self) -> PortRole {
+        match (self.0 >> 2) & 0x03 {
+            0 => PortRole::Master,
+            1 => PortRole::AlternateOrBackup,
+            2 => PortRole::Root,
+            _ => PortRole::Designated,
+        }
+    }
+
+    pub fn learning(&self) -> bool {
+        self.0 & 0x10 != 0
+    }
+
+    pub fn forwarding(&self) -> bool {
+        self.0 & 0x20 != 0
+    }
+
+    pub fn agreement(&self) -> bool {
+        self.0 & 0x40 != 0
+    }
+
+    /// The topology change acknowledgment flag. MSTI configuration messages use this bit as the
+    /// master flag.
+    pub fn topology_change_ack(&self) -> bool {
+        self.0 & 0x80 != 0
+    }
+}
+
+impl PrimitiveValues for BpduFlags {
+    type T = (u8,);
+    fn to_primitive_values(&self) -> (u8,) {
+        (self.0,)
+    }
+}
+
+/// A bridge identifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BridgeId {
+    /// The priority, in steps of 4096.
+    pub priority: u4,
+    /// The VLAN or MST instance the identifier belongs to.
+    pub system_id_extension: u12be,
+    pub mac: MacAddr,
+}
+
+impl BridgeId {
+    /// The priority and system ID extension as the 16 bit bridge priority.
+    pub fn bridge_priority(&self) -> u16 {
+        (self.priority as u16) << 12 | self.system_id_extension
+    }
+}
+
+impl std::fmt::Display for BridgeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}/{}", self.bridge_priority(), self.mac)
+    }
+}
+
+/// The common header of all BPDUs.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct BpduHeader {
+    pub protocol_id: u16be,
+    pub version: u8,
+    #[construct_with(u8)]
+    pub bpdu_type: BpduType,
+    #[payload]
+    pub payload: Vec<u8>,
+}
+
+/// A Topology Change Notification BPDU.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct TcnBpdu {
+    pub protocol_id: u16be,
+    pub version: u8,
+    #[construct_with(u8)]
+    pub bpdu_type: BpduType,
+    #[payload]
+    pub payload: Vec<u8>,
+}
+
+/// A Configuration BPDU. Times are in units of 1/256 seconds.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct ConfigBpdu {
+    pub protocol_id: u16be,
+    pub version: u8,
+    #[construct_with(u8)]
+    pub bpdu_type: BpduType,
+    #[construct_with(u8)]
+    pub flags: BpduFlags,
+    pub root_priority: u4,
+    pub root_system_id_extension: u12be,
+    #[construct_with(u8, u8, u8, u8, u8, u8)]
+    pub root_mac: MacAddr,
+    pub root_path_cost: u32be,
+    pub bridge_priority: u4,
+    pub bridge_system_id_extension: u12be,
+    #[construct_with(u8, u8, u8, u8, u8, u8)]
+    pub bridge_mac: MacAddr,
+    pub port_priority: u4,
+    pub port_number: u12be,
+    pub message_age: u16be,
+    pub max_age: u16be,
+    pub hello_time: u16be,
+    pub forward_delay: u16be,
+    #[payload]
+    pub payload: Vec<u8>,
+}
+
+/// A Rapid Spanning Tree BPDU. Times are in units of 1/256 seconds.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct RstBpdu {
+    pub protocol_id: u16be,
+    pub version: u8,
+    #[construct_with(u8)]
+    pub bpdu_type: BpduType,
+    #[construct_with(u8)]
+    pub flags: BpduFlags,
+    pub root_priority: u4,
+    pub root_system_id_extension: u12be,
+    #[construct_with(u8, u8, u8, u8, u8, u8)]
+    pub root_mac: MacAddr,
+    pub root_path_cost: u32be,
+    pub bridge_priority: u4,
+    pub bridge_system_id_extension: u12be,
+    #[construct_with(u8, u8, u8, u8, u8, u8)]
+    pub bridge_mac: MacAddr,
+    pub port_priority: u4,
+    pub port_number: u12be,
+    pub message_age: u16be,
+    pub max_age: u16be,
+    pub hello_time: u16be,
+    pub forward_delay: u16be,
+    pub version1_length: u8,
+    #[payload]
+    pub payload: Vec<u8>,
+}
+
+/// A Multiple Spanning Tree BPDU. The root and bridge identifiers are the CIST root and CIST
+/// regional root, and the root path cost is the CIST external root path cost. Times are in units
+/// of 1/256 seconds.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct MstBpdu {
+    pub protocol_id: u16be,
+    pub version: u8,
+    #[construct_with(u8)]
+    pub bpdu_type: BpduType,
+    #[construct_with(u8)]
+    pub flags: BpduFlags,
+    pub root_priority: u4,
+    pub root_system_id_extension: u12be,
+    #[construct_with(u8, u8, u8, u8, u8, u8)]
+    pub root_mac: MacAddr,
+    pub root_path_cost: u32be,
+    pub bridge_priority: u4,
+    pub bridge_system_id_extension: u12be,
+    #[construct_with(u8, u8, u8, u8, u8, u8)]
+    pub bridge_mac: MacAddr,
+    pub port_priority: u4,
+    pub port_number: u12be,
+    pub message_age: u16be,
+    pub max_age: u16be,
+    pub hello_time: u16be,
+    pub forward_delay: u16be,
+    pub version1_length: u8,
+    pub version3_length: u16be,
+    pub config_format_selector: u8,
+    #[length = "32"]
+    pub config_name: Vec<u8>,
+    pub config_revision: u16be,
+    #[length = "16"]
+    pub config_digest: Vec<u8>,
+    pub cist_internal_root_path_cost: u32be,
+    pub cist_bridge_priority: u4,
+    pub cist_bridge_system_id_extension: u12be,
+    #[construct_with(u8, u8, u8, u8, u8, u8)]
+    pub cist_bridge_mac: MacAddr,
+    pub cist_remaining_hops: u8,
+    #[length = "msti_configs_length(version3_length)"]
+    pub msti_configs: Vec<MstiConfig>,
+    #[payload]
+    pub payload: Vec<u8>,
+}
+
+/// The length of the MSTI configuration messages following the 64 bytes of CIST information
+/// counted by the version 3 length.
+#[inline]
+fn msti_configs_length(version3_length: u16) -> usize {
+    (version3_length as usize).saturating_sub(64)
+}
+
+/// An MSTI configuration message of an MST BPDU. The MSTI is identified by the system ID
+/// extension of the regional root.
+#[derive(Clone, Debug, PartialEq, Packet)]
+pub struct MstiConfig {
+    #[construct_with(u8)]
+    pub flags: BpduFlags,
+    pub regional_root_priority: u4,
+    pub regional_root_system_id_extension: u12be,
+    #[construct_with(u8, u8, u8, u8, u8, u8)]
+    pub regional_root_mac: MacAddr,
+    pub internal_root_path_cost: u32be,
+    pub bridge_priority: u4,
+    pub reserved1: u4,
+    pub port_priority: u4,
+    pub reserved2: u4,
+    pub remaining_hops: u8,
+    #[length = "0"]
+    #[payload]
+    pub payload: Vec<u8>,
+}
+
+macro_rules! bridge_ids {
+    ($($packet:ident),*) => {
+        $(
+            impl<'p> $packet<'p> {
+                pub fn root_id(&self) -> BridgeId {
+                    BridgeId {
+                        priority: self.get_root_priority(),
+                        system_id_extension: self.get_root_system_id_extension(),
+                        mac: self.get_root_mac(),
+                    }
+                }
+
+                pub fn bridge_id(&self) -> BridgeId {
+                    BridgeId {
+                        priority: self.get_bridge_priority(),
+                        system_id_extension: self.get_bridge_system_id_extension(),
+                        mac: self.get_bridge_mac(),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+bridge_ids!(ConfigBpduPacket, RstBpduPacket, MstBpduPacket);
+
+impl<'p> MstBpduPacket<'p> {
+    pub fn cist_bridge_id(&self) -> BridgeId {
+        BridgeId {
+            priority: self.get_cist_bridge_priority(),
+            system_id_extension: self.get_cist_bridge_system_id_extension(),
+            mac: self.get_cist_bridge_mac(),
+        }
+    }
+}
+
+impl<'p> MstiConfigPacket<'p> {
+    pub fn regional_root_id(&self) -> BridgeId {
+        BridgeId {
+            priority: self.get_regional_root_priority(),
+            system_id_extension: self.get_regional_root_system_id_extension(),
+            mac: self.get_regional_root_mac(),
+        }
+    }
+
+    /// The MST instance the message belongs to.
+    pub fn msti(&self) -> u12be {
+        self.get_regional_root_system_id_extension()
+    }
+}
+
+/// A BPDU, dispatched on its type and version.
+#[derive(Debug)]
+pub enum Bpdu<'a> {
+    Config(ConfigBpduPacket<'a>),
+    Tcn(TcnBpduPacket<'a>),
+    Rst(RstBpduPacket<'a>),
+    Mst(MstBpduPacket<'a>),
+}
+
+impl<'a> Bpdu<'a> {
+    /// Decodes a BPDU. An MSTP BPDU too short for the MST extension is decoded as RST, as
+    /// IEEE 802.1Q requires. Returns `None` for unknown types or if the BPDU is truncated.
+    pub fn new(data: &'a [u8]) -> Option<Self> {
+        let header = BpduHeaderPacket::new(data)?;
+        if header.get_protocol_id() != 0 {
+            return None;
+        }
+        Some(match header.get_bpdu_type() {
+            BpduTypes::Config => Self::Config(ConfigBpduPacket::new(data)?),
+            BpduTypes::Tcn => Self::Tcn(TcnBpduPacket::new(data)?),
+            BpduTypes::Rst => {
+                if header.get_version() >= MSTP_VERSION {
+                    if let Some(mst) = MstBpduPacket::new(data) {
+                        return Some(Self::Mst(mst));
+                    }
+                }
+                Self::Rst(RstBpduPacket::new(data)?)
+            }
+            _ => return None,
+        })
+    }
+
+    /// The flags, which TCN BPDUs don't have.
+    pub fn flags(&self) -> Option<BpduFlags> {
+        match self {
+            Self::Config(bpdu) => Some(bpdu.get_flags()),
+            Self::Tcn(_) => None,
+            Self::Rst(bpdu) => Some(bpdu.get_flags()),
+            Self::Mst(bpdu) => Some(bpdu.get_flags()),
+        }
+    }
+}
+
+impl<'p> LlcPacket<'p> {
+    /// The BPDU of a frame between `LlcSaps::Stp` addresses.
+    pub fn bpdu(&self) -> Option<Bpdu<'_>> {
+        if self.get_dsap().address() == LlcSaps::Stp && self.get_ssap().address() == LlcSaps::Stp {
+            Bpdu::new(self.payload())
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ethernet::EthernetPacket;
+
+    fn rst_bpdu(version: u8) -> Vec<u8> {
+        let mut bpdu = vec![0x00, 0x00, version, 0x02, 0x3c];
+        bpdu.extend_from_slice(&[0x80, 0x01, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        bpdu.extend_from_slice(&20000u32.to_be_bytes());
+        bpdu.extend_from_slice(&[0x90, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x02]);
+        bpdu.extend_from_slice(&[0x80, 0x05]);
+        for time in &[1u16, 20, 2, 15] {
+            bpdu.extend_from_slice(&(time * 256).to_be_bytes());
+        }
+        bpdu.push(0);
+        bpdu
+    }
+
+    #[test]
+    fn config_over_llc() {
+        let mut frame = vec![0x01, 0x80, 0xc2, 0x00, 0x00, 0x00, 0x02, 0, 0, 0, 0, 1];
+        frame.extend_from_slice(&[0x00, 0x26, 0x42, 0x42, 0x03]);
+        let mut bpdu = rst_bpdu(STP_VERSION);
+        bpdu[3] = BpduTypes::Config.0;
+        bpdu[4] = 0x81;
+        bpdu.pop();
+        frame.extend_from_slice(&bpdu);
+        frame.resize(60, 0);
+
+        let packet = EthernetPacket::new(&frame).unwrap();
+        let llc = packet.llc().unwrap();
+        let config = match llc.bpdu() {
+            Some(Bpdu::Config(config)) => config,
+            bpdu => panic!("unexpected {:?}", bpdu),
+        };
+        let flags = config.get_flags();
+        assert!(flags.topology_change());
+        assert!(flags.topology_change_ack());
+        assert_eq!(
+            config.root_id(),
+            BridgeId {
+                priority: 8,
+                system_id_extension: 1,
+                mac: MacAddr(0x00, 0x11, 0x22, 0x33, 0x44, 0x55),
+            }
+        );
+        assert_eq!(config.root_id().bridge_priority(), 32769);
+        assert_eq!(config.get_root_path_cost(), 20000);
+        assert_eq!(config.bridge_id().priority, 9);
+        assert_eq!(config.get_port_priority(), 8);
+        assert_eq!(config.get_port_number(), 5);
+        assert_eq!(config.get_max_age(), 20 * 256);
+        assert_eq!(config.get_forward_delay(), 15 * 256);
+    }
+
+    #[test]
+    fn tcn() {
+        let bpdu = Bpdu::new(&[0x00, 0x00, 0x00, 0x80]).unwrap();
+        assert!(matches!(bpdu, Bpdu::Tcn(_)));
+        assert_eq!(bpdu.flags(), None);
+        assert!(Bpdu::new(&[0x00, 0x00, 0x00, 0x42]).is_none());
+        assert!(Bpdu::new(&[0x00, 0x01, 0x00, 0x80]).is_none());
+    }
+
+    #[test]
+    fn rst() {
+        let data = rst_bpdu(RSTP_VERSION);
+        let rst = match Bpdu::new(&data).unwrap() {
+            Bpdu::Rst(rst) => rst,
+            bpdu => panic!("unexpected {:?}", bpdu),
+        };
+        let flags = rst.get_flags();
+        assert_eq!(flags.port_role(), PortRole::Designated);
+        assert!(!flags.proposal());
+        assert!(flags.learning());
+        assert!(flags.forwarding());
+        assert!(!flags.agreement());
+        assert!(Bpdu::new(&data[..35]).is_none());
+
+        // Too short for the MST extension.
+        assert!(matches!(
+            Bpdu::new(&rst_bpdu(MSTP_VERSION)),
+            Some(Bpdu::Rst(_))
+        ));
+    }
+
+    #[test]
+    fn mst() {
+        let mut data = rst_bpdu(MSTP_VERSION);
+        data.extend_from_slice(&(64u16 + 2 * 16).to_be_bytes());
+        data.push(0);
+        let mut name = b"region".to_vec();
+        name.resize(32, 0);
+        data.extend_from_slice(&name);
+        data.extend_from_slice(&[0x00, 0x01]);
+        data.extend_from_slice(&[0xac; 16]);
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        data.extend_from_slice(&[0x80, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x02]);
+        data.push(20);
+        for msti in 1..=2u8 {
+            data.extend_from_slice(&[0x7c, 0x80, msti, 0x02, 0x00, 0x00, 0x00, 0x00, 0x02]);
+            data.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x80, 0x80, 20]);
+        }
+
+        let mst = match Bpdu::new(&data).unwrap() {
+            Bpdu::Mst(mst) => mst,
+            bpdu => panic!("unexpected {:?}", bpdu),
+        };
+        assert_eq!(&mst.get_config_name()[..6], b"region");
+        assert_eq!(mst.get_config_revision(), 1);
+        assert_eq!(mst.get_config_digest(), [0xac; 16]);
+        assert_eq!(mst.cist_bridge_id().priority, 8);
+        assert_eq!(mst.get_cist_remaining_hops(), 20);
+
+        let mstis: Vec<_> = mst.get_msti_configs_iter().collect();
+        assert_eq!(mstis.len(), 2);
+        for (msti, config) in (1..).zip(&mstis) {
+            assert_eq!(config.msti(), msti);
+            assert_eq!(config.regional_root_id().priority, 8);
+            assert_eq!(config.get_flags().port_role(), PortRole::Designated);
+            assert!(config.get_flags().agreement());
+            assert_eq!(config.get_bridge_priority(), 8);
+            assert_eq!(config.get_port_priority(), 8);
+            assert_eq!(config.get_remaining_hops(), 20);
+        }
+    }
+}
@@ -51,7 +51,7 @@ pub mod TcpFlags {
 }
 
 /// Represents a TCP packet.
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Tcp {
     pub source: u16be,
     pub destination: u16be,
@@ -102,7 +102,7 @@ pub mod TcpOptionNumbers {
 }
 
 /// A TCP option.
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct TcpOption {
     #[construct_with(u8)]
     number: TcpOptionNumber,
@@ -13,7 +13,7 @@ use crate::{types::*, util, Packet};
 use std::net::{Ipv4Addr, Ipv6Addr};
 
 /// Represents a UDP Packet.
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Udp {
     pub source: u16be,
     pub destination: u16be,
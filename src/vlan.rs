@@ -1,6 +1,6 @@
 //! A VLAN packet abstraction.
 
-use crate::ethernet::EtherType;
+use crate::ethernet::{EtherType, EtherTypes, EthernetPacket};
 use crate::{types::*, Packet, PrimitiveValues};
 
 /// Represents an IEEE 802.1p class of a service.
@@ -54,7 +54,7 @@ pub mod ClassesOfService {
 }
 
 /// Represents a VLAN-tagged packet.
-#[derive(Debug, Packet)]
+#[derive(Clone, Debug, PartialEq, Packet)]
 pub struct Vlan {
     #[construct_with(u3)]
     pub priority_code_point: ClassOfService,
@@ -66,6 +66,121 @@ pub struct Vlan {
     pub payload: Vec<u8>,
 }
 
+/// Offset of the first ethertype or tag protocol identifier in an Ethernet frame.
+const TAG_OFFSET: usize = 12;
+
+/// Length of an 802.1Q or 802.1ad tag.
+const TAG_LEN: usize = 4;
+
+/// An 802.1Q or 802.1ad tag of an Ethernet frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VlanTag {
+    /// The tag protocol identifier, `EtherTypes::Vlan` for customer tags and
+    /// `EtherTypes::PBridge` or `EtherTypes::QinQ` for service tags.
+    pub tpid: EtherType,
+    pub pcp: ClassOfService,
+    pub dei: u1,
+    pub vid: u12be,
+}
+
+impl VlanTag {
+    /// Whether `ethertype` identifies a tag.
+    pub fn is_tpid(ethertype: EtherType) -> bool {
+        matches!(
+            ethertype,
+            EtherTypes::Vlan | EtherTypes::PBridge | EtherTypes::QinQ
+        )
+    }
+
+    /// Parses the tag at the start of `data`, if it starts with a tag protocol identifier.
+    fn parse(data: &[u8]) -> Option<Self> {
+        let tag = data.get(..TAG_LEN)?;
+        let tpid = EtherType(u16::from_be_bytes([tag[0], tag[1]]));
+        if !Self::is_tpid(tpid) {
+            return None;
+        }
+        let tci = u16::from_be_bytes([tag[2], tag[3]]);
+        Some(Self {
+            tpid,
+            pcp: ClassOfService((tci >> 13) as u3),
+            dei: (tci >> 12 & 1) as u1,
+            vid: tci & 0xfff,
+        })
+    }
+
+    fn to_bytes(self) -> [u8; TAG_LEN] {
+        let tci =
+            u16::from(self.pcp.0 & 0x7) << 13 | u16::from(self.dei & 1) << 12 | self.vid & 0xfff;
+        let [t0, t1] = self.tpid.0.to_be_bytes();
+        let [c0, c1] = tci.to_be_bytes();
+        [t0, t1, c0, c1]
+    }
+}
+
+/// Iterator over the VLAN tags of an Ethernet frame, outermost first.
+#[derive(Clone, Debug)]
+pub struct VlanTags<'a> {
+    frame: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> VlanTags<'a> {
+    /// The ethertype following the tags returned so far, and the offset of its payload in the
+    /// frame. Once the iterator is exhausted, this is the ethertype of the encapsulated protocol.
+    pub fn ethertype(&self) -> (EtherType, usize) {
+        let ethertype = self
+            .frame
+            .get(self.offset..self.offset + 2)
+            .map_or(0, |b| u16::from_be_bytes([b[0], b[1]]));
+        (EtherType(ethertype), self.offset + 2)
+    }
+}
+
+impl<'a> Iterator for VlanTags<'a> {
+    type Item = VlanTag;
+
+    fn next(&mut self) -> Option<VlanTag> {
+        let tag = VlanTag::parse(self.frame.get(self.offset..)?)?;
+        self.offset += TAG_LEN;
+        Some(tag)
+    }
+}
+
+impl<'p> EthernetPacket<'p> {
+    /// The 802.1Q and 802.1ad tags of the frame, outermost first.
+    pub fn vlan_tags(&self) -> VlanTags<'_> {
+        VlanTags {
+            frame: self.packet(),
+            offset: TAG_OFFSET,
+        }
+    }
+
+    /// The ethertype following all VLAN tags, and the offset of its payload in the frame.
+    pub fn inner_ethertype(&self) -> (EtherType, usize) {
+        let mut tags = self.vlan_tags();
+        tags.by_ref().for_each(drop);
+        tags.ethertype()
+    }
+}
+
+/// Inserts `tag` as the outermost tag of an Ethernet frame.
+///
+/// Returns `None`, leaving the frame unchanged, if it is too short to contain the addresses.
+pub fn push_vlan_tag(frame: &mut Vec<u8>, tag: VlanTag) -> Option<()> {
+    if frame.len() < TAG_OFFSET {
+        return None;
+    }
+    frame.splice(TAG_OFFSET..TAG_OFFSET, tag.to_bytes());
+    Some(())
+}
+
+/// Removes and returns the outermost tag of an Ethernet frame, if it has one.
+pub fn pop_vlan_tag(frame: &mut Vec<u8>) -> Option<VlanTag> {
+    let tag = VlanTag::parse(frame.get(TAG_OFFSET..)?)?;
+    frame.drain(TAG_OFFSET..TAG_OFFSET + TAG_LEN);
+    Some(tag)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,4 +212,75 @@ mod tests {
         ]; // Second byte of ethertype
         assert_eq!(&ref_packet[..], &packet[..]);
     }
+
+    #[test]
+    fn qinq_tags() {
+        let frame = [
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, /* destination */
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, /* source */
+            0x88, 0xa8, 0xa0, 0x64, /* service tag, PCP 5, VID 100 */
+            0x81, 0x00, 0x10, 0x0a, /* customer tag, DEI, VID 10 */
+            0x08, 0x00, /* ethertype */
+            0x45, 0x00, /* payload */
+        ];
+        let packet = EthernetPacket::new(&frame).unwrap();
+        assert_eq!(packet.get_ethertype(), EtherTypes::PBridge);
+        let tags: Vec<_> = packet.vlan_tags().collect();
+        assert_eq!(
+            tags,
+            vec![
+                VlanTag {
+                    tpid: EtherTypes::PBridge,
+                    pcp: ClassesOfService::VO,
+                    dei: 0,
+                    vid: 100,
+                },
+                VlanTag {
+                    tpid: EtherTypes::Vlan,
+                    pcp: ClassesOfService::BE,
+                    dei: 1,
+                    vid: 10,
+                },
+            ]
+        );
+        assert_eq!(packet.inner_ethertype(), (EtherTypes::Ipv4, 22));
+
+        let mut untagged = frame[..12].to_vec();
+        untagged.extend_from_slice(&frame[20..]);
+        let untagged = EthernetPacket::new(&untagged).unwrap();
+        assert_eq!(untagged.vlan_tags().count(), 0);
+        assert_eq!(untagged.inner_ethertype(), (EtherTypes::Ipv4, 14));
+    }
+
+    #[test]
+    fn push_and_pop_tags() {
+        let mut frame = vec![0; 12];
+        frame.extend_from_slice(&[0x86, 0xdd, 0x60]);
+        let customer = VlanTag {
+            tpid: EtherTypes::Vlan,
+            pcp: ClassesOfService::CA,
+            dei: 0,
+            vid: 0xfff,
+        };
+        let service = VlanTag {
+            tpid: EtherTypes::QinQ,
+            pcp: ClassesOfService::BE,
+            dei: 1,
+            vid: 7,
+        };
+        push_vlan_tag(&mut frame, customer).unwrap();
+        push_vlan_tag(&mut frame, service).unwrap();
+        assert_eq!(
+            &frame[12..],
+            &[0x91, 0x00, 0x10, 0x07, 0x81, 0x00, 0x6f, 0xff, 0x86, 0xdd, 0x60]
+        );
+        let packet = EthernetPacket::new(&frame).unwrap();
+        assert_eq!(packet.inner_ethertype(), (EtherTypes::Ipv6, 22));
+
+        assert_eq!(pop_vlan_tag(&mut frame), Some(service));
+        assert_eq!(pop_vlan_tag(&mut frame), Some(customer));
+        assert_eq!(pop_vlan_tag(&mut frame), None);
+        assert_eq!(frame.len(), 15);
+        assert!(push_vlan_tag(&mut vec![0; 11], customer).is_none());
+    }
 }